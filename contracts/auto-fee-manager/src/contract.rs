@@ -7,13 +7,19 @@ use crate::error::ContractError;
 use crate::handlers::*;
 use crate::helpers::validate_address;
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
-use crate::state::{DEPOSIT_ACCEPTED_DENOMS, CONFIG, Config};
+use crate::state::{DEPOSIT_ACCEPTED_DENOMS, CONFIG, Config, ROLES};
+use auto_access_control::{
+    execute::execute_access_control,
+    msg::{AccessControlMsg, AccessControlQuery},
+    query::query_access_control,
+    state::{may_load_ownership, save_owner},
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, crate::CONTRACT_NAME, crate::CONTRACT_VERSION)?;
@@ -50,6 +56,7 @@ pub fn instantiate(
         creator_distribution_fee: msg.creator_distribution_fee,
     };
     CONFIG.save(deps.storage, &config)?;
+    save_owner(deps.storage, &info.sender)?;
     Ok(Response::new())
 }
 
@@ -69,6 +76,7 @@ pub fn execute(
         ExecuteMsg::ChargeFeesFromMessageCoins {fees,} => 
             handle_charge_fees_from_message_coins(deps, env, info, fees),
         ExecuteMsg::ClaimCreatorFees {} => handle_claim_creator_fees(deps, info),
+        ExecuteMsg::ClaimExecutorFees {} => handle_claim_executor_fees(deps, info),
         ExecuteMsg::DistributeNonCreatorFees {} => {
             handle_distribute_non_creator_fees(deps, env, info)
         }
@@ -81,9 +89,19 @@ pub fn execute(
         ExecuteMsg::DisableCreatorFeeDistribution {} => {
             handle_disable_creator_fee_distribution(deps, info)
         }
+        ExecuteMsg::TransferOwnership { new_owner } => access_control(deps, info, AccessControlMsg::TransferOwnership { new_owner }),
+        ExecuteMsg::AcceptOwnership {} => access_control(deps, info, AccessControlMsg::AcceptOwnership {}),
+        ExecuteMsg::CancelOwnershipTransfer {} => access_control(deps, info, AccessControlMsg::CancelOwnershipTransfer {}),
+        ExecuteMsg::GrantRole { role, address } => access_control(deps, info, AccessControlMsg::GrantRole { role, address }),
+        ExecuteMsg::RevokeRole { role, address } => access_control(deps, info, AccessControlMsg::RevokeRole { role, address }),
     }
 }
 
+fn access_control(deps: DepsMut, info: MessageInfo, msg: AccessControlMsg) -> Result<Response, ContractError> {
+    cw_utils::nonpayable(&info)?;
+    Ok(execute_access_control(deps, info, msg, ROLES, "autorujira-fee-manager")?)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -99,6 +117,10 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             let result = get_creator_fees(deps, creator)?;
             cosmwasm_std::to_json_binary(&result)
         }
+        QueryMsg::GetExecutorFees { executor } => {
+            let result = get_executor_fees(deps, executor)?;
+            cosmwasm_std::to_json_binary(&result)
+        }
         QueryMsg::GetNonCreatorFees {} => {
             let result = get_non_creator_fees(deps)?;
             cosmwasm_std::to_json_binary(&result)
@@ -111,6 +133,11 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             let result = get_subscribed_creators(deps)?;
             cosmwasm_std::to_json_binary(&result)
         }
+        QueryMsg::Ownership {} => query_access_control(deps, AccessControlQuery::Ownership {}),
+        QueryMsg::HasRole { role, address } => query_access_control(deps, AccessControlQuery::HasRole { role, address }),
+        QueryMsg::RoleMembers { role, start_after, limit } => {
+            query_access_control(deps, AccessControlQuery::RoleMembers { role, start_after, limit })
+        }
         QueryMsg::GetConfig {} => {
             let config = CONFIG.load(deps.storage)?;
             // Load all accepted denoms
@@ -182,11 +209,24 @@ pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, Contract
                 cosmwasm_std::Event::new("autorujira-fee-manager/sudo_set_creator_distribution_fee")
             ))
         }
+        SudoMsg::SetOwner { address } => {
+            validate_address(&deps, address.as_str(), "owner")?;
+            save_owner(deps.storage, &address)?;
+            Ok(Response::new().add_event(
+                cosmwasm_std::Event::new("autorujira-fee-manager/sudo_set_owner")
+            ))
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Contracts instantiated before access control have no owner yet
+    if let (None, Some(owner)) = (may_load_ownership(deps.storage)?, msg.owner) {
+        validate_address(&deps, owner.as_str(), "owner")?;
+        save_owner(deps.storage, &owner)?;
+    }
+
     // Update contract version
     set_contract_version(deps.storage, crate::CONTRACT_NAME, crate::CONTRACT_VERSION)?;
     
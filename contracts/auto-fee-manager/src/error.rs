@@ -1,3 +1,4 @@
+use auto_access_control::AccessControlError;
 use cosmwasm_std::{StdError, Uint128};
 use cw_utils::PaymentError;
 use thiserror::Error;
@@ -34,6 +35,9 @@ pub enum ContractError {
     #[error("No creator fees available to claim")]
     NoCreatorFeesToClaim {},
 
+    #[error("No executor fees available to claim")]
+    NoExecutorFeesToClaim {},
+
     #[error("No execution fees available to distribute")]
     NoExecutionFeesToDistribute {},
 
@@ -49,6 +53,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    AccessControl(#[from] AccessControlError),
+
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
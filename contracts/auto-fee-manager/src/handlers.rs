@@ -2,7 +2,7 @@ use crate::events::{balance_below_threshold, deposit_completed};
 use crate::helpers::{verify_crank, verify_workflow_manager};
 use crate::msg::{AcceptedDenomValue, Fee, FeeType};
 use crate::state::{
-    CONFIG, USER_BALANCES, CREATOR_FEES, EXECUTION_FEES, DISTRIBUTION_FEES, DEPOSIT_ACCEPTED_DENOMS,
+    CONFIG, USER_BALANCES, CREATOR_FEES, EXECUTOR_FEES, EXECUTION_FEES, DISTRIBUTION_FEES, DEPOSIT_ACCEPTED_DENOMS,
     SUBSCRIBED_CREATORS,
 };
 use crate::{error::ContractError, msg::UserFees};
@@ -151,6 +151,7 @@ pub fn handle_charge_fees_from_user_balance(
     // 1. Single iteration - accumulate by (user, denom, fee_type)
     // TODO: This could be done offchain
     let mut user_execution_totals: HashMap<(Addr, String), Uint128> = HashMap::new();
+    let mut user_executor_totals: HashMap<(Addr, String), Uint128> = HashMap::new();
     let mut user_creator_totals: HashMap<(Addr, String), Uint128> = HashMap::new();
     let mut creator_fees_totals: HashMap<(Addr, String), Uint128> = HashMap::new();
     
@@ -169,6 +170,11 @@ pub fn handle_charge_fees_from_user_balance(
                         .entry((user_fees.user.clone(), fee.denom.clone()))
                         .or_insert(Uint128::zero()) += fee.amount;
                 }
+                FeeType::Executor { .. } => {
+                    *user_executor_totals
+                        .entry((user_fees.user.clone(), fee.denom.clone()))
+                        .or_insert(Uint128::zero()) += fee.amount;
+                }
                 FeeType::Creator { creator_address } => {
                     *user_creator_totals
                         .entry((user_fees.user.clone(), fee.denom.clone()))
@@ -184,6 +190,7 @@ pub fn handle_charge_fees_from_user_balance(
     // 2. Process each user/denom with partial collection logic
     let mut all_users_with_fees: HashSet<(Addr, String)> = HashSet::new();
     all_users_with_fees.extend(user_execution_totals.keys().cloned());
+    all_users_with_fees.extend(user_executor_totals.keys().cloned());
     all_users_with_fees.extend(user_creator_totals.keys().cloned());
     
     for (user, denom) in all_users_with_fees {
@@ -195,6 +202,9 @@ pub fn handle_charge_fees_from_user_balance(
             .get(&(user.clone(), denom.clone()))
             .unwrap_or(&Uint128::zero())
             .clone();
+        let executor_total = *user_executor_totals
+            .get(&(user.clone(), denom.clone()))
+            .unwrap_or(&Uint128::zero());
         let creator_total = user_creator_totals
             .get(&(user.clone(), denom.clone()))
             .unwrap_or(&Uint128::zero())
@@ -208,14 +218,20 @@ pub fn handle_charge_fees_from_user_balance(
             0
         };
         let remaining_after_execution = available - execution_chargeable as i128;
-        let creator_chargeable = if remaining_after_execution > 0 {
-            creator_total.u128().min(remaining_after_execution as u128)
+        let executor_chargeable = if remaining_after_execution > 0 {
+            executor_total.u128().min(remaining_after_execution as u128)
+        } else {
+            0
+        };
+        let remaining_after_executor = remaining_after_execution - executor_chargeable as i128;
+        let creator_chargeable = if remaining_after_executor > 0 {
+            creator_total.u128().min(remaining_after_executor as u128)
         } else {
             0
         };
         
         // Update balance
-        let total_fees_i128 = (execution_total.u128() + creator_total.u128()) as i128;
+        let total_fees_i128 = (execution_total.u128() + executor_total.u128() + creator_total.u128()) as i128;
         let new_balance = current_balance - total_fees_i128;
         USER_BALANCES.save(deps.storage, (user.clone(), denom.as_str()), &new_balance)?;
         
@@ -235,6 +251,30 @@ pub fn handle_charge_fees_from_user_balance(
             EXECUTION_FEES.save(deps.storage, denom.as_str(), &new_execution_fees)?;
         }
         
+        // Update executor_fees only with what could actually be charged, in batch order
+        if executor_chargeable > 0 {
+            let mut remaining = executor_chargeable;
+            for user_fees in batch.iter().filter(|user_fees| user_fees.user == user) {
+                for fee in user_fees.fees.iter().filter(|fee| fee.denom == denom) {
+                    if let FeeType::Executor { executor_address } = &fee.fee_type {
+                        let credited = fee.amount.u128().min(remaining);
+                        if credited == 0 {
+                            continue;
+                        }
+                        let current_executor_fees = EXECUTOR_FEES
+                            .may_load(deps.storage, (executor_address, denom.as_str()))?
+                            .unwrap_or(Uint128::zero());
+                        EXECUTOR_FEES.save(
+                            deps.storage,
+                            (executor_address, denom.as_str()),
+                            &(current_executor_fees + Uint128::from(credited)),
+                        )?;
+                        remaining -= credited;
+                    }
+                }
+            }
+        }
+        
         // Update creator_fees only with what could actually be charged
         if creator_chargeable > 0 {
             // Find the creator address for this user/denom combination
@@ -285,6 +325,7 @@ pub fn handle_charge_fees_from_message_coins(
     // TODO: This validations could be removed as we trust the workflow manager
     let mut expected_funds: HashMap<String, Uint128> = HashMap::new();
     let mut creator_fees_accum: HashMap<(Addr, String), Uint128> = HashMap::new();
+    let mut executor_fees_accum: HashMap<(Addr, String), Uint128> = HashMap::new();
     let mut execution_fees_accum: HashMap<String, Uint128> = HashMap::new();
     
     for fee in &fees {
@@ -299,6 +340,12 @@ pub fn handle_charge_fees_from_message_coins(
                     .entry((creator_address.clone(), fee.denom.clone()))
                     .or_insert(Uint128::zero()) += fee.amount;
             }
+            FeeType::Executor { executor_address } => {
+                // Accumulate executor rewards
+                *executor_fees_accum
+                    .entry((executor_address.clone(), fee.denom.clone()))
+                    .or_insert(Uint128::zero()) += fee.amount;
+            }
             FeeType::Execution => {
                 // Accumulate execution fees
                 *execution_fees_accum
@@ -346,6 +393,15 @@ pub fn handle_charge_fees_from_message_coins(
         CREATOR_FEES.save(deps.storage, (creator, denom.as_str()), &new_total)?;
     }
 
+    // Update executor fees storage
+    for ((executor, denom), total_fees) in &executor_fees_accum {
+        let current = EXECUTOR_FEES
+            .may_load(deps.storage, (executor, denom.as_str()))?
+            .unwrap_or(Uint128::zero());
+        let new_total = current + *total_fees;
+        EXECUTOR_FEES.save(deps.storage, (executor, denom.as_str()), &new_total)?;
+    }
+
     // Update execution fees storage
     for (denom, total_fees) in &execution_fees_accum {
         let current = EXECUTION_FEES
@@ -422,6 +478,62 @@ pub fn handle_claim_creator_fees(
     Ok(response)
 }
 
+pub fn handle_claim_executor_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let executor = &info.sender;
+    let mut total_claimed = Vec::new();
+    let mut bank_messages = Vec::new();
+
+    // Get all rewards for this executor using prefix
+    let executor_fees: Vec<(String, Uint128)> = EXECUTOR_FEES
+        .prefix(executor)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .filter_map(|result| {
+            result.ok().and_then(|(denom, amount)| {
+                if amount > Uint128::zero() {
+                    Some((denom.to_string(), amount))
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    for (denom, amount) in executor_fees {
+        total_claimed.push(Coin {
+            denom: denom.clone(),
+            amount,
+        });
+
+        bank_messages.push(BankMsg::Send {
+            to_address: executor.to_string(),
+            amount: vec![Coin { denom, amount }],
+        });
+    }
+
+    if total_claimed.is_empty() {
+        return Err(ContractError::NoExecutorFeesToClaim {});
+    }
+
+    for coin in &total_claimed {
+        EXECUTOR_FEES.remove(deps.storage, (executor, coin.denom.as_str()));
+    }
+
+    let response = Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-fee-manager/claim_executor_fees")
+                .add_attribute("executor", executor.to_string())
+                .add_attribute("total_claimed", format!("{:?}", total_claimed))
+        )
+        .add_messages(bank_messages);
+
+    Ok(response)
+}
+
 pub fn handle_distribute_non_creator_fees(
     deps: DepsMut,
     _env: Env,
@@ -726,6 +838,27 @@ pub fn get_creator_fees(deps: Deps, creator: Addr) -> StdResult<crate::msg::Crea
     })
 }
 
+pub fn get_executor_fees(deps: Deps, executor: Addr) -> StdResult<crate::msg::ExecutorFeesResponse> {
+    let fees = EXECUTOR_FEES
+        .prefix(&executor)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .filter_map(|result| {
+            result.ok().and_then(|(denom, amount)| {
+                if amount > Uint128::zero() {
+                    Some(crate::msg::FeeBalance {
+                        denom: denom.to_string(),
+                        balance: amount,
+                    })
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    Ok(crate::msg::ExecutorFeesResponse { executor, fees })
+}
+
 pub fn get_non_creator_fees(deps: Deps) -> StdResult<crate::msg::NonCreatorFeesResponse> {
     let execution_fees = EXECUTION_FEES
         .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
@@ -5,7 +5,8 @@ use cosmwasm_std::{to_json_binary, Addr, CosmosMsg, StdResult, WasmMsg, DepsMut,
 
 use crate::msg::ExecuteMsg;
 use crate::error::ContractError;
-use crate::state::{CONFIG, Config};
+use crate::state::{CONFIG, CRANK_ROLE, Config};
+use auto_access_control::state::has_role;
 
 /// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
 /// for working with this.
@@ -46,7 +47,7 @@ pub fn validate_address(deps: &DepsMut, address: &str, field_name: &str) -> Resu
 /// Helper function to check if an address is authorized
 pub fn is_crank(deps: Deps, address: &Addr) -> Result<bool, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
-    Ok(config.crank_authorized_address == *address)
+    Ok(config.crank_authorized_address == *address || has_role(deps.storage, CRANK_ROLE, address))
 }
 
 /// Helper function to check if an address is the workflow manager
@@ -2,6 +2,8 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Uint128};
 use std::collections::HashMap;
 
+use auto_access_control::msg::{OwnershipResponse, RoleMembersResponse};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     // Denoms that are accepted for deposits
@@ -42,10 +44,17 @@ pub enum ExecuteMsg {
         fees: Vec<Fee>,
     },
     ClaimCreatorFees {},
+    ClaimExecutorFees {},
     DistributeCreatorFees {},
     DistributeNonCreatorFees {},
     EnableCreatorFeeDistribution {},
     DisableCreatorFeeDistribution {},
+    // Access control, see auto-access-control. The only role is "crank"
+    TransferOwnership { new_owner: String },
+    AcceptOwnership {},
+    CancelOwnershipTransfer {},
+    GrantRole { role: String, address: String },
+    RevokeRole { role: String, address: String },
 }
 
 #[cw_serde]
@@ -55,6 +64,7 @@ pub enum SudoMsg {
     SetExecutionFeesDestinationAddress { address: Addr },
     SetDistributionFeesDestinationAddress { address: Addr },
     SetCreatorDistributionFee { fee: Uint128 },
+    SetOwner { address: Addr },
 }
 
 #[cw_serde]
@@ -66,12 +76,20 @@ pub enum QueryMsg {
     GetUserBalances { user: Addr },
     #[returns(CreatorFeesResponse)]
     GetCreatorFees { creator: Addr },
+    #[returns(ExecutorFeesResponse)]
+    GetExecutorFees { executor: Addr },
     #[returns(NonCreatorFeesResponse)]
     GetNonCreatorFees {},
     #[returns(bool)]
     IsCreatorSubscribed { creator: Addr },
     #[returns(SubscribedCreatorsResponse)]
     GetSubscribedCreators {},
+    #[returns(OwnershipResponse)]
+    Ownership {},
+    #[returns(bool)]
+    HasRole { role: String, address: String },
+    #[returns(RoleMembersResponse)]
+    RoleMembers { role: String, start_after: Option<String>, limit: Option<u32> },
     #[returns(InstantiateMsg)]
     GetConfig {},
 }
@@ -107,11 +125,15 @@ pub struct Fee {
 pub enum FeeType {
     Execution,
     Creator { creator_address: Addr },
+    // Share of the execution fee rewarded to the bonded executor that performed the actions
+    Executor { executor_address: Addr },
 }
 
 #[cw_serde]
 pub struct MigrateMsg {
-    // Empty for now, can be extended in future migrations
+    // Owner for contracts instantiated before access control, ignored once an owner is set
+    #[serde(default)]
+    pub owner: Option<Addr>,
 }
 
 #[cw_serde]
@@ -120,6 +142,12 @@ pub struct CreatorFeesResponse {
     pub fees: Vec<FeeBalance>,
 }
 
+#[cw_serde]
+pub struct ExecutorFeesResponse {
+    pub executor: Addr,
+    pub fees: Vec<FeeBalance>,
+}
+
 #[cw_serde]
 pub struct NonCreatorFeesResponse {
     pub execution_fees: Vec<FeeBalance>,
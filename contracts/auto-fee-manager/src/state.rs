@@ -15,12 +15,19 @@ pub struct Config {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+// Addresses that can crank fee distribution besides the configured crank_authorized_address
+pub const CRANK_ROLE: &str = "crank";
+pub const ROLES: &[&str] = &[CRANK_ROLE];
+
 // user address → denom → balance (can be negative)
 pub const USER_BALANCES: Map<(Addr, &str), i128> = Map::new("user_balances");
 
 // user address → denom → creator fee balance
 pub const CREATOR_FEES: Map<(&Addr, &str), Uint128> = Map::new("creator_fees");
 
+// executor address → denom → executor reward balance
+pub const EXECUTOR_FEES: Map<(&Addr, &str), Uint128> = Map::new("executor_fees");
+
 // denom → execution fee balance
 pub const EXECUTION_FEES: Map<&str, Uint128> = Map::new("execution_fees");
 
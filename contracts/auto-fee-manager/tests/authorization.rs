@@ -520,4 +520,177 @@ fn test_get_user_balances() {
     assert_eq!(uusdc_balance, Some(500));
     assert_eq!(uatom_balance, Some(-200));
     assert_eq!(uosmo_balance, Some(0));
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_owner_grants_crank_role() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let api = deps.api;
+    let admin_address = api.addr_make("admin");
+    let accepted_denoms: HashMap<String, AcceptedDenomValue> = vec![(
+        "uusdc".to_string(),
+        AcceptedDenomValue {
+            max_debt: Uint128::from(1000u128),
+            min_balance_threshold: Uint128::from(100u128),
+        }
+    )].into_iter().collect();
+    instantiate_contract(
+        deps.as_mut(),
+        env.clone(),
+        admin_address.clone(),
+        accepted_denoms,
+        api.addr_make("execution_destination"),
+        api.addr_make("distribution_destination"),
+        api.addr_make("crank_authorized"),
+        api.addr_make("workflow_manager"),
+        Uint128::from(5u128),
+    ).unwrap();
+    auto_fee_manager::state::EXECUTION_FEES.save(deps.as_mut().storage, "uusdc", &Uint128::from(100u128)).unwrap();
+
+    let second_crank_address = api.addr_make("second_crank");
+    let grant_msg = auto_fee_manager::msg::ExecuteMsg::GrantRole {
+        role: "crank".to_string(),
+        address: second_crank_address.to_string(),
+    };
+
+    // Only the owner can grant roles
+    let result = auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&second_crank_address, &[]), grant_msg.clone());
+    assert!(matches!(result, Err(ContractError::AccessControl(auto_access_control::AccessControlError::Unauthorized {}))));
+
+    let response = auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&admin_address, &[]), grant_msg).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-fee-manager/grant_role");
+
+    execute_distribute_non_creator_fees(deps.as_mut(), env, second_crank_address).unwrap();
+}
+
+fn instantiate_with_owner(deps: cosmwasm_std::DepsMut, env: cosmwasm_std::Env, owner: cosmwasm_std::Addr) {
+    let api = cosmwasm_std::testing::MockApi::default();
+    let accepted_denoms: HashMap<String, AcceptedDenomValue> = vec![(
+        "uusdc".to_string(),
+        AcceptedDenomValue {
+            max_debt: Uint128::from(1000u128),
+            min_balance_threshold: Uint128::from(100u128),
+        }
+    )].into_iter().collect();
+    instantiate_contract(
+        deps,
+        env,
+        owner,
+        accepted_denoms,
+        api.addr_make("execution_destination"),
+        api.addr_make("distribution_destination"),
+        api.addr_make("crank_authorized"),
+        api.addr_make("workflow_manager"),
+        Uint128::from(5u128),
+    ).unwrap();
+}
+
+fn query_owner(deps: cosmwasm_std::Deps, env: cosmwasm_std::Env) -> auto_access_control::msg::OwnershipResponse {
+    let response = auto_fee_manager::contract::query(deps, env, auto_fee_manager::msg::QueryMsg::Ownership {}).unwrap();
+    cosmwasm_std::from_json(response).unwrap()
+}
+
+#[test]
+fn test_revoked_crank_role_is_refused() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let api = deps.api;
+    let admin_address = api.addr_make("admin");
+    instantiate_with_owner(deps.as_mut(), env.clone(), admin_address.clone());
+
+    let crank_address = api.addr_make("granted_crank");
+    auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&admin_address, &[]), auto_fee_manager::msg::ExecuteMsg::GrantRole {
+        role: "crank".to_string(),
+        address: crank_address.to_string(),
+    }).unwrap();
+
+    // The granted address passes the crank check and only fails on the empty fee pool
+    let result = execute_distribute_creator_fees(deps.as_mut(), env.clone(), crank_address.clone());
+    assert!(matches!(result, Err(ContractError::NoCreatorFeesToDistribute {})));
+
+    let response = auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&admin_address, &[]), auto_fee_manager::msg::ExecuteMsg::RevokeRole {
+        role: "crank".to_string(),
+        address: crank_address.to_string(),
+    }).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-fee-manager/revoke_role");
+
+    let result = execute_distribute_creator_fees(deps.as_mut(), env, crank_address.clone());
+    match result {
+        Err(ContractError::NotAuthorized { address }) => assert_eq!(address, crank_address.to_string()),
+        _ => panic!("Expected NotAuthorized error"),
+    }
+}
+
+#[test]
+fn test_ownership_transfer_takes_two_steps() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let api = deps.api;
+    let admin_address = api.addr_make("admin");
+    let new_owner_address = api.addr_make("new_owner");
+    instantiate_with_owner(deps.as_mut(), env.clone(), admin_address.clone());
+
+    auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&admin_address, &[]), auto_fee_manager::msg::ExecuteMsg::TransferOwnership {
+        new_owner: new_owner_address.to_string(),
+    }).unwrap();
+    let ownership = query_owner(deps.as_ref(), env.clone());
+    assert_eq!(ownership.owner, admin_address);
+    assert_eq!(ownership.pending_owner, Some(new_owner_address.clone()));
+
+    // Only the pending owner can accept
+    let result = auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&api.addr_make("other"), &[]), auto_fee_manager::msg::ExecuteMsg::AcceptOwnership {});
+    assert!(matches!(result, Err(ContractError::AccessControl(auto_access_control::AccessControlError::Unauthorized {}))));
+
+    auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&new_owner_address, &[]), auto_fee_manager::msg::ExecuteMsg::AcceptOwnership {}).unwrap();
+    let ownership = query_owner(deps.as_ref(), env.clone());
+    assert_eq!(ownership.owner, new_owner_address);
+    assert_eq!(ownership.pending_owner, None);
+
+    // The previous owner can no longer grant roles
+    let result = auto_fee_manager::contract::execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(&admin_address, &[]), auto_fee_manager::msg::ExecuteMsg::GrantRole {
+        role: "crank".to_string(),
+        address: admin_address.to_string(),
+    });
+    assert!(matches!(result, Err(ContractError::AccessControl(auto_access_control::AccessControlError::Unauthorized {}))));
+}
+
+#[test]
+fn test_sudo_set_owner() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let api = deps.api;
+    let admin_address = api.addr_make("admin");
+    let new_owner_address = api.addr_make("new_owner");
+    instantiate_with_owner(deps.as_mut(), env.clone(), admin_address.clone());
+
+    // A pending transfer is dropped when governance sets the owner
+    auto_fee_manager::contract::execute(deps.as_mut(), env.clone(), cosmwasm_std::testing::message_info(&admin_address, &[]), auto_fee_manager::msg::ExecuteMsg::TransferOwnership {
+        new_owner: api.addr_make("pending").to_string(),
+    }).unwrap();
+
+    let response = auto_fee_manager::contract::sudo(deps.as_mut(), env.clone(), auto_fee_manager::msg::SudoMsg::SetOwner { address: new_owner_address.clone() }).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-fee-manager/sudo_set_owner");
+
+    let ownership = query_owner(deps.as_ref(), env);
+    assert_eq!(ownership.owner, new_owner_address);
+    assert_eq!(ownership.pending_owner, None);
+}
+
+#[test]
+fn test_migrate_sets_the_owner_only_when_missing() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let api = deps.api;
+    let admin_address = api.addr_make("admin");
+    let migrated_owner_address = api.addr_make("migrated_owner");
+    instantiate_with_owner(deps.as_mut(), env.clone(), admin_address.clone());
+
+    // An existing owner is kept
+    auto_fee_manager::contract::migrate(deps.as_mut(), env.clone(), auto_fee_manager::msg::MigrateMsg { owner: Some(migrated_owner_address.clone()) }).unwrap();
+    assert_eq!(query_owner(deps.as_ref(), env.clone()).owner, admin_address);
+
+    // Contracts instantiated before access control get the migration owner
+    auto_access_control::state::OWNERSHIP.remove(deps.as_mut().storage);
+    auto_fee_manager::contract::migrate(deps.as_mut(), env.clone(), auto_fee_manager::msg::MigrateMsg { owner: Some(migrated_owner_address.clone()) }).unwrap();
+    assert_eq!(query_owner(deps.as_ref(), env).owner, migrated_owner_address);
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use auto_fee_manager::handlers::get_executor_fees;
+use auto_fee_manager::msg::{AcceptedDenomValue, Fee, FeeType, UserFees};
+use auto_fee_manager::state::{EXECUTION_FEES, USER_BALANCES};
+use auto_fee_manager::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{Addr, BankMsg, CosmosMsg, Empty, Env, OwnedDeps, Uint128};
+mod utils;
+use crate::utils::*;
+
+fn setup() -> (OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, Env, Addr) {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let api = deps.api;
+    let accepted_denoms: HashMap<String, AcceptedDenomValue> = vec![(
+        "uusdc".to_string(),
+        AcceptedDenomValue {
+            max_debt: Uint128::from(1000u128),
+            min_balance_threshold: Uint128::zero(),
+        }
+    )].into_iter().collect();
+    let workflow_manager_address = api.addr_make("workflow_manager");
+    instantiate_contract(
+        deps.as_mut(),
+        env.clone(),
+        api.addr_make("admin"),
+        accepted_denoms,
+        api.addr_make("execution_destination"),
+        api.addr_make("distribution_destination"),
+        api.addr_make("crank_authorized"),
+        workflow_manager_address.clone(),
+        Uint128::from(5u128),
+    ).unwrap();
+    (deps, env, workflow_manager_address)
+}
+
+#[test]
+fn test_executor_fees_from_user_balance_are_claimable() {
+    let (mut deps, env, workflow_manager_address) = setup();
+    let user = deps.api.addr_make("user");
+    let executor = deps.api.addr_make("executor");
+    USER_BALANCES.save(deps.as_mut().storage, (user.clone(), "uusdc"), &100).unwrap();
+
+    // Execution is charged first, the executor reward only gets what's left of the balance
+    let batch = vec![UserFees {
+        user: user.clone(),
+        fees: vec![
+            Fee { fee_type: FeeType::Execution, denom: "uusdc".to_string(), amount: Uint128::from(80u128) },
+            Fee { fee_type: FeeType::Executor { executor_address: executor.clone() }, denom: "uusdc".to_string(), amount: Uint128::from(30u128) },
+        ],
+    }];
+    execute_charge_fees_from_user_balance(deps.as_mut(), env.clone(), workflow_manager_address, batch).unwrap();
+
+    assert_eq!(USER_BALANCES.load(deps.as_ref().storage, (user, "uusdc")).unwrap(), -10);
+    assert_eq!(EXECUTION_FEES.load(deps.as_ref().storage, "uusdc").unwrap(), Uint128::from(80u128));
+    let executor_fees = get_executor_fees(deps.as_ref(), executor.clone()).unwrap();
+    assert_eq!(executor_fees.fees.len(), 1);
+    assert_eq!(executor_fees.fees[0].balance, Uint128::from(20u128));
+
+    let response = execute_claim_executor_fees(deps.as_mut(), env.clone(), executor.clone()).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-fee-manager/claim_executor_fees");
+    match &response.messages[0].msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            assert_eq!(to_address, &executor.to_string());
+            assert_eq!(amount[0].amount, Uint128::from(20u128));
+        }
+        _ => panic!("Expected BankMsg::Send"),
+    }
+
+    let result = execute_claim_executor_fees(deps.as_mut(), env, executor);
+    assert!(matches!(result, Err(ContractError::NoExecutorFeesToClaim {})));
+}
+
+#[test]
+fn test_executor_fees_from_message_coins() {
+    let (mut deps, env, _workflow_manager_address) = setup();
+    let user = deps.api.addr_make("user");
+    let executor = deps.api.addr_make("executor");
+
+    let fees = vec![
+        Fee { fee_type: FeeType::Execution, denom: "uusdc".to_string(), amount: Uint128::from(90u128) },
+        Fee { fee_type: FeeType::Executor { executor_address: executor.clone() }, denom: "uusdc".to_string(), amount: Uint128::from(10u128) },
+    ];
+    execute_charge_fees_from_message_coins(deps.as_mut(), env, user, fees).unwrap();
+
+    assert_eq!(EXECUTION_FEES.load(deps.as_ref().storage, "uusdc").unwrap(), Uint128::from(90u128));
+    let executor_fees = get_executor_fees(deps.as_ref(), executor).unwrap();
+    assert_eq!(executor_fees.fees[0].balance, Uint128::from(10u128));
+}
@@ -200,4 +200,13 @@ pub fn execute_claim_creator_fees(
 ) -> Result<Response, ContractError> {
     let info = message_info(&sender, &[]);
     auto_fee_manager::handlers::handle_claim_creator_fees(deps, info)
-} 
\ No newline at end of file
+} 
+#[allow(dead_code)]
+pub fn execute_claim_executor_fees(
+    deps: DepsMut,
+    _env: Env,
+    sender: Addr,
+) -> Result<Response, ContractError> {
+    let info = message_info(&sender, &[]);
+    auto_fee_manager::handlers::handle_claim_executor_fees(deps, info)
+}
@@ -9,14 +9,21 @@ use cw_utils::nonpayable;
 use crate::{
     error::ContractError,
     execute::{
-        cancel_instance, cancel_run, charge_fees, execute_action, execute_instance, finish_instances, pause_schedule, publish_workflow, purge_instances, remove_user_payment_config_execute, reset_instance, resume_schedule, set_user_payment_config
+        ACTION_REPLY_ID_START, bond_executor, cancel_instance, cancel_run, charge_fees, claim_lease, execute_action, execute_instance, finish_instances, pause_schedule, publish_workflow, purge_instances, release_lease, remove_user_payment_config_execute, reset_instance, resume_schedule, set_user_payment_config, set_workflow_executors,
+        slash_executor, set_execution_fee_table, unbond_executor, withdraw_executor_bond, pause_executions, unpause_executions, set_instance_spend_limits,
     },
     msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg},
     query::{
-        query_instances_by_requester, query_user_payment_config, query_workflow_by_id,
+        query_executor, query_executors, query_execution_fee_table, query_accrued_execution_fees, query_fee_batch, query_pauses, query_workflow_stats, query_workflow_leaderboard, query_required_grants, query_instance_spend_limits, query_simulate_action, query_executable_actions, query_instance_lease, query_instances_by_requester, query_user_payment_config, query_workflow_by_id,
         query_workflow_instance,
     },
-    state::{legacy_load_user_payment_config, legacy_load_user_payment_config_keys, legacy_remove_user_payment_config, load_config, save_config, save_user_payment_config, Config, LegacyPaymentSource, PaymentConfig}
+    state::{legacy_load_user_payment_config, legacy_load_user_payment_config_keys, legacy_remove_user_payment_config, load_config, save_config, save_user_payment_config, Config, LegacyPaymentSource, PaymentConfig, EXECUTOR_ROLE, LEGACY_CONFIG, PUBLISHER_ROLE, ROLES}
+};
+use auto_access_control::{
+    execute::execute_access_control,
+    msg::{AccessControlMsg, AccessControlQuery},
+    query::query_access_control,
+    state::{load_role_members, may_load_ownership, save_owner, save_role_members},
 };
 
 // version info for migration info
@@ -39,22 +46,24 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let state = Config {
-        owner: info.sender.clone(),
-        allowed_publishers: msg.allowed_publishers,
-        allowed_action_executors: msg.allowed_action_executors,
         referral_memo: msg.referral_memo,
         fee_manager_address: msg.fee_manager_address,
+        max_consecutive_failures: msg.max_consecutive_failures,
+        executor_bonding: msg.executor_bonding,
     };
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     save_config(deps.storage, &state)?;
+    save_owner(deps.storage, &info.sender)?;
+    save_role_members(deps.storage, PUBLISHER_ROLE, &msg.allowed_publishers)?;
+    save_role_members(deps.storage, EXECUTOR_ROLE, &msg.allowed_action_executors)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender)
         .add_attribute(
             "approvers_count",
-            state.allowed_publishers.len().to_string(),
+            msg.allowed_publishers.len().to_string(),
         ))
 }
 
@@ -65,7 +74,10 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    nonpayable(&info)?;
+    // Bonding is the only operation that takes funds
+    if !matches!(msg, ExecuteMsg::BondExecutor {}) {
+        nonpayable(&info)?;
+    }
 
     match msg {
         ExecuteMsg::PublishWorkflow { workflow } => publish_workflow(deps, env, info, workflow),
@@ -81,6 +93,8 @@ pub fn execute(
             action_id,
             template_id,
             params,
+            params_signature,
+            execution_id,
         } => execute_action(
             deps,
             env,
@@ -90,6 +104,8 @@ pub fn execute(
             action_id,
             template_id,
             params,
+            params_signature,
+            execution_id,
         ),
         ExecuteMsg::SetUserPaymentConfig {
             payment_config,
@@ -101,14 +117,40 @@ pub fn execute(
         ExecuteMsg::PurgeInstances { instance_ids } => purge_instances(deps, env, info, instance_ids),
         ExecuteMsg::FinishInstances { instances } => finish_instances(deps, env, info, instances),
         ExecuteMsg::ResetInstance { user_address, instance_id } => reset_instance(deps, env, info, user_address, instance_id),
+        ExecuteMsg::ClaimLease { user_address, instance_id, duration_seconds } => claim_lease(deps, env, info, user_address, instance_id, duration_seconds),
+        ExecuteMsg::ReleaseLease { user_address, instance_id } => release_lease(deps, env, info, user_address, instance_id),
+        ExecuteMsg::SetWorkflowExecutors { workflow_id, executors } => set_workflow_executors(deps, env, info, workflow_id, executors),
+        ExecuteMsg::BondExecutor {} => bond_executor(deps, env, info),
+        ExecuteMsg::UnbondExecutor {} => unbond_executor(deps, env, info),
+        ExecuteMsg::WithdrawExecutorBond {} => withdraw_executor_bond(deps, env, info),
+        ExecuteMsg::SlashExecutor { executor, amount, reason } => slash_executor(deps, env, info, executor, amount, reason),
+        ExecuteMsg::SetExecutionFeeTable { table } => set_execution_fee_table(deps, env, info, table),
+        ExecuteMsg::SetInstanceSpendLimits { instance_id, limits } => set_instance_spend_limits(deps, env, info, instance_id, limits),
+        ExecuteMsg::PauseExecutions { scope, reason, block_instances, block_fees } => {
+            pause_executions(deps, env, info, scope, reason, block_instances, block_fees)
+        }
+        ExecuteMsg::UnpauseExecutions { scope } => unpause_executions(deps, env, info, scope),
+        ExecuteMsg::TransferOwnership { new_owner } => access_control(deps, info, AccessControlMsg::TransferOwnership { new_owner }),
+        ExecuteMsg::AcceptOwnership {} => access_control(deps, info, AccessControlMsg::AcceptOwnership {}),
+        ExecuteMsg::CancelOwnershipTransfer {} => access_control(deps, info, AccessControlMsg::CancelOwnershipTransfer {}),
+        ExecuteMsg::GrantRole { role, address } => access_control(deps, info, AccessControlMsg::GrantRole { role, address }),
+        ExecuteMsg::RevokeRole { role, address } => access_control(deps, info, AccessControlMsg::RevokeRole { role, address }),
     }
 }
 
+fn access_control(deps: DepsMut, info: MessageInfo, msg: AccessControlMsg) -> Result<Response, ContractError> {
+    Ok(execute_access_control(deps, info, msg, ROLES, "autorujira-workflow-manager")?)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
     // Handle replies from submessages
     match reply.id {
-        // Handle fee manager replies (instance_id is used as reply_id)
+        // Handle action replies (dispatched by execute_action)
+        id if id >= ACTION_REPLY_ID_START => {
+            crate::execute::handle_action_reply(deps, env, reply)
+        }
+        // Handle fee manager replies (dispatched by charge_fees)
         id if id > 0 => {
             // This is a fee manager reply
             crate::execute::handle_fee_manager_reply(deps, env, reply)
@@ -122,17 +164,23 @@ pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, Contract
     let mut config = load_config(deps.storage)?;
     match msg {
         SudoMsg::SetOwner(owner) => {
-            config.owner = owner;
+            save_owner(deps.storage, &owner)?;
         }
         SudoMsg::SetAllowedPublishers(allowed_publishers) => {
-            config.allowed_publishers = allowed_publishers;
+            save_role_members(deps.storage, PUBLISHER_ROLE, &allowed_publishers)?;
         }
         SudoMsg::SetAllowedActionExecutors(allowed_action_executors) => {
-            config.allowed_action_executors = allowed_action_executors;
+            save_role_members(deps.storage, EXECUTOR_ROLE, &allowed_action_executors)?;
         }
         SudoMsg::SetReferralMemo(referral_memo) => {
             config.referral_memo = referral_memo;
         }
+        SudoMsg::SetMaxConsecutiveFailures(max_consecutive_failures) => {
+            config.max_consecutive_failures = max_consecutive_failures;
+        }
+        SudoMsg::SetExecutorBonding(executor_bonding) => {
+            config.executor_bonding = executor_bonding;
+        }
     }
     save_config(deps.storage, &config)?;
     Ok(Response::default())
@@ -140,6 +188,19 @@ pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, Contract
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: ()) -> StdResult<Response> {
+    // Move the owner and role sets out of the config into access control
+    if may_load_ownership(deps.storage)?.is_none() {
+        let legacy_config = LEGACY_CONFIG.load(deps.storage)?;
+        save_owner(deps.storage, &legacy_config.owner)?;
+        save_role_members(deps.storage, PUBLISHER_ROLE, &legacy_config.allowed_publishers)?;
+        save_role_members(deps.storage, EXECUTOR_ROLE, &legacy_config.allowed_action_executors)?;
+        save_config(deps.storage, &Config {
+            referral_memo: legacy_config.referral_memo,
+            fee_manager_address: legacy_config.fee_manager_address,
+            max_consecutive_failures: legacy_config.max_consecutive_failures,
+            executor_bonding: legacy_config.executor_bonding,
+        })?;
+    }
     if get_contract_version(deps.storage)?.version == "0.1.0" {
         // Migrate legacy payment config
         for user in legacy_load_user_payment_config_keys(deps.storage)? {
@@ -158,7 +219,7 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: ()) -> StdResult<Response> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetInstancesByRequester { requester_address } => {
             to_json_binary(&query_instances_by_requester(deps, requester_address)?)
@@ -173,13 +234,51 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetUserPaymentConfig { user_address } => {
             to_json_binary(&query_user_payment_config(deps, user_address)?)
         }
+        QueryMsg::GetInstanceLease { user_address, instance_id } => {
+            to_json_binary(&query_instance_lease(deps, env, user_address, instance_id)?)
+        }
+        QueryMsg::GetInstanceSpendLimits { user_address, instance_id } => {
+            to_json_binary(&query_instance_spend_limits(deps, env, user_address, instance_id)?)
+        }
+        QueryMsg::GetExecutor { executor_address } => {
+            to_json_binary(&query_executor(deps, executor_address)?)
+        }
+        QueryMsg::GetExecutors { start_after, limit } => {
+            to_json_binary(&query_executors(deps, start_after, limit)?)
+        }
+        QueryMsg::GetPauses {} => to_json_binary(&query_pauses(deps)?),
+        QueryMsg::GetExecutionFeeTable {} => to_json_binary(&query_execution_fee_table(deps)?),
+        QueryMsg::GetAccruedExecutionFees { user_address } => {
+            to_json_binary(&query_accrued_execution_fees(deps, user_address)?)
+        }
+        QueryMsg::GetFeeBatch { batch_id } => to_json_binary(&query_fee_batch(deps, batch_id)?),
+        QueryMsg::GetWorkflowStats { workflow_id } => to_json_binary(&query_workflow_stats(deps, workflow_id)?),
+        QueryMsg::GetWorkflowLeaderboard { start_after, limit } => {
+            to_json_binary(&query_workflow_leaderboard(deps, start_after, limit)?)
+        }
+        QueryMsg::GetExecutableActions { user_address, instance_id } => {
+            to_json_binary(&query_executable_actions(deps, env, user_address, instance_id)?)
+        }
+        QueryMsg::SimulateAction { user_address, instance_id, action_id, template_id, params } => {
+            to_json_binary(&query_simulate_action(deps, env, user_address, instance_id, action_id, template_id, params)?)
+        }
+        QueryMsg::GetRequiredGrants { workflow_id, user_address } => {
+            to_json_binary(&query_required_grants(deps, env, workflow_id, user_address)?)
+        }
+        QueryMsg::Ownership {} => query_access_control(deps, AccessControlQuery::Ownership {}),
+        QueryMsg::HasRole { role, address } => query_access_control(deps, AccessControlQuery::HasRole { role, address }),
+        QueryMsg::RoleMembers { role, start_after, limit } => {
+            query_access_control(deps, AccessControlQuery::RoleMembers { role, start_after, limit })
+        }
         QueryMsg::GetConfig {} => {
             let config = load_config(deps.storage)?;
             let result = InstantiateMsg {
-                allowed_publishers: config.allowed_publishers.clone(),
-                allowed_action_executors: config.allowed_action_executors.clone(),
+                allowed_publishers: load_role_members(deps.storage, PUBLISHER_ROLE, None, None)?.into_iter().collect(),
+                allowed_action_executors: load_role_members(deps.storage, EXECUTOR_ROLE, None, None)?.into_iter().collect(),
                 referral_memo: config.referral_memo,
-                fee_manager_address: config.fee_manager_address
+                fee_manager_address: config.fee_manager_address,
+                max_consecutive_failures: config.max_consecutive_failures,
+                executor_bonding: config.executor_bonding,
             };
             to_json_binary(&result)
         }
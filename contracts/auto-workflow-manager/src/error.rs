@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use auto_access_control::AccessControlError;
 use cw_utils::PaymentError;
 use thiserror::Error;
 
@@ -10,6 +11,9 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
+    #[error("{0}")]
+    AccessControl(#[from] AccessControlError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -63,6 +67,37 @@ pub enum ContractError {
         instance_id: String,
     },
 
+    #[error("Execution {execution_id} was already submitted for instance {instance_id}")]
+    DuplicateExecution {
+        execution_id: String,
+        instance_id: String,
+    },
+
+    #[error("Spend limit of {limit}{denom} exceeded, {spent}{denom} already spent")]
+    SpendLimitExceeded {
+        denom: String,
+        limit: String,
+        spent: String,
+    },
+
+    #[error("Executions are paused ({scope}): {reason}")]
+    ExecutionsPaused {
+        scope: String,
+        reason: String,
+    },
+
+    #[error("Executor {executor} is not bonded")]
+    ExecutorNotBonded {
+        executor: String,
+    },
+
+    #[error("Instance {instance_id} is leased by {holder} until {expires_at}")]
+    InstanceLeased {
+        instance_id: String,
+        holder: String,
+        expires_at: String,
+    },
+
     #[error("Action {action_id} not found in workflow {workflow_id}")]
     ActionNotFound {
         workflow_id: String,
@@ -81,6 +116,54 @@ pub enum ContractError {
         reason: String,
     },
 
+    #[error("Invalid params signature: {reason}")]
+    InvalidParamsSignature {
+        reason: String,
+    },
+
+    #[error("Creator fee of {usd_amount} for instance {instance_id} exceeds its schedule, {remaining} left")]
+    CreatorFeeExceedsSchedule {
+        instance_id: String,
+        usd_amount: String,
+        remaining: String,
+    },
+
+    #[error("Fee batch {batch_id} was already processed")]
+    FeeBatchAlreadyProcessed {
+        batch_id: String,
+    },
+
+    #[error("Execution fee of {usd_amount} exceeds the {accrued} accrued by executed actions")]
+    ExecutionFeeExceedsAccrued {
+        usd_amount: String,
+        accrued: String,
+    },
+
+    #[error("Action {action_id} can't run now: {reason}")]
+    ActionTimingViolation {
+        action_id: String,
+        reason: String,
+    },
+
+    #[error("Price trigger not met: price {price} is not {condition} {trigger_price}")]
+    PriceTriggerNotMet {
+        price: String,
+        condition: String,
+        trigger_price: String,
+    },
+
+    #[error("Invalid value for parameter {param}: {reason}")]
+    InvalidParamValue {
+        param: String,
+        reason: String,
+    },
+
+    #[error("Parameter {param} violates constraint: {reason}")]
+    ParamConstraintViolation {
+        param: String,
+        reason: String,
+    },
+
     #[error("Denom {0} is not allowed for this action")]
     InvalidDenom(String),
 
@@ -89,4 +172,10 @@ pub enum ContractError {
         contract: String,
         workflow_id: String,
     },
+
+    #[error("Message {message} is not allowed for contract {contract}")]
+    MessageNotAllowed {
+        message: String,
+        contract: String,
+    },
 }
@@ -1,8 +1,8 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::{BTreeMap, HashMap, HashSet}, str::FromStr};
 
-use cosmwasm_std::{to_json_string, Event, QuerierWrapper};
+use cosmwasm_std::{to_json_string, to_json_vec, Event, QuerierWrapper};
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg,
+    to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Decimal256, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg,
     Reply, SubMsg
 };
 
@@ -10,11 +10,13 @@ use auto_fee_manager::msg::ExecuteMsg as FeeManagerExecuteMsg;
 use auto_fee_manager::msg::Fee as FeeManagerFee;
 use auto_fee_manager::msg::FeeType as FeeManagerFeeType;
 use auto_fee_manager::msg::UserFees as FeeManagerUserFees;
-use rujira_rs::Oracle;
+use rujira_rs::{Layer1Asset, Oracle};
+use regex::Regex;
+use sha2::{Digest, Sha256};
 
 use crate::{
     msg::{
-        ActionParamValue, ExecutionType, FeeType, FinishInstanceRequest, InstanceId, NewWorkflowMsg, UserFee, WorkflowInstanceState, WorkflowState, WorkflowVisibility
+        ActionId, ActionParamValue, ExecutionType, FeeTotal, FeeType, FinishInstanceRequest, InstanceId, NewWorkflowMsg, RunOutcome, UserFee, WorkflowId, WorkflowInstanceState, WorkflowState, WorkflowVisibility
     },
     state::{load_config, load_user_payment_config},
     ContractError,
@@ -32,25 +34,99 @@ pub struct FeeEventData {
     pub debit_amount: Uint128,
     pub fee_type: FeeType,
     pub creator_address: Option<String>,
+    #[serde(default)]
+    pub executor_address: Option<String>,
+    // Workflow a creator fee is attributed to in its stats
+    #[serde(default)]
+    pub workflow_id: Option<WorkflowId>,
+    #[serde(default)]
+    pub batch_id: String,
+    // Executor accrued execution fees consumed by the executor reward, given back if the charge fails
+    #[serde(default)]
+    pub executor_accrued_usd: Uint128,
+    // USD counted against the instance's creator fee schedule, given back if the charge fails
+    #[serde(default)]
+    pub creator_fee_usd: Uint128,
+    // USD deducted from the user's accrued execution fees, given back if the charge fails
+    #[serde(default)]
+    pub execution_fee_usd: Uint128,
 }
 
 // Temporary storage for fee event data
 pub const FEE_EVENT_DATA: Map<u64, Vec<FeeEventData>> = Map::new("fed");
 
+// Data structure to pass from execute_action to reply
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ActionReplyData {
+    pub user_address: Addr,
+    pub instance_id: InstanceId,
+    pub workflow_id: WorkflowId,
+    pub action_id: ActionId,
+    pub previous_action: Option<ActionId>,
+    pub execution_id: Option<String>,
+    // USD execution fee accrued for the user if the message succeeds
+    #[serde(default)]
+    pub execution_fee: Uint128,
+    // Executor credited with the execution fee, for its rewards
+    #[serde(default)]
+    pub executor: Option<Addr>,
+    #[serde(default)]
+    pub previous_run_started_at: Option<cosmwasm_std::Timestamp>,
+    // Rate limited executions of the action without this one
+    #[serde(default)]
+    pub previous_action_executions: Option<Vec<cosmwasm_std::Timestamp>>,
+    // Creator fee usage without this action
+    #[serde(default)]
+    pub previous_creator_fee_usage: Option<CreatorFeeUsage>,
+    // Spends of the limited denoms without this action
+    #[serde(default)]
+    pub previous_spends: Vec<(String, Vec<Spend>)>,
+}
+
+// Temporary storage for action reply data
+pub const ACTION_REPLY_DATA: Map<u64, ActionReplyData> = Map::new("ard");
+
+// Reply ids used by charge_fees, one per charged user
+pub const FEE_REPLY_ID_START: u64 = 1000;
+// Reply ids used by execute_action, one per dispatched message
+pub const ACTION_REPLY_ID_START: u64 = 1 << 32;
+
 
 use crate::{
-    msg::{NewInstanceMsg, ParamId, TemplateId},
+    msg::{ActionMsg, ActionTiming, CreatorFee, ExecutionFeeTable, CreatorFeeSchedule, FeeAmount, MinReturnProtection, NewInstanceMsg, PriceCondition, PriceSource, PriceTrigger, SubWorkflow, SwapVenue, PauseScope, SpendLimit, ParamBound, ParamConstraint, ParamId, ParamsSignature, SignatureAlgorithm, SignedParamsPayload, TemplateId},
     state::{
-        load_next_instance_id, load_workflow, load_workflow_action, load_workflow_action_params,
-        load_workflow_action_template, load_workflow_instance, load_workflow_instance_params,
+        load_next_instance_id, load_workflow, load_workflow_action, load_workflow_action_outputs,
+        load_workflow_action_param_constraints, load_workflow_action_params, load_workflow_action_signed_params, load_workflow_action_template,
+        load_workflow_actions, load_workflow_instance, load_workflow_instance_param_constraints, load_workflow_instance_param_signer, load_workflow_instance_price_trigger,
+        load_workflow_instance_params, load_workflow_instance_params_nonce, load_workflow_instance_run_outputs,
         remove_user_payment_config, remove_workflow_instance, save_user_payment_config,
         save_workflow, save_workflow_action, save_workflow_action_contracts,
-        save_workflow_action_params, save_workflow_action_templates, save_workflow_instance,
-        save_workflow_instance_params, validate_contract_is_whitelisted,
-        validate_sender_is_action_executor, validate_sender_is_owner, validate_sender_is_publisher,
-        Action, PaymentConfig, Workflow, WorkflowInstance,
+        save_workflow_action_outputs, save_workflow_action_param_constraints, save_workflow_action_params,
+        save_workflow_action_signed_params, save_workflow_action_templates, save_workflow_instance,
+        load_workflow_action_sub_workflow, save_workflow_action_sub_workflow,
+        load_workflow_action_min_return_protection, save_workflow_action_min_return_protection,
+        load_workflow_action_timing, save_workflow_action_timing,
+        load_execution_fee_table, save_execution_fee_table, remove_execution_fee_table,
+        load_fee_batch, save_fee_batch, record_fee_batch_charge, record_fee_batch_failures, FeeBatch, FeeBatchFailure,
+        load_user_accrued_execution_fees, save_user_accrued_execution_fees,
+        load_executor_accrued_execution_fees, save_executor_accrued_execution_fees,
+        load_workflow_creator_fee_schedule, save_workflow_creator_fee_schedule,
+        load_workflow_instance_creator_fee_usage, save_workflow_instance_creator_fee_usage, CreatorFeeUsage,
+        load_workflow_instance_action_executions, save_workflow_instance_action_executions,
+        save_workflow_instance_param_constraints, save_workflow_instance_param_signer, save_workflow_instance_price_trigger,
+        save_workflow_instance_params, save_workflow_instance_params_nonce, save_workflow_instance_run_outputs,
+        has_workflow_instance_execution, remove_workflow_instance_execution, save_workflow_instance_execution,
+        validate_contract_is_whitelisted, validate_message_is_allowed,
+        validate_sender_can_use_lease, validate_sender_is_action_executor, validate_sender_is_owner,
+        validate_sender_is_publisher, validate_sender_is_guardian, validate_actions_not_paused,
+        validate_instances_not_paused, validate_fees_not_paused, validate_contract_not_paused,
+        load_pause, save_pause, remove_pause, Pause, load_workflow_instance_spend_limits, save_workflow_instance_spend_limits,
+        remove_workflow_instance_spend_limits, load_workflow_instance_spends, save_workflow_instance_spends, spent_within_limit, Spend, load_workflow_instance_lease, remove_workflow_instance_lease,
+        save_workflow_instance_lease, save_workflow_executors, remove_workflow_executors,
+        load_executor_bond, save_executor_bond, remove_executor_bond, record_workflow_instance_created,
+        record_workflow_instance_state_change, record_workflow_action_executed, record_workflow_creator_fee, ExecutorBond, Action, InstanceLease, PaymentConfig, Workflow, WorkflowInstance,
     },
-    utils::build_authz_execute_contract_msg,
+    utils::{build_authz_execute_contract_msg, extract_attribute_from_reply, extract_attribute_from_reply_with_filters, find_missing_authz_grant, is_param_reference, render_param_value, validate_param_value, validate_param_values, MSG_EXECUTE_CONTRACT_TYPE_URL},
 };
 
 pub fn publish_workflow(
@@ -76,7 +152,41 @@ pub fn publish_workflow(
         state: WorkflowState::Approved,
     };
 
+    for action in input_workflow.actions.values() {
+        validate_param_constraint_definitions(&action.param_constraints)?;
+        let fixed_params = action.params.iter()
+            .chain(action.sub_workflow.iter().flat_map(|sub_workflow| sub_workflow.params.iter()))
+            .filter(|(_, value)| !is_param_reference(value));
+        for (param_id, value) in fixed_params {
+            validate_param_value(deps.api, param_id, value)?;
+        }
+        if let Some(contract) = action.allowed_messages.keys().find(|contract| !action.whitelisted_contracts.contains(*contract)) {
+            return Err(ContractError::ContractNotWhitelisted {
+                contract: contract.clone(),
+                workflow_id: input_workflow.id.clone(),
+            });
+        }
+    }
+    for (action_id, action) in &input_workflow.actions {
+        if let Some(sub_workflow) = &action.sub_workflow {
+            validate_sub_workflow(deps.storage, &info, action_id, action, sub_workflow)?;
+        }
+        if let Some(protection) = &action.min_return_protection {
+            validate_min_return_protection(deps.api, action_id, action, protection)?;
+        }
+        if let Some(timing) = &action.timing {
+            validate_action_timing_definition(&new_workflow.start_actions, action_id, timing)?;
+        }
+        validate_template_placeholders(action_id, action)?;
+    }
+    if let Some(schedule) = &input_workflow.creator_fee_schedule {
+        validate_creator_fee_schedule(deps.api, &input_workflow.actions, schedule)?;
+    }
+
     save_workflow(deps.storage, &input_workflow.id, &new_workflow)?;
+    if let Some(schedule) = &input_workflow.creator_fee_schedule {
+        save_workflow_creator_fee_schedule(deps.storage, &input_workflow.id, schedule)?;
+    }
     for (action_id, action) in input_workflow.actions {
         let new_action = Action {
             next_actions: action.next_actions,
@@ -94,7 +204,20 @@ pub fn publish_workflow(
             &input_workflow.id,
             &action_id,
             &action.whitelisted_contracts,
+            &action.allowed_messages,
         )?;
+        save_workflow_action_outputs(deps.storage, &input_workflow.id, &action_id, &action.outputs)?;
+        save_workflow_action_signed_params(deps.storage, &input_workflow.id, &action_id, &action.signed_params)?;
+        save_workflow_action_param_constraints(deps.storage, &input_workflow.id, &action_id, &action.param_constraints)?;
+        if let Some(sub_workflow) = &action.sub_workflow {
+            save_workflow_action_sub_workflow(deps.storage, &input_workflow.id, &action_id, sub_workflow)?;
+        }
+        if let Some(protection) = &action.min_return_protection {
+            save_workflow_action_min_return_protection(deps.storage, &input_workflow.id, &action_id, protection)?;
+        }
+        if let Some(timing) = &action.timing {
+            save_workflow_action_timing(deps.storage, &input_workflow.id, &action_id, timing)?;
+        }
     }
 
     Ok(Response::new()
@@ -126,6 +249,9 @@ pub fn execute_instance(
         });
     }
 
+    // Pauses can opt into blocking new instances too
+    validate_instances_not_paused(deps.storage, &instance.workflow_id)?;
+
     // Check if workflow is private and sender is not the publisher
     if matches!(workflow.visibility, WorkflowVisibility::Private)
         && info.sender != workflow.publisher
@@ -135,6 +261,36 @@ pub fn execute_instance(
         });
     }
 
+    // Validate the param signer key, and require one if the workflow has signed params
+    match &instance.param_signer {
+        Some(signer) => {
+            let valid_length = match signer.algorithm {
+                SignatureAlgorithm::Secp256k1 => signer.public_key.len() == 33 || signer.public_key.len() == 65,
+                SignatureAlgorithm::Ed25519 => signer.public_key.len() == 32,
+            };
+            if !valid_length {
+                return Err(ContractError::GenericError(
+                    "Invalid param signer public key".to_string(),
+                ));
+            }
+        }
+        None => {
+            for (workflow_id, action_id) in workflow_action_definitions(deps.storage, &instance.workflow_id)? {
+                if !load_workflow_action_signed_params(deps.storage, &workflow_id, &action_id)?.is_empty() {
+                    return Err(ContractError::GenericError(
+                        "Workflow requires signed parameters but no param signer was provided".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    validate_param_constraint_definitions(&instance.param_constraints)?;
+    validate_param_values(deps.api, &instance.onchain_parameters)?;
+    if let Some(PriceTrigger { source: PriceSource::Pool { asset }, .. }) = &instance.price_trigger {
+        Layer1Asset::from_str(asset).map_err(|e| ContractError::GenericError(e.to_string()))?;
+    }
+
     // Generate auto-incremental ID for the instance
     let instance_id = load_next_instance_id(deps.storage)?;
 
@@ -145,16 +301,38 @@ pub fn execute_instance(
         last_executed_action: None,
         execution_type: instance.execution_type,
         expiration_time: instance.expiration_time,
+        last_run_outcome: None,
+        consecutive_failures: 0,
+        run_started_at: None,
+        allow_bonded_executors: instance.allow_bonded_executors,
     };
 
     // Save the instance
     save_workflow_instance(deps.storage, &info.sender, &instance_id, &new_instance)?;
+    record_workflow_instance_created(deps.storage, &new_instance.workflow_id)?;
     save_workflow_instance_params(
         deps.storage,
         &info.sender,
         &instance_id,
         &instance.onchain_parameters,
     )?;
+    if let Some(signer) = &instance.param_signer {
+        save_workflow_instance_param_signer(deps.storage, &info.sender, &instance_id, signer)?;
+    }
+    if !instance.param_constraints.is_empty() {
+        save_workflow_instance_param_constraints(deps.storage, &info.sender, &instance_id, &instance.param_constraints)?;
+    }
+    if let Some(trigger) = &instance.price_trigger {
+        save_workflow_instance_price_trigger(deps.storage, &info.sender, &instance_id, trigger)?;
+    }
+    if load_workflow_creator_fee_schedule(deps.storage, &new_instance.workflow_id)?.is_some() {
+        save_workflow_instance_creator_fee_usage(deps.storage, &info.sender, &instance_id, &CreatorFeeUsage {
+            created_at: _env.block.time,
+            runs: 0,
+            action_funds: BTreeMap::new(),
+            charged_usd: Uint128::zero(),
+        })?;
+    }
 
     Ok(Response::new()
         .add_event(
@@ -189,6 +367,7 @@ pub fn cancel_run(
         // instance state remains Running, but last_executed_action is reset to None
         updated_instance.last_executed_action = None;
         save_workflow_instance(deps.storage, &info.sender, &instance_id, &updated_instance)?;
+        remove_workflow_instance_lease(deps.storage, &info.sender, &instance_id)?;
     }
 
     Ok(Response::new()
@@ -219,6 +398,7 @@ pub fn cancel_instance(
         ));
     }
 
+    record_workflow_instance_state_change(deps.storage, &instance.workflow_id, &instance.state, &WorkflowInstanceState::Cancelled)?;
     let mut updated_instance = instance;
     updated_instance.state = WorkflowInstanceState::Cancelled;
     updated_instance.last_executed_action = None;
@@ -289,8 +469,9 @@ pub fn resume_schedule(
             }
         })?;
 
-    // Check if instance is NOT Recurrent (only Recurrent instances can have their schedule changed)
-    if !matches!(instance.execution_type, ExecutionType::Recurrent) {
+    // Check if instance is NOT Recurrent (only Recurrent instances can have their schedule changed),
+    // unless it was paused after consecutive action failures
+    if !matches!(instance.execution_type, ExecutionType::Recurrent) && instance.consecutive_failures == 0 {
         return Err(ContractError::GenericError(
             "Can't change schedule for non-recurrent instances".to_string(),
         ));
@@ -303,8 +484,9 @@ pub fn resume_schedule(
         ));
     }
 
-    // Resume the instance
+    // Resume the instance and give it a fresh failure budget
     instance.state = WorkflowInstanceState::Running;
+    instance.consecutive_failures = 0;
 
     // Save the updated instance
     save_workflow_instance(deps.storage, &info.sender, &instance_id, &instance)?;
@@ -326,92 +508,179 @@ pub fn execute_action(
     action_id: String,
     template_id: TemplateId,
     params: Option<HashMap<String, ActionParamValue>>,
+    params_signature: Option<ParamsSignature>,
+    execution_id: Option<String>,
 ) -> Result<Response, ContractError> {
-    // Validate sender is action executor
-    validate_sender_is_action_executor(deps.storage, &info)?;
-
     // Load user instance
     let user_addr = deps.api.addr_validate(&user_address)?;
     let user_instance: WorkflowInstance =
         load_workflow_instance(deps.storage, &user_addr, &instance_id)?;
 
-    // Validate instance expiration time
-    if env.block.time >= user_instance.expiration_time {
-        return Err(ContractError::GenericError(
-            "Instance has expired".to_string(),
-        ));
+    // Validate sender is action executor
+    validate_sender_is_action_executor(deps.storage, &info, &user_instance)?;
+
+    // Nothing runs while the contract or the workflow is paused
+    validate_actions_not_paused(deps.storage, &user_instance.workflow_id)?;
+
+    // Only the leaseholder can execute while its lease is active
+    validate_sender_can_use_lease(deps.storage, &env, &info, &user_addr, &instance_id)?;
+
+    // Reject executions that were already submitted, so executors can retry safely
+    if let Some(execution_id) = &execution_id {
+        if has_workflow_instance_execution(deps.storage, &user_addr, &instance_id, execution_id) {
+            return Err(ContractError::DuplicateExecution {
+                execution_id: execution_id.clone(),
+                instance_id: instance_id.to_string(),
+            });
+        }
     }
 
-    // Instance must be running or finished (if recurrent)
-    if !(matches!(user_instance.state, WorkflowInstanceState::Running) || 
-        (matches!(user_instance.state, WorkflowInstanceState::Finished) && matches!(user_instance.execution_type, ExecutionType::Recurrent))) 
-        {
-        return Err(ContractError::GenericError(
-            "Instance is not running".to_string(),
-        ));
+    // Instance must be able to run the action now
+    let starts_new_run = validate_action_can_execute(deps.storage, &env, &user_instance, &action_id)?;
+    if starts_new_run {
+        validate_price_trigger(deps.storage, deps.querier, &user_addr, &instance_id)?;
+    }
+    let action_executions = check_action_timing(deps.storage, &env, &user_addr, &instance_id, &user_instance, &action_id, starts_new_run)?;
+    if let Some(params) = &params {
+        validate_param_values(deps.api, params)?;
     }
 
-    // Load workflow from user_instance.workflow_id
-    let workflow = load_workflow(deps.storage, &user_instance.workflow_id)?;
+    // Sub-workflow steps run the sub-workflow definition, which can be paused on its own
+    let target = resolve_action_target(deps.storage, &user_instance.workflow_id, &action_id)?;
+    if target.sub_workflow.is_some() {
+        validate_actions_not_paused(deps.storage, &target.workflow_id)?;
+    }
 
-    // Ensure the action exists
-    let _action_to_execute =
-        load_workflow_action(deps.storage, &user_instance.workflow_id, &action_id).map_err(
-            |_| ContractError::ActionNotFound {
-                workflow_id: user_instance.workflow_id.clone(),
-                action_id: action_id.clone(),
-            },
-        )?;
+    // #cp params required to be signed by the workflow action or the instance
+    let mut required_signed_params: HashSet<ParamId> =
+        load_workflow_action_signed_params(deps.storage, &target.workflow_id, &target.action_id)?;
+    let param_signer = load_workflow_instance_param_signer(deps.storage, &user_addr, &instance_id)?;
+    if let Some(signer) = &param_signer {
+        required_signed_params.extend(signer.required_params.iter().cloned());
+    }
+    let mut signed_nonce = None;
+    let signature_required = params
+        .as_ref()
+        .map(|params| params.keys().any(|key| required_signed_params.contains(key)))
+        .unwrap_or(false);
+
+    if signature_required || params_signature.is_some() {
+        let signer = param_signer.ok_or_else(|| ContractError::InvalidParamsSignature {
+            reason: "Instance has no param signer".to_string(),
+        })?;
+        let params_signature = params_signature.ok_or_else(|| ContractError::InvalidParamsSignature {
+            reason: "Signature required for execute action parameters".to_string(),
+        })?;
 
-    let can_execute = match &user_instance.last_executed_action {
-        None => workflow.start_actions.contains(&action_id),
-        Some(last_executed_action_id) => {
-            let last_executed_action = load_workflow_action(
-                deps.storage,
-                &user_instance.workflow_id,
-                &last_executed_action_id,
-            )
-            .map_err(|_| ContractError::ActionNotFound {
-                workflow_id: user_instance.workflow_id.clone(),
-                action_id: last_executed_action_id.clone(),
-            })?;
-            last_executed_action.next_actions.contains(&action_id)
-                || (user_instance.execution_type == ExecutionType::Recurrent
-                    && workflow.end_actions.contains(last_executed_action_id)
-                    && workflow.start_actions.contains(&action_id))
+        if env.block.time >= params_signature.expires_at {
+            return Err(ContractError::InvalidParamsSignature {
+                reason: "Signature has expired".to_string(),
+            });
+        }
+        if let Some(last_nonce) = load_workflow_instance_params_nonce(deps.storage, &user_addr, &instance_id)? {
+            if params_signature.nonce <= last_nonce {
+                return Err(ContractError::InvalidParamsSignature {
+                    reason: format!("Nonce {} already used", params_signature.nonce),
+                });
+            }
         }
-    };
 
-    if !can_execute {
-        return Err(ContractError::GenericError(
-            "Action cannot be executed: not first execution, not valid next action, and not recurrent start action".to_string()
-        ));
+        let payload = SignedParamsPayload {
+            contract_address: env.contract.address.to_string(),
+            user_address: user_addr.to_string(),
+            instance_id,
+            action_id: action_id.clone(),
+            template_id: template_id.clone(),
+            params: params.clone().unwrap_or_default().into_iter().collect(),
+            nonce: params_signature.nonce,
+            expires_at: params_signature.expires_at,
+        };
+        let digest = Sha256::digest(to_json_vec(&payload)?);
+        let verified = match signer.algorithm {
+            SignatureAlgorithm::Secp256k1 => deps.api.secp256k1_verify(&digest, &params_signature.signature, &signer.public_key),
+            SignatureAlgorithm::Ed25519 => deps.api.ed25519_verify(&digest, &params_signature.signature, &signer.public_key),
+        }
+        .map_err(|e| ContractError::InvalidParamsSignature { reason: e.to_string() })?;
+        if !verified {
+            return Err(ContractError::InvalidParamsSignature {
+                reason: "Signature does not match".to_string(),
+            });
+        }
+
+        signed_nonce = Some(params_signature.nonce);
     }
 
-    // Get action parameters and create new HashMap
-    let action_params =
-        load_workflow_action_params(deps.storage, &user_instance.workflow_id, &action_id)?;
-    let instance_params = load_workflow_instance_params(deps.storage, &user_addr, &instance_id)?;
-    let mut resolved_params = HashMap::<String, ActionParamValue>::new();
+    // Outputs from previous actions only live for the run that produced them
+    let run_outputs = if starts_new_run {
+        HashMap::new()
+    } else {
+        load_workflow_instance_run_outputs(deps.storage, &user_addr, &instance_id)?
+    };
 
-    for (key, value) in action_params {
-        // si param.value es #ip.requester => busco user_instance.requester
-        // si param.value comienza con #ip, busco en user_instance.params
-        // si param.value comienza con #cp, busco en execute_action_params
-        // else es un valor fijo
-        let resolved_value = resolve_param_value(&value, &user_addr, &instance_params, &params)?;
-        resolved_params.insert(key.clone(), resolved_value);
-    }
+    let resolved_params = resolve_action_params(
+        deps.storage,
+        deps.querier,
+        &user_addr,
+        &instance_id,
+        &target,
+        &params,
+        &run_outputs,
+    )?;
 
     // Execute template-based action
     let msgs: Vec<WasmMsg> = execute_dynamic_template(
         deps.storage,
-        &user_instance.workflow_id,
-        &action_id,
+        &target.workflow_id,
+        &target.action_id,
         &template_id,
         &resolved_params,
         &params,
+        &run_outputs,
     )?;
+    let msgs = apply_min_return_protection(deps.storage, deps.querier, &target, msgs)?;
+
+    // Check the user's grants up front, a missing grant would only fail deep inside the authz module
+    for msg in &msgs {
+        let WasmMsg::Execute { contract_addr, .. } = msg else {
+            continue;
+        };
+        if let Some(error) = find_missing_authz_grant(&deps.querier, &env, &user_addr, contract_addr) {
+            // Nothing was recorded yet, so the execution can be retried once the grant is in place
+            return Ok(Response::new()
+                .add_event(
+                    cosmwasm_std::Event::new("autorujira-workflow-manager/action-authz-error")
+                        .add_attribute("user_address", user_address)
+                        .add_attribute("instance_id", instance_id.to_string())
+                        .add_attribute("action_id", action_id)
+                        .add_attribute("grantee", env.contract.address.to_string())
+                        .add_attribute("msg_type_url", MSG_EXECUTE_CONTRACT_TYPE_URL)
+                        .add_attribute("contract", contract_addr.clone())
+                        .add_attribute("error", error)
+                ));
+        }
+    }
+
+    // The action is dispatched from here on
+    if let Some(execution_id) = &execution_id {
+        save_workflow_instance_execution(deps.storage, &user_addr, &instance_id, execution_id, &env.block.time)?;
+    }
+    if let Some(executions) = &action_executions {
+        save_workflow_instance_action_executions(deps.storage, &user_addr, &instance_id, &action_id, executions)?;
+    }
+    if let Some(nonce) = signed_nonce {
+        save_workflow_instance_params_nonce(deps.storage, &user_addr, &instance_id, nonce)?;
+    }
+    if starts_new_run {
+        save_workflow_instance_run_outputs(deps.storage, &user_addr, &instance_id, &run_outputs)?;
+    }
+
+    // Count the funds against the user's spend limits, rejecting the action if one is exceeded
+    let mut previous_spends = Vec::new();
+    for (denom, spends) in check_instance_spends(deps.storage, &env, &user_addr, &instance_id, &msgs)? {
+        previous_spends.push((denom.clone(), load_workflow_instance_spends(deps.storage, &user_addr, &instance_id, &denom)?));
+        save_workflow_instance_spends(deps.storage, &user_addr, &instance_id, &denom, &spends)?;
+    }
+    let previous_creator_fee_usage = record_creator_fee_usage(deps.storage, &user_addr, &instance_id, &user_instance.workflow_id, &action_id, starts_new_run, &msgs)?;
 
     let authz_msgs: Vec<CosmosMsg> = msgs
         .iter()
@@ -440,9 +709,34 @@ pub fn execute_action(
         })
         .collect::<Result<Vec<CosmosMsg>, ContractError>>()?;
 
+    // Dispatch each message as a submessage so failures are recorded instead of reverting the tx
+    let execution_fees = expected_execution_fees(deps.storage, &msgs)?;
+    let mut sub_msgs = Vec::new();
+    for (index, authz_msg) in authz_msgs.into_iter().enumerate() {
+        let reply_id = ACTION_REPLY_ID_START + index as u64;
+        ACTION_REPLY_DATA.save(deps.storage, reply_id, &ActionReplyData {
+            user_address: user_addr.clone(),
+            instance_id,
+            workflow_id: user_instance.workflow_id.clone(),
+            action_id: action_id.clone(),
+            previous_action: user_instance.last_executed_action.clone(),
+            execution_id: execution_id.clone(),
+            execution_fee: execution_fees[index],
+            executor: Some(info.sender.clone()),
+            previous_run_started_at: user_instance.run_started_at,
+            previous_action_executions: action_executions.as_ref().map(|executions| executions[..executions.len() - 1].to_vec()),
+            previous_creator_fee_usage: previous_creator_fee_usage.clone(),
+            previous_spends: previous_spends.clone(),
+        })?;
+        sub_msgs.push(SubMsg::reply_always(authz_msg, reply_id));
+    }
+
     // Update instance with last executed action
     let mut updated_instance = user_instance;
     updated_instance.last_executed_action = Some(action_id.clone());
+    if starts_new_run {
+        updated_instance.run_started_at = Some(env.block.time);
+    }
     
     // Update instance state based on whether this is an end action
     // if workflow.end_actions.contains(&action_id) {
@@ -453,154 +747,1073 @@ pub fn execute_action(
     
     save_workflow_instance(deps.storage, &user_addr, &instance_id, &updated_instance)?;
 
+    let mut event = cosmwasm_std::Event::new("autorujira-workflow-manager/execute_action")
+        .add_attribute("user_address", user_address)
+        .add_attribute("instance_id", instance_id.to_string())
+        .add_attribute("action_id", action_id);
+    if let Some(execution_id) = execution_id {
+        event = event.add_attribute("execution_id", execution_id);
+    }
+
     Ok(Response::new()
-        .add_messages(authz_msgs)
-        .add_event(
-            cosmwasm_std::Event::new("autorujira-workflow-manager/execute_action")
-                .add_attribute("user_address", user_address)
-                .add_attribute("instance_id", instance_id.to_string())
-                .add_attribute("action_id", action_id)
-        ))
+        .add_submessages(sub_msgs)
+        .add_event(event))
 }
 
-fn resolve_param_value(
-    param_value: &ActionParamValue,
-    user_addr: &Addr,
-    instance_params: &HashMap<ParamId, ActionParamValue>,
-    execute_action_params: &Option<HashMap<ParamId, ActionParamValue>>,
-) -> Result<ActionParamValue, ContractError> {
-    let value_str = match param_value {
-        ActionParamValue::String(s) => s,
-        ActionParamValue::BigInt(s) => s,
-    };
-
-    if value_str == "#ip.requester" {
-        Ok(ActionParamValue::String(user_addr.to_string()))
-    } else if value_str.starts_with("#ip.") {
-        // Extract the key after #ip.
-        let key = &value_str[4..];
-        if let Some(value) = instance_params.get(key) {
-            Ok(value.clone())
-        } else {
-            Err(ContractError::GenericError(format!(
-                "Parameter '{}' not found in instance parameters",
-                key
-            )))
-        }
-    } else if value_str.starts_with("#cp.") {
-        // Extract the key after #cp.
-        let key = &value_str[4..];
-        if let Some(params) = execute_action_params {
-            if let Some(value) = params.get(key) {
-                Ok(value.clone())
-            } else {
-                Err(ContractError::GenericError(format!(
-                    "Parameter '{}' not found in execute action parameters",
-                    key
-                )))
-            }
-        } else {
-            Err(ContractError::GenericError(
-                "Execute action parameters not provided".to_string(),
-            ))
-        }
-    } else {
-        Ok(param_value.clone()) // Fixed value
+// Checks the instance can run the action now: not expired, running and in sequence.
+// Returns whether the action starts a new run.
+pub fn validate_action_can_execute(
+    storage: &dyn cosmwasm_std::Storage,
+    env: &Env,
+    user_instance: &WorkflowInstance,
+    action_id: &ActionId,
+) -> Result<bool, ContractError> {
+    // Validate instance expiration time
+    if env.block.time >= user_instance.expiration_time {
+        return Err(ContractError::GenericError(
+            "Instance has expired".to_string(),
+        ));
     }
-}
 
-//=========== DYNAMIC TEMPLATE ACTION ============
-fn execute_dynamic_template(
-    storage: &dyn cosmwasm_std::Storage,
-    workflow_id: &str,
-    action_id: &str,
-    template_id: &TemplateId,
-    resolved_params: &HashMap<String, ActionParamValue>,
-    execute_action_params: &Option<HashMap<String, ActionParamValue>>,
-) -> Result<Vec<WasmMsg>, ContractError> {
-    // Load template for this action
-    let template = load_workflow_action_template(
-        storage,
-        &workflow_id.to_string(),
-        &action_id.to_string(),
-        &template_id.to_string(),
-    )
-    .map_err(|_| ContractError::TemplateNotFound {
-        workflow_id: workflow_id.to_string(),
-        action_id: action_id.to_string(),
-        template_id: template_id.to_string(),
-    })?;
+    // Instance must be running or finished (if recurrent)
+    if !(matches!(user_instance.state, WorkflowInstanceState::Running) || 
+        (matches!(user_instance.state, WorkflowInstanceState::Finished) && matches!(user_instance.execution_type, ExecutionType::Recurrent))) 
+        {
+        return Err(ContractError::GenericError(
+            "Instance is not running".to_string(),
+        ));
+    }
 
-    // Resolve template parameters
-    let resolved_contract =
-        resolve_template_parameter(&template.contract, resolved_params, execute_action_params)?;
-    let resolved_message =
-        resolve_template_parameter(&template.message, resolved_params, execute_action_params)?;
-    let resolved_funds =
-        resolve_template_funds(&template.funds, resolved_params, execute_action_params)?;
+    // Load workflow from user_instance.workflow_id
+    let workflow = load_workflow(storage, &user_instance.workflow_id)?;
 
-    // Validate that the resolved contract is whitelisted
-    validate_contract_is_whitelisted(
-        storage,
-        &workflow_id.to_string(),
-        &action_id.to_string(),
-        &resolved_contract,
-    )?;
+    // Ensure the action exists, in the sub-workflow for sub-workflow steps
+    let target = resolve_action_target(storage, &user_instance.workflow_id, action_id)?;
+    let _action_to_execute =
+        load_workflow_action(storage, &target.workflow_id, &target.action_id).map_err(
+            |_| ContractError::ActionNotFound {
+                workflow_id: user_instance.workflow_id.clone(),
+                action_id: action_id.clone(),
+            },
+        )?;
 
-    // Create the WasmMsg
-    let wasm_msg = WasmMsg::Execute {
-        contract_addr: resolved_contract,
-        msg: Binary::from(resolved_message.as_bytes()),
-        funds: resolved_funds,
-    };
+    if !executable_action_ids(storage, &workflow, user_instance)?.contains(action_id) {
+        return Err(ContractError::GenericError(
+            "Action cannot be executed: not first execution, not valid next action, and not recurrent start action".to_string()
+        ));
+    }
 
-    Ok(vec![wasm_msg])
+    // A start action executed from scratch or after an end action begins a new run
+    Ok(match &user_instance.last_executed_action {
+        None => true,
+        Some(last_executed_action_id) => {
+            completed_workflow_action(storage, &user_instance.workflow_id, last_executed_action_id)?
+                .is_some_and(|completed_action_id| workflow.end_actions.contains(&completed_action_id))
+                && expand_sub_workflow_actions(storage, &user_instance.workflow_id, &workflow.start_actions)?.contains(action_id)
+        }
+    })
 }
 
-fn resolve_template_parameter(
-    template_param: &str,
-    resolved_params: &HashMap<String, ActionParamValue>,
-    execute_action_params: &Option<HashMap<String, ActionParamValue>>,
-) -> Result<String, ContractError> {
-    let mut result = template_param.to_string();
+// Enforces the action's timing, returning the executions to record when it's rate limited
+pub fn check_action_timing(
+    storage: &dyn cosmwasm_std::Storage,
+    env: &Env,
+    user_addr: &Addr,
+    instance_id: &InstanceId,
+    user_instance: &WorkflowInstance,
+    action_id: &ActionId,
+    starts_new_run: bool,
+) -> Result<Option<Vec<cosmwasm_std::Timestamp>>, ContractError> {
+    let target = resolve_action_target(storage, &user_instance.workflow_id, action_id)?;
+    let Some(timing) = load_workflow_action_timing(storage, &target.workflow_id, &target.action_id)? else {
+        return Ok(None);
+    };
+    let now = env.block.time;
+    let violation = |reason: String| ContractError::ActionTimingViolation {
+        action_id: action_id.clone(),
+        reason,
+    };
 
-    // Replace {{param}} placeholders with resolved values
-    for (key, value) in resolved_params {
-        let placeholder = format!("{{{{{}}}}}", key);
-        let value_str = match value {
-            ActionParamValue::String(s) => s.clone(),
-            ActionParamValue::BigInt(s) => s.clone(),
-        };
-        result = result.replace(&placeholder, &value_str);
+    // Instances created before timings were tracked skip the window until their next run
+    let run_started_at = if starts_new_run { Some(now) } else { user_instance.run_started_at };
+    if let Some(run_started_at) = run_started_at {
+        let elapsed = now.seconds().saturating_sub(run_started_at.seconds());
+        if let Some(not_before) = timing.not_before.filter(|not_before| elapsed < *not_before) {
+            return Err(violation(format!("{} seconds into the run, not before {}", elapsed, not_before)));
+        }
+        if let Some(not_after) = timing.not_after.filter(|not_after| elapsed > *not_after) {
+            return Err(violation(format!("{} seconds into the run, not after {}", elapsed, not_after)));
+        }
     }
 
-    // Replace #cp.param placeholders with execute action params
-    if let Some(params) = execute_action_params {
-        for (key, value) in params {
-            let placeholder = format!("#cp.{}", key);
-            let value_str = match value {
-                ActionParamValue::String(s) => s.clone(),
-                ActionParamValue::BigInt(s) => s.clone(),
-            };
-            result = result.replace(&placeholder, &value_str);
+    // The run being replaced is the previous one
+    if let (Some(cooldown), Some(previous_run_started_at)) = (timing.cooldown, user_instance.run_started_at) {
+        let cooldown_ends_at = previous_run_started_at.plus_seconds(cooldown);
+        if starts_new_run && now < cooldown_ends_at {
+            return Err(violation(format!("cooldown until {}", cooldown_ends_at.seconds())));
         }
     }
 
-    Ok(result)
+    let Some(rate_limit) = timing.rate_limit else {
+        return Ok(None);
+    };
+    let mut executions = load_workflow_instance_action_executions(storage, user_addr, instance_id, action_id)?;
+    executions.retain(|executed_at| executed_at.plus_seconds(rate_limit.window) > now);
+    if executions.len() >= rate_limit.max_executions as usize {
+        return Err(violation(format!(
+            "{} executions in the last {} seconds",
+            executions.len(),
+            rate_limit.window
+        )));
+    }
+    executions.push(now);
+    Ok(Some(executions))
 }
 
-fn resolve_template_funds(
-    template_funds: &[(String, String)],
-    resolved_params: &HashMap<String, ActionParamValue>,
-    execute_action_params: &Option<HashMap<String, ActionParamValue>>,
-) -> Result<Vec<cosmwasm_std::Coin>, ContractError> {
-    let mut resolved_funds = Vec::new();
-
-    for (amount_template, denom_template) in template_funds {
+fn validate_action_timing_definition(
+    start_actions: &HashSet<ActionId>,
+    action_id: &ActionId,
+    timing: &ActionTiming,
+) -> Result<(), ContractError> {
+    if let (Some(not_before), Some(not_after)) = (timing.not_before, timing.not_after) {
+        if not_before > not_after {
+            return Err(ContractError::GenericError(format!(
+                "Action '{}' has not_before after not_after",
+                action_id
+            )));
+        }
+    }
+    if timing.cooldown.is_some() && !start_actions.contains(action_id) {
+        return Err(ContractError::GenericError(format!(
+            "Only start actions can have a cooldown, '{}' is not one",
+            action_id
+        )));
+    }
+    if let Some(rate_limit) = &timing.rate_limit {
+        if rate_limit.max_executions == 0 || rate_limit.window == 0 {
+            return Err(ContractError::GenericError(format!(
+                "Rate limit of action '{}' needs max_executions and window",
+                action_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Price triggered instances only begin a run while their price condition holds
+pub fn validate_price_trigger(
+    storage: &dyn cosmwasm_std::Storage,
+    querier: QuerierWrapper,
+    user_addr: &Addr,
+    instance_id: &InstanceId,
+) -> Result<(), ContractError> {
+    let Some(trigger) = load_workflow_instance_price_trigger(storage, user_addr, instance_id)? else {
+        return Ok(());
+    };
+
+    let price = match &trigger.source {
+        PriceSource::Oracle { symbol } => symbol.oracle_price(querier),
+        PriceSource::Pool { asset } => Layer1Asset::from_str(asset)
+            .map_err(|e| ContractError::GenericError(e.to_string()))?
+            .tor_price(querier),
+    }
+    .map_err(|e| ContractError::GenericError(format!("Trigger price not available: {}", e)))?;
+
+    let (met, condition) = match trigger.condition {
+        PriceCondition::Above => (price >= trigger.price, "above"),
+        PriceCondition::Below => (price <= trigger.price, "below"),
+    };
+    if !met {
+        return Err(ContractError::PriceTriggerNotMet {
+            price: price.to_string(),
+            condition: condition.to_string(),
+            trigger_price: trigger.price.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Actions the instance can run next: start actions first, then the next actions of the last
+// executed one, and start actions again after an end action of a recurrent instance.
+// Sub-workflow actions are replaced by the steps of their sub-workflow.
+pub fn executable_action_ids(
+    storage: &dyn cosmwasm_std::Storage,
+    workflow: &Workflow,
+    user_instance: &WorkflowInstance,
+) -> Result<HashSet<ActionId>, ContractError> {
+    let Some(last_executed_action_id) = &user_instance.last_executed_action else {
+        return expand_sub_workflow_actions(storage, &user_instance.workflow_id, &workflow.start_actions);
+    };
+    let action_not_found = |_| ContractError::ActionNotFound {
+        workflow_id: user_instance.workflow_id.clone(),
+        action_id: last_executed_action_id.clone(),
+    };
+
+    let mut action_ids = HashSet::new();
+    let target = resolve_action_target(storage, &user_instance.workflow_id, last_executed_action_id)?;
+    if let Some((parent_action_id, _)) = &target.sub_workflow {
+        let sub_action = load_workflow_action(storage, &target.workflow_id, &target.action_id).map_err(action_not_found)?;
+        action_ids.extend(sub_action.next_actions.iter().map(|next_action_id| sub_workflow_step_id(parent_action_id, next_action_id)));
+    }
+
+    if let Some(completed_action_id) = completed_workflow_action(storage, &user_instance.workflow_id, last_executed_action_id)? {
+        let completed_action = load_workflow_action(storage, &user_instance.workflow_id, &completed_action_id).map_err(action_not_found)?;
+        action_ids.extend(expand_sub_workflow_actions(storage, &user_instance.workflow_id, &completed_action.next_actions)?);
+        if user_instance.execution_type == ExecutionType::Recurrent
+            && workflow.end_actions.contains(&completed_action_id)
+        {
+            action_ids.extend(expand_sub_workflow_actions(storage, &user_instance.workflow_id, &workflow.start_actions)?);
+        }
+    }
+    Ok(action_ids)
+}
+
+// Every action definition an instance of the workflow can execute, including sub-workflow actions
+pub fn workflow_action_definitions(
+    storage: &dyn cosmwasm_std::Storage,
+    workflow_id: &WorkflowId,
+) -> Result<Vec<(WorkflowId, ActionId)>, ContractError> {
+    let mut definitions = vec![];
+    for action_id in load_workflow_actions(storage, workflow_id)?.into_keys() {
+        if let Some(sub_workflow) = load_workflow_action_sub_workflow(storage, workflow_id, &action_id)? {
+            for sub_action_id in load_workflow_actions(storage, &sub_workflow.workflow_id)?.into_keys() {
+                definitions.push((sub_workflow.workflow_id.clone(), sub_action_id));
+            }
+        }
+        definitions.push((workflow_id.clone(), action_id));
+    }
+    Ok(definitions)
+}
+
+// Separates a sub-workflow action from the sub-workflow action being executed
+pub const SUB_WORKFLOW_SEPARATOR: char = '.';
+
+/// Definition an action id of an instance executes: a workflow action, or for a sub-workflow
+/// step (<action_id>.<sub_action_id>) the action of the sub-workflow
+pub struct ActionTarget {
+    pub workflow_id: WorkflowId,
+    pub action_id: ActionId,
+    // Action of the instance workflow running the sub-workflow, with its definition
+    pub sub_workflow: Option<(ActionId, SubWorkflow)>,
+}
+
+pub fn resolve_action_target(
+    storage: &dyn cosmwasm_std::Storage,
+    workflow_id: &WorkflowId,
+    action_id: &ActionId,
+) -> Result<ActionTarget, ContractError> {
+    if let Some((parent_action_id, sub_action_id)) = action_id.split_once(SUB_WORKFLOW_SEPARATOR) {
+        let parent_action_id = parent_action_id.to_string();
+        if let Some(sub_workflow) = load_workflow_action_sub_workflow(storage, workflow_id, &parent_action_id)? {
+            return Ok(ActionTarget {
+                workflow_id: sub_workflow.workflow_id.clone(),
+                action_id: sub_action_id.to_string(),
+                sub_workflow: Some((parent_action_id, sub_workflow)),
+            });
+        }
+    }
+    Ok(ActionTarget {
+        workflow_id: workflow_id.clone(),
+        action_id: action_id.clone(),
+        sub_workflow: None,
+    })
+}
+
+fn sub_workflow_step_id(parent_action_id: &str, sub_action_id: &str) -> ActionId {
+    format!("{}{}{}", parent_action_id, SUB_WORKFLOW_SEPARATOR, sub_action_id)
+}
+
+// Replaces sub-workflow actions by the start steps of their sub-workflow
+fn expand_sub_workflow_actions(
+    storage: &dyn cosmwasm_std::Storage,
+    workflow_id: &WorkflowId,
+    action_ids: &HashSet<ActionId>,
+) -> Result<HashSet<ActionId>, ContractError> {
+    let mut expanded = HashSet::new();
+    for action_id in action_ids {
+        match load_workflow_action_sub_workflow(storage, workflow_id, action_id)? {
+            Some(sub_workflow) => {
+                let sub_workflow_definition = load_workflow(storage, &sub_workflow.workflow_id)?;
+                expanded.extend(sub_workflow_definition.start_actions.iter().map(|start_action_id| sub_workflow_step_id(action_id, start_action_id)));
+            }
+            None => {
+                expanded.insert(action_id.clone());
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+// Workflow action completed by an executed action id: the action itself, or the sub-workflow
+// action once its sub-workflow reached an end action
+pub fn completed_workflow_action(
+    storage: &dyn cosmwasm_std::Storage,
+    workflow_id: &WorkflowId,
+    action_id: &ActionId,
+) -> Result<Option<ActionId>, ContractError> {
+    let target = resolve_action_target(storage, workflow_id, action_id)?;
+    match target.sub_workflow {
+        Some((parent_action_id, _)) => {
+            let sub_workflow_definition = load_workflow(storage, &target.workflow_id)?;
+            Ok(sub_workflow_definition.end_actions.contains(&target.action_id).then_some(parent_action_id))
+        }
+        None => Ok(Some(target.action_id)),
+    }
+}
+
+// Resolves the action params against the instance, the executor params and the run outputs.
+// Sub-workflow steps see the params mapped by their sub-workflow action as instance params.
+pub fn resolve_action_params(
+    storage: &dyn cosmwasm_std::Storage,
+    querier: QuerierWrapper,
+    user_addr: &Addr,
+    instance_id: &InstanceId,
+    target: &ActionTarget,
+    params: &Option<HashMap<String, ActionParamValue>>,
+    run_outputs: &HashMap<String, String>,
+) -> Result<HashMap<String, ActionParamValue>, ContractError> {
+    // Get action parameters and create new HashMap
+    let action_params =
+        load_workflow_action_params(storage, &target.workflow_id, &target.action_id)?;
+    let mut instance_params = load_workflow_instance_params(storage, user_addr, instance_id)?;
+    if let Some((_, sub_workflow)) = &target.sub_workflow {
+        let sources = ParamSources { storage, querier, user_addr, target, instance_params: &instance_params, execute_action_params: params, run_outputs };
+        instance_params = sub_workflow.params
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), resolve_param_value(value, &sources)?)))
+            .collect::<Result<_, ContractError>>()?;
+    }
+    let mut resolved_params = HashMap::<String, ActionParamValue>::new();
+
+    // Workflow and instance constraints on #cp params, both must hold
+    let mut param_constraints: HashMap<ParamId, Vec<ParamConstraint>> = HashMap::new();
+    let workflow_constraints =
+        load_workflow_action_param_constraints(storage, &target.workflow_id, &target.action_id)?;
+    let instance_constraints = load_workflow_instance_param_constraints(storage, user_addr, instance_id)?;
+    for (param_id, constraint) in workflow_constraints.into_iter().chain(instance_constraints) {
+        param_constraints.entry(param_id).or_default().push(constraint);
+    }
+
+    // Templates can reference #cp params directly, so every constrained param sent is checked
+    if let Some(params) = params {
+        for (key, value) in params {
+            validate_param_constraints(key, value, &param_constraints, &instance_params)?;
+        }
+    }
+
+    let sources = ParamSources { storage, querier, user_addr, target, instance_params: &instance_params, execute_action_params: params, run_outputs };
+    for (key, value) in action_params {
+        // si param.value es #ip.requester => busco user_instance.requester
+        // si param.value comienza con #ip, busco en user_instance.params
+        // si param.value comienza con #cp, busco en execute_action_params
+        // si param.value comienza con #out, busco en los outputs del run actual
+        // si param.value comienza con #bal, #q o #price, se consulta la cadena
+        // else es un valor fijo
+        let resolved_value = resolve_param_value(&value, &sources)?;
+        resolved_params.insert(key.clone(), resolved_value);
+    }
+
+    Ok(resolved_params)
+}
+
+// Adds the funds of the messages to the instance's spends, failing if a spend limit would be exceeded.
+// Returns the updated spends of each limited denom, without saving them.
+pub fn check_instance_spends(
+    storage: &dyn cosmwasm_std::Storage,
+    env: &Env,
+    user_addr: &Addr,
+    instance_id: &InstanceId,
+    msgs: &[WasmMsg],
+) -> Result<Vec<(String, Vec<Spend>)>, ContractError> {
+    let limits = load_workflow_instance_spend_limits(storage, user_addr, instance_id)?;
+    let mut updated_spends = vec![];
+    if limits.is_empty() {
+        return Ok(updated_spends);
+    }
+
+    let mut funds: BTreeMap<String, Uint128> = BTreeMap::new();
+    for msg in msgs {
+        if let WasmMsg::Execute { funds: msg_funds, .. } = msg {
+            for coin in msg_funds {
+                *funds.entry(coin.denom.clone()).or_default() += coin.amount;
+            }
+        }
+    }
+
+    for (denom, amount) in funds {
+        let denom_limits: Vec<&SpendLimit> = limits.iter().filter(|limit| limit.denom == denom).collect();
+        if denom_limits.is_empty() {
+            continue;
+        }
+
+        // Drop the spends no limit covers anymore, limits without period keep them all
+        let mut spends = load_workflow_instance_spends(storage, user_addr, instance_id, &denom)?;
+        if denom_limits.iter().all(|limit| limit.period.is_some()) {
+            let longest_period = denom_limits.iter().filter_map(|limit| limit.period).max().unwrap_or_default();
+            spends.retain(|spend| spend.at.plus_seconds(longest_period) > env.block.time);
+        }
+
+        for limit in denom_limits {
+            let spent = spent_within_limit(&spends, limit, &env.block.time);
+            if spent + amount > limit.amount {
+                return Err(ContractError::SpendLimitExceeded {
+                    denom,
+                    limit: limit.amount.to_string(),
+                    spent: spent.to_string(),
+                });
+            }
+        }
+
+        spends.push(Spend {
+            at: env.block.time,
+            amount,
+        });
+        updated_spends.push((denom, spends));
+    }
+    Ok(updated_spends)
+}
+
+fn validate_param_constraint_definitions(
+    constraints: &HashMap<ParamId, ParamConstraint>,
+) -> Result<(), ContractError> {
+    for (param_id, constraint) in constraints {
+        if let Some(pattern) = &constraint.regex {
+            Regex::new(pattern).map_err(|e| ContractError::GenericError(format!(
+                "Invalid regex for parameter '{}': {}",
+                param_id, e
+            )))?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_param_constraints(
+    param_id: &str,
+    param_value: &ActionParamValue,
+    constraints: &HashMap<ParamId, Vec<ParamConstraint>>,
+    instance_params: &HashMap<ParamId, ActionParamValue>,
+) -> Result<(), ContractError> {
+    let Some(constraints) = constraints.get(param_id) else {
+        return Ok(());
+    };
+    let value_str = &render_param_value(param_id, param_value)?;
+    let violation = |reason: String| ContractError::ParamConstraintViolation {
+        param: param_id.to_string(),
+        reason,
+    };
+
+    for constraint in constraints {
+        if let Some(allowed_values) = &constraint.allowed_values {
+            if !allowed_values.contains(value_str) {
+                return Err(violation(format!("value '{}' is not allowed", value_str)));
+            }
+        }
+
+        if constraint.min.is_some() || constraint.max.is_some() {
+            let value = Decimal256::from_str(value_str)
+                .map_err(|_| violation(format!("value '{}' is not a number", value_str)))?;
+            if let Some(min) = &constraint.min {
+                let min = resolve_param_bound(min, instance_params).map_err(violation)?;
+                if value < min {
+                    return Err(violation(format!("value {} is below minimum {}", value, min)));
+                }
+            }
+            if let Some(max) = &constraint.max {
+                let max = resolve_param_bound(max, instance_params).map_err(violation)?;
+                if value > max {
+                    return Err(violation(format!("value {} is above maximum {}", value, max)));
+                }
+            }
+        }
+
+        if let Some(pattern) = &constraint.regex {
+            let regex = Regex::new(pattern).map_err(|e| violation(e.to_string()))?;
+            let matches_whole_value = regex
+                .find(value_str)
+                .map(|m| m.start() == 0 && m.end() == value_str.len())
+                .unwrap_or(false);
+            if !matches_whole_value {
+                return Err(violation(format!("value '{}' does not match '{}'", value_str, pattern)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_param_bound(
+    bound: &ParamBound,
+    instance_params: &HashMap<ParamId, ActionParamValue>,
+) -> Result<Decimal256, String> {
+    match bound {
+        ParamBound::Value(value) => Ok(*value),
+        ParamBound::InstanceParam { param, ratio } => {
+            let value = match instance_params.get(param) {
+                Some(value) => render_param_value(param, value).map_err(|e| e.to_string())?,
+                None => return Err(format!("bound parameter '{}' not found in instance parameters", param)),
+            };
+            let value = Decimal256::from_str(&value)
+                .map_err(|_| format!("bound parameter '{}' is not a number", param))?;
+            value.checked_mul(*ratio).map_err(|e| e.to_string())
+        }
+    }
+}
+
+const PARAM_ARITHMETIC_OPERATORS: [char; 4] = ['*', '/', '+', '-'];
+
+// Splits "<reference> <operator> <operand>", operators must be surrounded by spaces
+fn split_param_arithmetic(value_str: &str) -> Option<(&str, char, &str)> {
+    if !value_str.starts_with('#') {
+        return None;
+    }
+    PARAM_ARITHMETIC_OPERATORS.iter().find_map(|operator| {
+        value_str
+            .split_once(&format!(" {} ", operator))
+            .map(|(reference, operand)| (reference.trim(), *operator, operand.trim()))
+    })
+}
+
+// Integer values stay integers (rounded down), so amounts can be sent to contracts as-is
+fn apply_param_arithmetic(value: &ActionParamValue, operator: char, operand: &str) -> Result<ActionParamValue, ContractError> {
+    let invalid = |reason: String| ContractError::GenericError(format!("Invalid arithmetic '{} {}': {}", operator, operand, reason));
+    let rendered = render_param_value("arithmetic", value)?;
+    let base = Decimal256::from_str(&rendered).map_err(|_| invalid(format!("'{}' is not a number", rendered)))?;
+    let operand = match operand.strip_suffix('%') {
+        Some(percentage) => Decimal256::from_str(percentage).map(|percentage| percentage / Decimal256::percent(10000)),
+        None => Decimal256::from_str(operand),
+    }
+    .map_err(|_| invalid("operand is not a number".to_string()))?;
+
+    let result = match operator {
+        '*' => base.checked_mul(operand).map_err(|e| invalid(e.to_string()))?,
+        '/' => base.checked_div(operand).map_err(|e| invalid(e.to_string()))?,
+        '+' => base.checked_add(operand).map_err(|e| invalid(e.to_string()))?,
+        _ => base.checked_sub(operand).map_err(|e| invalid(e.to_string()))?,
+    };
+    // The result keeps the type of the value, strings without a decimal point are integers
+    let is_integer = match value {
+        ActionParamValue::BigInt(_) | ActionParamValue::Uint128(_) => true,
+        ActionParamValue::String(s) => !s.contains('.'),
+        _ => false,
+    };
+    if is_integer {
+        Ok(ActionParamValue::BigInt(result.to_uint_floor().to_string()))
+    } else {
+        Ok(ActionParamValue::Decimal(Decimal::from_str(&result.to_string()).map_err(|e| invalid(e.to_string()))?))
+    }
+}
+
+// "#q.<contract>.<query>.<path>" sends {"<query>":{}} to a whitelisted contract and reads the
+// response at the dotted path (object keys or array indexes)
+fn resolve_query_param(query: &str, sources: &ParamSources) -> Result<ActionParamValue, ContractError> {
+    let mut segments = query.split('.');
+    let (Some(contract), Some(query_name)) = (segments.next(), segments.next()) else {
+        return Err(ContractError::GenericError(format!("Invalid query '#q.{}'", query)));
+    };
+    validate_contract_is_whitelisted(sources.storage, &sources.target.workflow_id, &sources.target.action_id, contract)?;
+
+    let query_msg = serde_json::json!({ query_name: {} });
+    let response: serde_json::Value = sources.querier.query_wasm_smart(contract, &query_msg)?;
+    let mut value = &response;
+    for segment in segments {
+        value = match value {
+            serde_json::Value::Array(items) => segment.parse::<usize>().ok().and_then(|index| items.get(index)),
+            _ => value.get(segment),
+        }
+        .ok_or_else(|| ContractError::GenericError(format!("Query '#q.{}' has no value at '{}'", query, segment)))?;
+    }
+
+    Ok(match value {
+        serde_json::Value::String(s) => ActionParamValue::String(s.clone()),
+        serde_json::Value::Number(n) => ActionParamValue::String(n.to_string()),
+        serde_json::Value::Bool(b) => ActionParamValue::Bool(*b),
+        other => ActionParamValue::Json(other.to_string()),
+    })
+}
+
+// Everything a param value can reference
+struct ParamSources<'a> {
+    storage: &'a dyn cosmwasm_std::Storage,
+    querier: QuerierWrapper<'a>,
+    user_addr: &'a Addr,
+    // Action whose whitelist limits the contracts #q can query
+    target: &'a ActionTarget,
+    instance_params: &'a HashMap<ParamId, ActionParamValue>,
+    execute_action_params: &'a Option<HashMap<ParamId, ActionParamValue>>,
+    run_outputs: &'a HashMap<String, String>,
+}
+
+fn resolve_param_value(
+    param_value: &ActionParamValue,
+    sources: &ParamSources,
+) -> Result<ActionParamValue, ContractError> {
+    let ParamSources { user_addr, instance_params, execute_action_params, run_outputs, .. } = *sources;
+
+    // Typed values are always fixed
+    let (ActionParamValue::String(value_str) | ActionParamValue::BigInt(value_str)) = param_value else {
+        return Ok(param_value.clone());
+    };
+
+    // A reference can be followed by one arithmetic operation, e.g. "#bal.rune * 50%"
+    if let Some((reference, operator, operand)) = split_param_arithmetic(value_str) {
+        let value = resolve_param_value(&ActionParamValue::String(reference.to_string()), sources)?;
+        return apply_param_arithmetic(&value, operator, operand);
+    }
+
+    let resolved_value = if value_str == "#ip.requester" {
+        Ok(ActionParamValue::String(user_addr.to_string()))
+    } else if value_str.starts_with("#ip.") {
+        // Extract the key after #ip.
+        let key = &value_str[4..];
+        if let Some(value) = instance_params.get(key) {
+            Ok(value.clone())
+        } else {
+            Err(ContractError::GenericError(format!(
+                "Parameter '{}' not found in instance parameters",
+                key
+            )))
+        }
+    } else if value_str.starts_with("#cp.") {
+        // Extract the key after #cp.
+        let key = &value_str[4..];
+        if let Some(params) = execute_action_params {
+            if let Some(value) = params.get(key) {
+                Ok(value.clone())
+            } else {
+                Err(ContractError::GenericError(format!(
+                    "Parameter '{}' not found in execute action parameters",
+                    key
+                )))
+            }
+        } else {
+            Err(ContractError::GenericError(
+                "Execute action parameters not provided".to_string(),
+            ))
+        }
+    } else if let Some(key) = value_str.strip_prefix("#out.") {
+        // The key after #out. is <action_id>.<output_id>
+        if let Some(value) = run_outputs.get(key) {
+            Ok(ActionParamValue::String(value.clone()))
+        } else {
+            Err(ContractError::GenericError(format!(
+                "Output '{}' not found in current run",
+                key
+            )))
+        }
+    } else if let Some(denom) = value_str.strip_prefix("#bal.") {
+        // The user's bank balance at execution time
+        let balance = sources.querier.query_balance(user_addr, denom)?;
+        Ok(ActionParamValue::Uint128(balance.amount))
+    } else if let Some(symbol) = value_str.strip_prefix("#price.") {
+        let price = symbol.to_string().oracle_price(sources.querier).map_err(|e| {
+            ContractError::GenericError(format!("Price of '{}' not available: {}", symbol, e))
+        })?;
+        Ok(ActionParamValue::Decimal(price))
+    } else if let Some(query) = value_str.strip_prefix("#q.") {
+        resolve_query_param(query, sources)
+    } else {
+        Ok(param_value.clone()) // Fixed value
+    }?;
+
+    // A BigInt referencing a plain string keeps its type, so it's still checked when rendered
+    Ok(match (param_value, resolved_value) {
+        (ActionParamValue::BigInt(_), ActionParamValue::String(s)) => ActionParamValue::BigInt(s),
+        (_, resolved_value) => resolved_value,
+    })
+}
+
+/// Handle reply from an action dispatched by execute_action
+pub fn handle_action_reply(
+    deps: DepsMut,
+    _env: Env,
+    reply: Reply,
+) -> Result<Response, ContractError> {
+    let reply_data = ACTION_REPLY_DATA.load(deps.storage, reply.id)?;
+    ACTION_REPLY_DATA.remove(deps.storage, reply.id);
+
+    let mut instance = load_workflow_instance(deps.storage, &reply_data.user_address, &reply_data.instance_id)?;
+
+    let event = match &reply.result {
+        cosmwasm_std::SubMsgResult::Ok(_) => {
+            instance.last_run_outcome = Some(RunOutcome::Succeeded);
+            instance.consecutive_failures = 0;
+
+            // Every action has a first message, so the action is counted once
+            if reply.id == ACTION_REPLY_ID_START {
+                record_workflow_action_executed(deps.storage, &reply_data.workflow_id)?;
+            }
+
+            if !reply_data.execution_fee.is_zero() {
+                let accrued = load_user_accrued_execution_fees(deps.storage, &reply_data.user_address)?;
+                save_user_accrued_execution_fees(deps.storage, &reply_data.user_address, accrued + reply_data.execution_fee)?;
+                if let Some(executor) = &reply_data.executor {
+                    let executor_accrued = load_executor_accrued_execution_fees(deps.storage, &reply_data.user_address, executor)?;
+                    save_executor_accrued_execution_fees(deps.storage, &reply_data.user_address, executor, executor_accrued + reply_data.execution_fee)?;
+                }
+            }
+
+            // Capture the outputs declared by the action so later actions can reference them
+            let target = resolve_action_target(deps.storage, &reply_data.workflow_id, &reply_data.action_id)?;
+            let outputs = load_workflow_action_outputs(deps.storage, &target.workflow_id, &target.action_id)?;
+            let mut captured_outputs = Vec::new();
+            if !outputs.is_empty() {
+                let mut run_outputs = load_workflow_instance_run_outputs(deps.storage, &reply_data.user_address, &reply_data.instance_id)?;
+                for (output_id, output) in outputs {
+                    let value = match &output.filter {
+                        Some((filter_key, filter_value)) => extract_attribute_from_reply_with_filters(
+                            &reply,
+                            &output.event_type,
+                            filter_key,
+                            filter_value,
+                            &output.attribute_key,
+                        ),
+                        None => extract_attribute_from_reply(&reply, &output.event_type, &output.attribute_key),
+                    };
+                    if let Some(value) = value {
+                        captured_outputs.push(output_id.clone());
+                        run_outputs.insert(format!("{}.{}", reply_data.action_id, output_id), value);
+                    }
+                }
+                save_workflow_instance_run_outputs(deps.storage, &reply_data.user_address, &reply_data.instance_id, &run_outputs)?;
+            }
+            captured_outputs.sort();
+
+            // The run is complete, so another executor can pick up the next one
+            let workflow = load_workflow(deps.storage, &reply_data.workflow_id)?;
+            if completed_workflow_action(deps.storage, &reply_data.workflow_id, &reply_data.action_id)?
+                .is_some_and(|completed_action_id| workflow.end_actions.contains(&completed_action_id))
+            {
+                remove_workflow_instance_lease(deps.storage, &reply_data.user_address, &reply_data.instance_id)?;
+            }
+
+            cosmwasm_std::Event::new("autorujira-workflow-manager/action-succeeded")
+                .add_attribute("outputs", captured_outputs.join(","))
+        }
+        cosmwasm_std::SubMsgResult::Err(error_msg) => {
+            // The action did not happen, so the instance goes back to where it was
+            // and the same execution can be submitted again
+            instance.last_executed_action = reply_data.previous_action.clone();
+            instance.run_started_at = reply_data.previous_run_started_at;
+            if let Some(executions) = &reply_data.previous_action_executions {
+                save_workflow_instance_action_executions(deps.storage, &reply_data.user_address, &reply_data.instance_id, &reply_data.action_id, executions)?;
+            }
+            if let Some(usage) = &reply_data.previous_creator_fee_usage {
+                save_workflow_instance_creator_fee_usage(deps.storage, &reply_data.user_address, &reply_data.instance_id, usage)?;
+            }
+            for (denom, spends) in &reply_data.previous_spends {
+                save_workflow_instance_spends(deps.storage, &reply_data.user_address, &reply_data.instance_id, denom, spends)?;
+            }
+            if let Some(execution_id) = &reply_data.execution_id {
+                remove_workflow_instance_execution(deps.storage, &reply_data.user_address, &reply_data.instance_id, execution_id)?;
+            }
+            instance.last_run_outcome = Some(RunOutcome::Failed {
+                action_id: reply_data.action_id.clone(),
+                error: error_msg.clone(),
+            });
+            instance.consecutive_failures += 1;
+
+            // Pause the instance if it keeps failing
+            let config = load_config(deps.storage)?;
+            let paused = match config.max_consecutive_failures {
+                Some(max_failures) => {
+                    instance.consecutive_failures >= max_failures
+                        && matches!(instance.state, WorkflowInstanceState::Running)
+                }
+                None => false,
+            };
+            if paused {
+                instance.state = WorkflowInstanceState::Paused;
+            }
+
+            cosmwasm_std::Event::new("autorujira-workflow-manager/action-failed")
+                .add_attribute("error", error_msg.clone())
+                .add_attribute("consecutive_failures", instance.consecutive_failures.to_string())
+                .add_attribute("paused", paused.to_string())
+        }
+    };
+
+    save_workflow_instance(deps.storage, &reply_data.user_address, &reply_data.instance_id, &instance)?;
+
+    Ok(Response::new()
+        .add_event(
+            event
+                .add_attribute("user_address", reply_data.user_address.to_string())
+                .add_attribute("instance_id", reply_data.instance_id.to_string())
+                .add_attribute("action_id", reply_data.action_id)
+        ))
+}
+
+// Sub-workflows are one level deep and run in place of the action's templates
+fn validate_sub_workflow(
+    storage: &dyn cosmwasm_std::Storage,
+    info: &MessageInfo,
+    action_id: &ActionId,
+    action: &ActionMsg,
+    sub_workflow: &SubWorkflow,
+) -> Result<(), ContractError> {
+    // Sub-workflow steps are addressed as <action_id>.<sub_action_id>
+    if action_id.contains(SUB_WORKFLOW_SEPARATOR) {
+        return Err(ContractError::GenericError(format!(
+            "Sub-workflow action '{}' can't contain '{}'",
+            action_id, SUB_WORKFLOW_SEPARATOR
+        )));
+    }
+    if !action.templates.is_empty() {
+        return Err(ContractError::GenericError(format!(
+            "Sub-workflow action '{}' can't have templates",
+            action_id
+        )));
+    }
+
+    let workflow = load_workflow(storage, &sub_workflow.workflow_id).map_err(|_| {
+        ContractError::WorkflowNotFound {
+            workflow_id: sub_workflow.workflow_id.clone(),
+        }
+    })?;
+    if !matches!(workflow.state, WorkflowState::Approved) {
+        return Err(ContractError::WorkflowNotApproved {
+            workflow_id: sub_workflow.workflow_id.clone(),
+        });
+    }
+    if matches!(workflow.visibility, WorkflowVisibility::Private) && info.sender != workflow.publisher {
+        return Err(ContractError::PrivateWorkflowExecutionDenied {
+            workflow_id: sub_workflow.workflow_id.clone(),
+        });
+    }
+    for sub_action_id in load_workflow_actions(storage, &sub_workflow.workflow_id)?.keys() {
+        if load_workflow_action_sub_workflow(storage, &sub_workflow.workflow_id, sub_action_id)?.is_some() {
+            return Err(ContractError::GenericError(format!(
+                "Workflow '{}' has sub-workflows and can't be used as a sub-workflow",
+                sub_workflow.workflow_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+//=========== DYNAMIC TEMPLATE ACTION ============
+fn validate_min_return_protection(
+    api: &dyn cosmwasm_std::Api,
+    action_id: &ActionId,
+    action: &ActionMsg,
+    protection: &MinReturnProtection,
+) -> Result<(), ContractError> {
+    if action.templates.is_empty() {
+        return Err(ContractError::GenericError(format!(
+            "Action '{}' has no templates to protect",
+            action_id
+        )));
+    }
+    if protection.max_slippage_bps > 10000 {
+        return Err(ContractError::GenericError(format!(
+            "Max slippage of action '{}' can't exceed 10000 bps",
+            action_id
+        )));
+    }
+    if protection.offer_symbol.is_empty() || protection.ask_symbol.is_empty() {
+        return Err(ContractError::GenericError(format!(
+            "Oracle symbols of action '{}' can't be empty",
+            action_id
+        )));
+    }
+    validate_param_value(api, "ask_denom", &ActionParamValue::Denom(protection.ask_denom.clone()))
+}
+
+// Writes the oracle-derived min_return into the action's swap messages, replacing whatever the
+// template rendered so the user doesn't depend on the executor's slippage choice
+pub fn apply_min_return_protection(
+    storage: &dyn cosmwasm_std::Storage,
+    querier: QuerierWrapper,
+    target: &ActionTarget,
+    msgs: Vec<WasmMsg>,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    let Some(protection) = load_workflow_action_min_return_protection(storage, &target.workflow_id, &target.action_id)? else {
+        return Ok(msgs);
+    };
+
+    // Ask units received per offered unit
+    let price = [protection.offer_symbol.clone(), protection.ask_symbol.clone()]
+        .oracle_price(querier)
+        .map_err(|e| {
+            ContractError::GenericError(format!(
+                "Price of '{}' in '{}' not available: {}",
+                protection.offer_symbol, protection.ask_symbol, e
+            ))
+        })?;
+    let max_slippage = Decimal::from_ratio(protection.max_slippage_bps, 10000u128);
+    let min_rate = price * (Decimal::one() - max_slippage);
+
+    let mut protected_msgs = Vec::with_capacity(msgs.len());
+    let mut swaps = 0;
+    for msg in msgs {
+        let WasmMsg::Execute { contract_addr, msg, funds } = msg else {
+            protected_msgs.push(msg);
+            continue;
+        };
+        let mut value: serde_json::Value = serde_json::from_slice(msg.as_slice())
+            .map_err(|e| ContractError::GenericError(format!("Invalid message: {}", e)))?;
+        let Some(swap) = value.get_mut("swap").and_then(|swap| swap.as_object_mut()) else {
+            protected_msgs.push(WasmMsg::Execute { contract_addr, msg, funds });
+            continue;
+        };
+
+        let [offer] = funds.as_slice() else {
+            return Err(ContractError::GenericError(format!(
+                "Swap on {} must send exactly one coin to compute its min return",
+                contract_addr
+            )));
+        };
+        let min_return = offer.amount.mul_floor(min_rate);
+        let min_return = match protection.venue {
+            SwapVenue::Fin => serde_json::to_value(min_return),
+            SwapVenue::Bow | SwapVenue::ThorchainSwap => serde_json::to_value(Coin::new(min_return, protection.ask_denom.clone())),
+        }
+        .map_err(|e| ContractError::GenericError(e.to_string()))?;
+        swap.insert("min_return".to_string(), min_return);
+
+        swaps += 1;
+        protected_msgs.push(WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_vec(&value)?.into(),
+            funds,
+        });
+    }
+
+    if swaps == 0 {
+        return Err(ContractError::GenericError(format!(
+            "Action '{}' has no swap message to protect",
+            target.action_id
+        )));
+    }
+    Ok(protected_msgs)
+}
+
+pub fn execute_dynamic_template(
+    storage: &dyn cosmwasm_std::Storage,
+    workflow_id: &str,
+    action_id: &str,
+    template_id: &TemplateId,
+    resolved_params: &HashMap<String, ActionParamValue>,
+    execute_action_params: &Option<HashMap<String, ActionParamValue>>,
+    run_outputs: &HashMap<String, String>,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    // Load template for this action
+    let template = load_workflow_action_template(
+        storage,
+        &workflow_id.to_string(),
+        &action_id.to_string(),
+        &template_id.to_string(),
+    )
+    .map_err(|_| ContractError::TemplateNotFound {
+        workflow_id: workflow_id.to_string(),
+        action_id: action_id.to_string(),
+        template_id: template_id.to_string(),
+    })?;
+
+    // Resolve template parameters
+    let resolved_contract =
+        resolve_template_parameter(&template.contract, resolved_params, execute_action_params, run_outputs)?;
+    let resolved_message =
+        resolve_template_parameter(&template.message, resolved_params, execute_action_params, run_outputs)?;
+    let resolved_funds =
+        resolve_template_funds(&template.funds, resolved_params, execute_action_params, run_outputs)?;
+
+    // Validate that the resolved contract is whitelisted
+    validate_contract_is_whitelisted(
+        storage,
+        &workflow_id.to_string(),
+        &action_id.to_string(),
+        &resolved_contract,
+    )?;
+    validate_contract_not_paused(storage, &resolved_contract)?;
+
+    // Validate that the rendered message is one the contract allows
+    validate_message_is_allowed(
+        storage,
+        &workflow_id.to_string(),
+        &action_id.to_string(),
+        &resolved_contract,
+        &resolved_message,
+    )?;
+
+    // Create the WasmMsg
+    let wasm_msg = WasmMsg::Execute {
+        contract_addr: resolved_contract,
+        msg: Binary::from(resolved_message.as_bytes()),
+        funds: resolved_funds,
+    };
+
+    Ok(vec![wasm_msg])
+}
+
+// Placeholders that only resolve in action params, templates would send them literally
+const PARAM_ONLY_PLACEHOLDERS: [&str; 4] = ["#ip.", "#bal.", "#q.", "#price."];
+
+fn validate_template_placeholders(action_id: &ActionId, action: &ActionMsg) -> Result<(), ContractError> {
+    for (template_id, template) in &action.templates {
+        let fields = [&template.contract, &template.message]
+            .into_iter()
+            .chain(template.funds.iter().flat_map(|(amount, denom)| [amount, denom]));
+        for field in fields {
+            if let Some(placeholder) = PARAM_ONLY_PLACEHOLDERS.iter().find(|placeholder| field.contains(*placeholder)) {
+                return Err(ContractError::GenericError(format!(
+                    "Template '{}' of action '{}' can't use '{}', reference it from an action param instead",
+                    template_id, action_id, placeholder
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn resolve_template_parameter(
+    template_param: &str,
+    resolved_params: &HashMap<String, ActionParamValue>,
+    execute_action_params: &Option<HashMap<String, ActionParamValue>>,
+    run_outputs: &HashMap<String, String>,
+) -> Result<String, ContractError> {
+    let mut result = template_param.to_string();
+
+    // Replace {{param}} placeholders with resolved values
+    for (key, value) in resolved_params {
+        let placeholder = format!("{{{{{}}}}}", key);
+        if result.contains(&placeholder) {
+            result = result.replace(&placeholder, &render_param_value(key, value)?);
+        }
+    }
+
+    // Replace #cp.param placeholders with execute action params
+    if let Some(params) = execute_action_params {
+        for (key, value) in params {
+            let placeholder = format!("#cp.{}", key);
+            if result.contains(&placeholder) {
+                result = result.replace(&placeholder, &render_param_value(key, value)?);
+            }
+        }
+    }
+
+    // Replace #out.action.output placeholders with outputs of the current run
+    for (key, value) in run_outputs {
+        let placeholder = format!("#out.{}", key);
+        result = result.replace(&placeholder, value);
+    }
+
+    Ok(result)
+}
+
+fn resolve_template_funds(
+    template_funds: &[(String, String)],
+    resolved_params: &HashMap<String, ActionParamValue>,
+    execute_action_params: &Option<HashMap<String, ActionParamValue>>,
+    run_outputs: &HashMap<String, String>,
+) -> Result<Vec<cosmwasm_std::Coin>, ContractError> {
+    let mut resolved_funds = Vec::new();
+
+    for (amount_template, denom_template) in template_funds {
         let resolved_amount =
-            resolve_template_parameter(amount_template, resolved_params, execute_action_params)?;
+            resolve_template_parameter(amount_template, resolved_params, execute_action_params, run_outputs)?;
         let resolved_denom =
-            resolve_template_parameter(denom_template, resolved_params, execute_action_params)?;
+            resolve_template_parameter(denom_template, resolved_params, execute_action_params, run_outputs)?;
 
         let amount = Uint128::from_str(&resolved_amount)?;
         resolved_funds.push(cosmwasm_std::Coin {
@@ -712,10 +1925,196 @@ pub fn override_prices_from_oracle(prices: HashMap<String, (String, Decimal)>, q
                         backend_price.clone()
                     }
                 }
-            };
-            (denom.clone(), price)
-        }).collect::<HashMap<String, Decimal>>();        
-    Ok((prices, events))
+            };
+            (denom.clone(), price)
+        }).collect::<HashMap<String, Decimal>>();        
+    Ok((prices, events))
+}
+
+fn validate_creator_fee_schedule(
+    api: &dyn cosmwasm_std::Api,
+    actions: &HashMap<ActionId, ActionMsg>,
+    schedule: &CreatorFeeSchedule,
+) -> Result<(), ContractError> {
+    for fee in &schedule.fees {
+        let amount = match fee {
+            CreatorFee::PerRun { amount } => amount,
+            CreatorFee::FundsShare { action_id, share } => {
+                let parent_action_id = action_id.split(SUB_WORKFLOW_SEPARATOR).next().unwrap_or_default();
+                if !actions.contains_key(parent_action_id) {
+                    return Err(ContractError::GenericError(format!(
+                        "Creator fee action '{}' not found in workflow",
+                        action_id
+                    )));
+                }
+                if *share > Decimal::one() {
+                    return Err(ContractError::GenericError(format!(
+                        "Creator fee share of action '{}' can't exceed 1",
+                        action_id
+                    )));
+                }
+                continue;
+            }
+            CreatorFee::Subscription { amount, period } => {
+                if *period == 0 {
+                    return Err(ContractError::GenericError(
+                        "Creator fee subscription period can't be 0".to_string(),
+                    ));
+                }
+                amount
+            }
+        };
+        if let FeeAmount::Coin(coin) = amount {
+            validate_param_value(api, "creator_fee", &ActionParamValue::Denom(coin.denom.clone()))?;
+        }
+    }
+    Ok(())
+}
+
+// Counts the runs and action funds scheduled creator fees are charged for, returning the usage
+// before this action so its reply can restore it if the action fails
+fn record_creator_fee_usage(
+    storage: &mut dyn cosmwasm_std::Storage,
+    user_addr: &Addr,
+    instance_id: &InstanceId,
+    workflow_id: &WorkflowId,
+    action_id: &ActionId,
+    starts_new_run: bool,
+    msgs: &[WasmMsg],
+) -> Result<Option<CreatorFeeUsage>, ContractError> {
+    let Some(previous_usage) = load_workflow_instance_creator_fee_usage(storage, user_addr, instance_id)? else {
+        return Ok(None);
+    };
+    let Some(schedule) = load_workflow_creator_fee_schedule(storage, workflow_id)? else {
+        return Ok(None);
+    };
+    let mut usage = previous_usage.clone();
+
+    if starts_new_run {
+        usage.runs += 1;
+    }
+    let shares_funds = schedule.fees.iter().any(|fee| {
+        matches!(fee, CreatorFee::FundsShare { action_id: fee_action_id, .. } if fee_action_id == action_id)
+    });
+    if shares_funds {
+        let action_funds = usage.action_funds.entry(action_id.clone()).or_default();
+        for msg in msgs {
+            if let WasmMsg::Execute { funds, .. } = msg {
+                for coin in funds {
+                    *action_funds.entry(coin.denom.clone()).or_default() += coin.amount;
+                }
+            }
+        }
+    }
+    save_workflow_instance_creator_fee_usage(storage, user_addr, instance_id, &usage)?;
+    Ok(Some(previous_usage))
+}
+
+fn usd_value(amount: Uint128, denom: &str, prices: &HashMap<String, Decimal>) -> Result<Decimal256, ContractError> {
+    let price = prices.get(denom).ok_or_else(|| {
+        ContractError::GenericError(format!("Price not found for creator fee denom {}", denom))
+    })?;
+    Ok(Decimal256::from_ratio(amount, 1u128) * Decimal256::from(*price))
+}
+
+// Checks a creator fee against the instance's schedule, returning the usage to save once it's charged.
+// Instances of workflows without a schedule aren't bounded.
+fn check_creator_fee(
+    storage: &dyn cosmwasm_std::Storage,
+    env: &Env,
+    requester: &Addr,
+    instance_id: &InstanceId,
+    prices: &HashMap<String, Decimal>,
+    usd_amount: Uint128,
+) -> Result<Option<CreatorFeeUsage>, ContractError> {
+    let instance = load_workflow_instance(storage, requester, instance_id)?;
+    let Some(schedule) = load_workflow_creator_fee_schedule(storage, &instance.workflow_id)? else {
+        return Ok(None);
+    };
+    let mut usage = load_workflow_instance_creator_fee_usage(storage, requester, instance_id)?.unwrap_or(CreatorFeeUsage {
+        created_at: env.block.time,
+        runs: 0,
+        action_funds: BTreeMap::new(),
+        charged_usd: Uint128::zero(),
+    });
+
+    let fee_usd = |amount: &FeeAmount| match amount {
+        FeeAmount::Coin(coin) => usd_value(coin.amount, &coin.denom, prices),
+        FeeAmount::Usd(amount) => Ok(Decimal256::from_ratio(*amount, 1u128)),
+    };
+    let mut allowed = Decimal256::zero();
+    for fee in &schedule.fees {
+        allowed += match fee {
+            CreatorFee::PerRun { amount } => fee_usd(amount)? * Decimal256::from_ratio(usage.runs, 1u128),
+            CreatorFee::FundsShare { action_id, share } => {
+                let mut funds_usd = Decimal256::zero();
+                for (denom, amount) in usage.action_funds.get(action_id).into_iter().flatten() {
+                    funds_usd += usd_value(*amount, denom, prices)?;
+                }
+                funds_usd * Decimal256::from(*share)
+            }
+            CreatorFee::Subscription { amount, period } => {
+                let periods = env.block.time.seconds().saturating_sub(usage.created_at.seconds()) / period + 1;
+                fee_usd(amount)? * Decimal256::from_ratio(periods, 1u128)
+            }
+        };
+    }
+
+    let allowed = Uint128::try_from(allowed.to_uint_floor()).unwrap_or(Uint128::MAX);
+    let remaining = allowed.saturating_sub(usage.charged_usd);
+    if usd_amount > remaining {
+        return Err(ContractError::CreatorFeeExceedsSchedule {
+            instance_id: instance_id.to_string(),
+            usd_amount: usd_amount.to_string(),
+            remaining: remaining.to_string(),
+        });
+    }
+    usage.charged_usd += usd_amount;
+    Ok(Some(usage))
+}
+
+// Expected USD execution fee of each message, the first one also carries the action's base price
+fn expected_execution_fees(storage: &dyn cosmwasm_std::Storage, msgs: &[WasmMsg]) -> Result<Vec<Uint128>, ContractError> {
+    let Some(table) = load_execution_fee_table(storage)? else {
+        return Ok(vec![Uint128::zero(); msgs.len()]);
+    };
+    let mut fees = Vec::with_capacity(msgs.len());
+    for (index, msg) in msgs.iter().enumerate() {
+        let message_kind = match msg {
+            WasmMsg::Execute { msg, .. } => serde_json::from_slice::<serde_json::Value>(msg)
+                .ok()
+                .and_then(|value| value.as_object().and_then(|object| object.keys().next().cloned())),
+            _ => None,
+        };
+        let mut fee = message_kind
+            .and_then(|kind| table.message_kinds.get(&kind).copied())
+            .unwrap_or(table.per_message);
+        if index == 0 {
+            fee += table.base_price;
+        }
+        fees.push(fee);
+    }
+    Ok(fees)
+}
+
+// Checks an execution fee against what the user's actions accrued, returning what's left once it's charged.
+// Execution fees aren't bounded without a fee table.
+fn check_execution_fee(
+    storage: &dyn cosmwasm_std::Storage,
+    requester: &Addr,
+    usd_amount: Uint128,
+) -> Result<Option<Uint128>, ContractError> {
+    if load_execution_fee_table(storage)?.is_none() {
+        return Ok(None);
+    }
+    let accrued = load_user_accrued_execution_fees(storage, requester)?;
+    if usd_amount > accrued {
+        return Err(ContractError::ExecutionFeeExceedsAccrued {
+            usd_amount: usd_amount.to_string(),
+            accrued: accrued.to_string(),
+        });
+    }
+    Ok(Some(accrued - usd_amount))
 }
 
 pub fn charge_fees(
@@ -727,6 +2126,13 @@ pub fn charge_fees(
     fees: Vec<UserFee>,
 ) -> Result<Response, ContractError> {
     validate_sender_is_owner(deps.storage, &info)?;
+    validate_fees_not_paused(deps.storage)?;
+
+    // Resubmitted batches would charge users twice
+    if load_fee_batch(deps.storage, &batch_id)?.is_some() {
+        return Err(ContractError::FeeBatchAlreadyProcessed { batch_id });
+    }
+    let mut failures = Vec::new();
 
     let mut response = Response::new()
         .add_event(
@@ -741,8 +2147,8 @@ pub fn charge_fees(
     let config = load_config(deps.storage)?;
 
     // deps.querier.query_all_balances(&config.fee_manager_address)?;
-    // Initialize reply ID counter starting from 100
-    let mut reply_id = 1000u64;
+    // Initialize reply ID counter
+    let mut reply_id = FEE_REPLY_ID_START;
     
     // Process fees for each user
     for user_fee in fees {
@@ -759,7 +2165,23 @@ pub fn charge_fees(
             PaymentConfig::Prepaid => (false, Uint128::zero()),
         };
 
-        for fee_total in &user_fee.totals {
+        // Wallet fees are pulled through authz, skip users whose grant to the fee manager is missing
+        if use_wallet {
+            if let Some(error) = find_missing_authz_grant(&deps.querier, &_env, &requester, config.fee_manager_address.as_str()) {
+                response = response.add_event(
+                    cosmwasm_std::Event::new("autorujira-workflow-manager/fee-authz-error")
+                        .add_attribute("user_address", user_fee.address.clone())
+                        .add_attribute("grantee", _env.contract.address.to_string())
+                        .add_attribute("msg_type_url", MSG_EXECUTE_CONTRACT_TYPE_URL)
+                        .add_attribute("contract", config.fee_manager_address.to_string())
+                        .add_attribute("error", error.clone())
+                );
+                failures.extend(user_fee.totals.iter().map(|fee_total| fee_batch_failure(&user_fee, fee_total, &error)));
+                continue;
+            }
+        }
+
+        for (index, fee_total) in user_fee.totals.iter().enumerate() {
             let denom_price = match prices.get(&fee_total.denom) {
                 Some(price) => price,
                 None => {
@@ -770,11 +2192,53 @@ pub fn charge_fees(
                             .add_attribute("fee_denom", fee_total.denom.clone())
                             .add_attribute("error", "Price not found for fee denom")
                     );
+                    failures.push(fee_batch_failure(&user_fee, fee_total, "Price not found for fee denom"));
                     continue; // Skip to next fee_total
                 }
             };
             let usd_amount = (Decimal::from_atomics(fee_total.amount, 0).unwrap() * denom_price).to_uint_ceil();
 
+            // Creator fees are bounded by the workflow's creator fee schedule
+            let creator_fee_usage = match fee_total.fee_type {
+                FeeType::Creator { instance_id } => match check_creator_fee(deps.storage, &_env, &requester, &instance_id, &prices, usd_amount) {
+                    Ok(usage) => usage.map(|usage| (instance_id, usage)),
+                    Err(error) => {
+                        response = response.add_event(
+                            cosmwasm_std::Event::new("autorujira-workflow-manager/creator-fee-error")
+                                .add_attribute("user_address", user_fee.address.clone())
+                                .add_attribute("instance_id", instance_id.to_string())
+                                .add_attribute("fee_denom", fee_total.denom.clone())
+                                .add_attribute("fee_amount", fee_total.amount.to_string())
+                                .add_attribute("usd_amount", usd_amount.to_string())
+                                .add_attribute("error", error.to_string())
+                        );
+                        failures.push(fee_batch_failure(&user_fee, fee_total, &error.to_string()));
+                        continue;
+                    }
+                },
+                FeeType::Execution => None,
+            };
+
+            // Execution fees are bounded by what the user's executed actions accrued
+            let remaining_execution_fees = match fee_total.fee_type {
+                FeeType::Execution => match check_execution_fee(deps.storage, &requester, usd_amount) {
+                    Ok(remaining) => remaining,
+                    Err(error) => {
+                        response = response.add_event(
+                            cosmwasm_std::Event::new("autorujira-workflow-manager/execution-fee-error")
+                                .add_attribute("user_address", user_fee.address.clone())
+                                .add_attribute("fee_denom", fee_total.denom.clone())
+                                .add_attribute("fee_amount", fee_total.amount.to_string())
+                                .add_attribute("usd_amount", usd_amount.to_string())
+                                .add_attribute("error", error.to_string())
+                        );
+                        failures.push(fee_batch_failure(&user_fee, fee_total, &error.to_string()));
+                        continue;
+                    }
+                },
+                FeeType::Creator { .. } => None,
+            };
+
             if use_wallet {
                 if current_usd_allowance < usd_amount {
                     // If user has not enough allowance, set it to 0 and break as we can not charge any more fees
@@ -786,6 +2250,7 @@ pub fn charge_fees(
                             .add_attribute("usd_amount", usd_amount.to_string())
                             .add_attribute("error", "Not enough allowance")
                     );
+                    failures.extend(user_fee.totals[index..].iter().map(|fee_total| fee_batch_failure(&user_fee, fee_total, "Not enough allowance")));
                     current_usd_allowance = Uint128::zero();
                     break; // Break out of the loop as we can not charge any more fees for this user
                 }
@@ -806,6 +2271,7 @@ pub fn charge_fees(
                                 .add_attribute("debit_denom", fee_total.debit_denom.clone())
                                 .add_attribute("error", "Price not found for debit denom")
                         );
+                        failures.push(fee_batch_failure(&user_fee, fee_total, "Price not found for debit denom"));
                         continue; // Skip to next fee_total
                     }
                 };
@@ -814,19 +2280,63 @@ pub fn charge_fees(
 
             // Only process if there's something to charge
             if debit_denom_amount > Uint128::zero() {
+                if let Some((instance_id, usage)) = &creator_fee_usage {
+                    save_workflow_instance_creator_fee_usage(deps.storage, &requester, instance_id, usage)?;
+                }
+                if let Some(remaining) = remaining_execution_fees {
+                    save_user_accrued_execution_fees(deps.storage, &requester, remaining)?;
+                }
+                let creator_workflow_id = match fee_total.fee_type {
+                    FeeType::Creator { instance_id } => Some(load_workflow_instance(deps.storage, &requester, &instance_id)?.workflow_id),
+                    FeeType::Execution => None,
+                };
                 let fee_manager_fee = FeeManagerFee {
-                    fee_type: match fee_total.fee_type {
-                        FeeType::Creator { instance_id } => {
-                            let workflow_instance = load_workflow_instance(deps.storage, &requester.clone(), &instance_id)?;
-                            let workflow = load_workflow(deps.storage, &workflow_instance.workflow_id)?;
+                    fee_type: match &creator_workflow_id {
+                        Some(workflow_id) => {
+                            let workflow = load_workflow(deps.storage, workflow_id)?;
                             FeeManagerFeeType::Creator { creator_address: workflow.publisher.clone() }
                         },
-                        FeeType::Execution => FeeManagerFeeType::Execution,
+                        None => FeeManagerFeeType::Execution,
                     },
                     denom: fee_total.debit_denom.clone(),
                     amount: debit_denom_amount.clone(),
                 };
-                accumulated_fees.push(fee_manager_fee.clone());
+                // Part of an execution fee is rewarded to the bonded executor that performed the actions,
+                // up to the execution fees its successful actions accrued for the user
+                let mut executor_accrued_usd = Uint128::zero();
+                let executor_reward = match (&fee_manager_fee.fee_type, &fee_total.executor, &config.executor_bonding) {
+                    (FeeManagerFeeType::Execution, Some(executor), Some(bonding)) => {
+                        let executor_address = deps.api.addr_validate(executor)?;
+                        let executor_accrued = load_executor_accrued_execution_fees(deps.storage, &requester, &executor_address)?;
+                        executor_accrued_usd = executor_accrued.min(usd_amount);
+                        if load_executor_bond(deps.storage, &executor_address)?.is_some() && !executor_accrued_usd.is_zero() {
+                            save_executor_accrued_execution_fees(deps.storage, &requester, &executor_address, executor_accrued - executor_accrued_usd)?;
+                            let rewarded_amount = debit_denom_amount.multiply_ratio(executor_accrued_usd, usd_amount);
+                            Some((executor_address, rewarded_amount.mul_floor(bonding.reward_share)))
+                        } else {
+                            executor_accrued_usd = Uint128::zero();
+                            None
+                        }
+                    }
+                    _ => None,
+                };
+                match &executor_reward {
+                    Some((executor_address, reward)) if !reward.is_zero() => {
+                        let execution_amount = debit_denom_amount - *reward;
+                        if !execution_amount.is_zero() {
+                            accumulated_fees.push(FeeManagerFee {
+                                amount: execution_amount,
+                                ..fee_manager_fee.clone()
+                            });
+                        }
+                        accumulated_fees.push(FeeManagerFee {
+                            fee_type: FeeManagerFeeType::Executor { executor_address: executor_address.clone() },
+                            denom: fee_total.debit_denom.clone(),
+                            amount: *reward,
+                        });
+                    }
+                    _ => accumulated_fees.push(fee_manager_fee.clone()),
+                }
 
                 if use_wallet {
                     *accumulated_funds.entry(fee_total.debit_denom.clone()).or_insert(Uint128::zero()) += debit_denom_amount;
@@ -845,6 +2355,12 @@ pub fn charge_fees(
                         FeeManagerFeeType::Creator { creator_address } => Some(creator_address.to_string()),
                         _ => None,
                     },
+                    executor_address: executor_reward.map(|(executor_address, _)| executor_address.to_string()),
+                    workflow_id: creator_workflow_id,
+                    batch_id: batch_id.clone(),
+                    executor_accrued_usd,
+                    creator_fee_usd: if creator_fee_usage.is_some() { usd_amount } else { Uint128::zero() },
+                    execution_fee_usd: if remaining_execution_fees.is_some() { usd_amount } else { Uint128::zero() },
                 };
                 accumulated_fee_events.push(fee_event_data);
             }
@@ -902,9 +2418,26 @@ pub fn charge_fees(
         }
     }
 
+    // Charged users and totals are added by the fee manager replies
+    save_fee_batch(deps.storage, &batch_id, &FeeBatch {
+        processed_at: _env.block.time,
+        charged_users: vec![],
+        totals: vec![],
+        failures,
+    })?;
+
     Ok(response)
 }
 
+fn fee_batch_failure(user_fee: &UserFee, fee_total: &FeeTotal, error: &str) -> FeeBatchFailure {
+    FeeBatchFailure {
+        user_address: user_fee.address.clone(),
+        denom: fee_total.denom.clone(),
+        amount: fee_total.amount,
+        error: error.to_string(),
+    }
+}
+
 
 /// Handle reply from fee manager contract
 pub fn handle_fee_manager_reply(
@@ -916,11 +2449,38 @@ pub fn handle_fee_manager_reply(
     if let cosmwasm_std::SubMsgResult::Err(error_msg) = reply.result {
         // Load fee event data from storage for error event
         let fee_event_data_vec = FEE_EVENT_DATA.load(deps.storage, reply.id)?;
+        let batch_id = fee_event_data_vec.first().map(|fee_event_data| fee_event_data.batch_id.clone()).unwrap_or_default();
         
         let mut response = Response::new();
+        let mut failures = Vec::new();
         
         // Emit error event for each fee
         for fee_event_data in fee_event_data_vec {
+            let user_address = Addr::unchecked(&fee_event_data.user_address);
+            // The executor reward wasn't paid, so the executor keeps what it accrued
+            if let Some(executor_address) = fee_event_data.executor_address.as_ref().filter(|_| !fee_event_data.executor_accrued_usd.is_zero()) {
+                let executor_address = Addr::unchecked(executor_address);
+                let executor_accrued = load_executor_accrued_execution_fees(deps.storage, &user_address, &executor_address)?;
+                save_executor_accrued_execution_fees(deps.storage, &user_address, &executor_address, executor_accrued + fee_event_data.executor_accrued_usd)?;
+            }
+            // Nor was the execution fee, so the user's accrued fees still cover it
+            if !fee_event_data.execution_fee_usd.is_zero() {
+                let accrued = load_user_accrued_execution_fees(deps.storage, &user_address)?;
+                save_user_accrued_execution_fees(deps.storage, &user_address, accrued + fee_event_data.execution_fee_usd)?;
+            }
+            // Nor was the creator fee, so the schedule allows it again
+            if let FeeType::Creator { instance_id } = fee_event_data.fee_type {
+                if let Some(mut usage) = load_workflow_instance_creator_fee_usage(deps.storage, &user_address, &instance_id)?.filter(|_| !fee_event_data.creator_fee_usd.is_zero()) {
+                    usage.charged_usd = usage.charged_usd.saturating_sub(fee_event_data.creator_fee_usd);
+                    save_workflow_instance_creator_fee_usage(deps.storage, &user_address, &instance_id, &usage)?;
+                }
+            }
+            failures.push(FeeBatchFailure {
+                user_address: fee_event_data.user_address.clone(),
+                denom: fee_event_data.fee_denom.clone(),
+                amount: fee_event_data.fee_amount,
+                error: error_msg.clone(),
+            });
             response = response.add_event(
                 cosmwasm_std::Event::new("autorujira-workflow-manager/fee-error")
                     .add_attribute("user_address", fee_event_data.user_address)
@@ -934,6 +2494,7 @@ pub fn handle_fee_manager_reply(
                     .add_attribute("details", error_msg.clone())
             );
         }
+        record_fee_batch_failures(deps.storage, &batch_id, failures)?;
         
         // Clean up the temporary data
         FEE_EVENT_DATA.remove(deps.storage, reply.id);
@@ -950,19 +2511,35 @@ pub fn handle_fee_manager_reply(
     let fee_event_data_vec = FEE_EVENT_DATA.load(deps.storage, reply.id)?;
     
     let mut response = Response::new();
+
+    // Each reply charges a single user
+    if let Some(fee_event_data) = fee_event_data_vec.first() {
+        let charged = fee_event_data_vec
+            .iter()
+            .map(|fee_event_data| Coin::new(fee_event_data.debit_amount, fee_event_data.debit_denom.clone()))
+            .collect();
+        record_fee_batch_charge(deps.storage, &fee_event_data.batch_id, &fee_event_data.user_address, charged)?;
+    }
     
     // Emit fee-charged event for each fee
     for fee_event_data in fee_event_data_vec {
-        response = response.add_event(
-            cosmwasm_std::Event::new("autorujira-workflow-manager/fee-charged")
-                .add_attribute("user_address", fee_event_data.user_address)
-                .add_attribute("denom", fee_event_data.fee_denom)
-                .add_attribute("amount", fee_event_data.fee_amount.to_string())
-                .add_attribute("usd_amount", fee_event_data.usd_amount.to_string())
-                .add_attribute("debit_denom", fee_event_data.debit_denom)
-                .add_attribute("debit_amount", fee_event_data.debit_amount.to_string())
-                .add_attribute("fee_type", fee_event_data.fee_type.to_string())
-                .add_attribute("creator_address", fee_event_data.creator_address.unwrap_or_default()));
+        // Creator fees count towards their workflow's stats once the fee manager accepted them
+        if let Some(workflow_id) = &fee_event_data.workflow_id {
+            record_workflow_creator_fee(deps.storage, workflow_id, Coin::new(fee_event_data.debit_amount, fee_event_data.debit_denom.clone()))?;
+        }
+        let mut event = cosmwasm_std::Event::new("autorujira-workflow-manager/fee-charged")
+            .add_attribute("user_address", fee_event_data.user_address)
+            .add_attribute("denom", fee_event_data.fee_denom)
+            .add_attribute("amount", fee_event_data.fee_amount.to_string())
+            .add_attribute("usd_amount", fee_event_data.usd_amount.to_string())
+            .add_attribute("debit_denom", fee_event_data.debit_denom)
+            .add_attribute("debit_amount", fee_event_data.debit_amount.to_string())
+            .add_attribute("fee_type", fee_event_data.fee_type.to_string())
+            .add_attribute("creator_address", fee_event_data.creator_address.unwrap_or_default());
+        if let Some(executor_address) = fee_event_data.executor_address {
+            event = event.add_attribute("executor_address", executor_address);
+        }
+        response = response.add_event(event);
     }
     
     // Clean up the temporary data
@@ -998,6 +2575,7 @@ pub fn finish_instances(
                     }
 
                     // Update instance state to Finished
+                    record_workflow_instance_state_change(deps.storage, &instance.workflow_id, &instance.state, &WorkflowInstanceState::Finished)?;
                     let mut updated_instance = instance;
                     updated_instance.state = WorkflowInstanceState::Finished;
                     save_workflow_instance(deps.storage, &requester, &instance_id, &updated_instance)?;
@@ -1045,6 +2623,9 @@ pub fn reset_instance(
     // Handle different execution types
     if matches!(updated_instance.execution_type, ExecutionType::OneShot) {
         // For OneShot instances, change state to Finished
+        if !matches!(updated_instance.state, WorkflowInstanceState::Finished) {
+            record_workflow_instance_state_change(deps.storage, &updated_instance.workflow_id, &updated_instance.state, &WorkflowInstanceState::Finished)?;
+        }
         updated_instance.state = WorkflowInstanceState::Finished;
     } else {
         // For Recurrent instances, reset last_executed_action to None
@@ -1052,6 +2633,7 @@ pub fn reset_instance(
     }
     
     save_workflow_instance(deps.storage, &user_addr, &instance_id, &updated_instance)?;
+    remove_workflow_instance_lease(deps.storage, &user_addr, &instance_id)?;
 
     Ok(Response::new()
         .add_event(
@@ -1064,3 +2646,402 @@ pub fn reset_instance(
                 })
         ))
 }
+
+pub fn claim_lease(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    user_address: String,
+    instance_id: InstanceId,
+    duration_seconds: u64,
+) -> Result<Response, ContractError> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let instance = load_workflow_instance(deps.storage, &user_addr, &instance_id).map_err(|_| {
+        ContractError::InstanceNotFound {
+            instance_id: instance_id.to_string(),
+        }
+    })?;
+
+    validate_sender_is_action_executor(deps.storage, &info, &instance)?;
+
+    if duration_seconds == 0 {
+        return Err(ContractError::GenericError(
+            "Lease duration must be greater than zero".to_string(),
+        ));
+    }
+
+    // Active leases can only be renewed by their holder, expired ones can be taken over
+    validate_sender_can_use_lease(deps.storage, &env, &info, &user_addr, &instance_id)?;
+
+    // A lease never outlives the instance
+    let expires_at = std::cmp::min(env.block.time.plus_seconds(duration_seconds), instance.expiration_time);
+    let lease = InstanceLease {
+        holder: info.sender.clone(),
+        expires_at,
+    };
+    save_workflow_instance_lease(deps.storage, &user_addr, &instance_id, &lease)?;
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/claim_lease")
+                .add_attribute("user_address", user_address)
+                .add_attribute("instance_id", instance_id.to_string())
+                .add_attribute("holder", info.sender.to_string())
+                .add_attribute("expires_at", expires_at.seconds().to_string())
+        ))
+}
+
+pub fn release_lease(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    user_address: String,
+    instance_id: InstanceId,
+) -> Result<Response, ContractError> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let lease = load_workflow_instance_lease(deps.storage, &user_addr, &instance_id)?.ok_or_else(|| {
+        ContractError::GenericError("Instance has no lease".to_string())
+    })?;
+
+    if lease.holder != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    remove_workflow_instance_lease(deps.storage, &user_addr, &instance_id)?;
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/release_lease")
+                .add_attribute("user_address", user_address)
+                .add_attribute("instance_id", instance_id.to_string())
+                .add_attribute("holder", info.sender.to_string())
+        ))
+}
+
+pub fn set_workflow_executors(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    workflow_id: WorkflowId,
+    executors: Option<HashSet<Addr>>,
+) -> Result<Response, ContractError> {
+    let workflow = load_workflow(deps.storage, &workflow_id).map_err(|_| {
+        ContractError::WorkflowNotFound {
+            workflow_id: workflow_id.clone(),
+        }
+    })?;
+
+    // Only the workflow publisher or the owner can assign executors
+    if info.sender != workflow.publisher {
+        validate_sender_is_owner(deps.storage, &info)?;
+    }
+
+    let executors_attribute = match &executors {
+        Some(executors) if executors.is_empty() => {
+            return Err(ContractError::GenericError(
+                "Executors can't be empty, use None to fall back to the global executors".to_string(),
+            ));
+        }
+        Some(executors) => {
+            save_workflow_executors(deps.storage, &workflow_id, executors)?;
+            let mut executors: Vec<String> = executors.iter().map(|executor| executor.to_string()).collect();
+            executors.sort();
+            executors.join(",")
+        }
+        None => {
+            remove_workflow_executors(deps.storage, &workflow_id)?;
+            "global".to_string()
+        }
+    };
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/set_workflow_executors")
+                .add_attribute("workflow_id", workflow_id)
+                .add_attribute("executors", executors_attribute)
+                .add_attribute("sender", info.sender.to_string())
+        ))
+}
+
+pub fn bond_executor(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let bonding = load_config(deps.storage)?.executor_bonding.ok_or_else(|| {
+        ContractError::GenericError("Executor bonding is disabled".to_string())
+    })?;
+    let amount = cw_utils::must_pay(&info, &bonding.min_bond.denom)?;
+
+    // Bonding again tops up the bond and cancels any ongoing unbonding
+    let mut executor_bond = load_executor_bond(deps.storage, &info.sender)?.unwrap_or(ExecutorBond {
+        bond: Coin {
+            denom: bonding.min_bond.denom.clone(),
+            amount: Uint128::zero(),
+        },
+        unbonding_since: None,
+    });
+    // A bond in a previous bonding denom must be withdrawn before bonding in the new one
+    if executor_bond.bond.denom != bonding.min_bond.denom {
+        return Err(ContractError::GenericError(format!(
+            "Bond is in {}, unbond and withdraw it before bonding {}",
+            executor_bond.bond.denom, bonding.min_bond.denom
+        )));
+    }
+    executor_bond.bond.amount += amount;
+    executor_bond.unbonding_since = None;
+
+    if executor_bond.bond.amount < bonding.min_bond.amount {
+        return Err(ContractError::GenericError(format!(
+            "Bond of {} is below the minimum of {}",
+            executor_bond.bond, bonding.min_bond
+        )));
+    }
+    save_executor_bond(deps.storage, &info.sender, &executor_bond)?;
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/bond_executor")
+                .add_attribute("executor", info.sender.to_string())
+                .add_attribute("amount", amount.to_string())
+                .add_attribute("bond", executor_bond.bond.to_string())
+                .add_attribute("timestamp", env.block.time.seconds().to_string())
+        ))
+}
+
+pub fn unbond_executor(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut executor_bond = load_executor_bond(deps.storage, &info.sender)?.ok_or_else(|| {
+        ContractError::ExecutorNotBonded {
+            executor: info.sender.to_string(),
+        }
+    })?;
+    if executor_bond.unbonding_since.is_some() {
+        return Err(ContractError::GenericError("Executor is already unbonding".to_string()));
+    }
+    executor_bond.unbonding_since = Some(env.block.time);
+    save_executor_bond(deps.storage, &info.sender, &executor_bond)?;
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/unbond_executor")
+                .add_attribute("executor", info.sender.to_string())
+                .add_attribute("bond", executor_bond.bond.to_string())
+                .add_attribute("timestamp", env.block.time.seconds().to_string())
+        ))
+}
+
+pub fn withdraw_executor_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let executor_bond = load_executor_bond(deps.storage, &info.sender)?.ok_or_else(|| {
+        ContractError::ExecutorNotBonded {
+            executor: info.sender.to_string(),
+        }
+    })?;
+    let unbonding_since = executor_bond.unbonding_since.ok_or_else(|| {
+        ContractError::GenericError("Executor must unbond before withdrawing".to_string())
+    })?;
+
+    // The bond stays slashable until the unbonding period is over
+    let unbonding_period = load_config(deps.storage)?
+        .executor_bonding
+        .map(|bonding| bonding.unbonding_period)
+        .unwrap_or(0);
+    let withdrawable_at = unbonding_since.plus_seconds(unbonding_period);
+    if env.block.time < withdrawable_at {
+        return Err(ContractError::GenericError(format!(
+            "Bond can't be withdrawn until {}",
+            withdrawable_at.seconds()
+        )));
+    }
+    remove_executor_bond(deps.storage, &info.sender)?;
+
+    let mut response = Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/withdraw_executor_bond")
+                .add_attribute("executor", info.sender.to_string())
+                .add_attribute("bond", executor_bond.bond.to_string())
+        );
+    if !executor_bond.bond.amount.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![executor_bond.bond],
+        });
+    }
+    Ok(response)
+}
+
+pub fn slash_executor(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    executor: String,
+    amount: Uint128,
+    reason: String,
+) -> Result<Response, ContractError> {
+    validate_sender_is_owner(deps.storage, &info)?;
+
+    let executor_addr = deps.api.addr_validate(&executor)?;
+    let mut executor_bond = load_executor_bond(deps.storage, &executor_addr)?.ok_or_else(|| {
+        ContractError::ExecutorNotBonded {
+            executor: executor.clone(),
+        }
+    })?;
+
+    // Slashing more than the bond takes whatever is left
+    let slashed = amount.min(executor_bond.bond.amount);
+    if slashed.is_zero() {
+        return Err(ContractError::GenericError("Nothing to slash".to_string()));
+    }
+    executor_bond.bond.amount -= slashed;
+    save_executor_bond(deps.storage, &executor_addr, &executor_bond)?;
+
+    // Slashed funds go to the owner
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/slash_executor")
+                .add_attribute("executor", executor)
+                .add_attribute("slashed", slashed.to_string())
+                .add_attribute("remaining_bond", executor_bond.bond.amount.to_string())
+                .add_attribute("reason", reason)
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: executor_bond.bond.denom,
+                amount: slashed,
+            }],
+        }))
+}
+
+pub fn set_execution_fee_table(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    table: Option<ExecutionFeeTable>,
+) -> Result<Response, ContractError> {
+    validate_sender_is_owner(deps.storage, &info)?;
+
+    match &table {
+        Some(table) => save_execution_fee_table(deps.storage, table)?,
+        None => remove_execution_fee_table(deps.storage)?,
+    }
+
+    Ok(Response::new().add_event(
+        cosmwasm_std::Event::new("autorujira-workflow-manager/set_execution_fee_table")
+            .add_attribute("enabled", table.is_some().to_string())
+    ))
+}
+
+pub fn pause_executions(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    scope: PauseScope,
+    reason: String,
+    block_instances: bool,
+    block_fees: bool,
+) -> Result<Response, ContractError> {
+    validate_sender_is_guardian(deps.storage, &info)?;
+
+    match &scope {
+        PauseScope::Global => {}
+        PauseScope::Workflow { workflow_id } => {
+            load_workflow(deps.storage, workflow_id).map_err(|_| ContractError::WorkflowNotFound {
+                workflow_id: workflow_id.clone(),
+            })?;
+            if block_fees {
+                return Err(ContractError::GenericError(
+                    "Fees can only be blocked by a global pause".to_string(),
+                ));
+            }
+        }
+        PauseScope::Contract { .. } => {
+            if block_instances || block_fees {
+                return Err(ContractError::GenericError(
+                    "Contract pauses only block action execution".to_string(),
+                ));
+            }
+        }
+    }
+
+    save_pause(deps.storage, &scope, &Pause {
+        paused_by: info.sender.clone(),
+        paused_at: env.block.time,
+        reason: reason.clone(),
+        block_instances,
+        block_fees,
+    })?;
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/pause_executions")
+                .add_attribute("scope", scope.to_string())
+                .add_attribute("paused_by", info.sender.to_string())
+                .add_attribute("reason", reason)
+                .add_attribute("block_instances", block_instances.to_string())
+                .add_attribute("block_fees", block_fees.to_string())
+        ))
+}
+
+pub fn unpause_executions(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    scope: PauseScope,
+) -> Result<Response, ContractError> {
+    // Guardians can only pause, lifting a pause is up to the owner
+    validate_sender_is_owner(deps.storage, &info)?;
+
+    if load_pause(deps.storage, &scope)?.is_none() {
+        return Err(ContractError::GenericError(format!("Scope {} is not paused", scope)));
+    }
+    remove_pause(deps.storage, &scope);
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/unpause_executions")
+                .add_attribute("scope", scope.to_string())
+        ))
+}
+
+pub fn set_instance_spend_limits(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    instance_id: InstanceId,
+    limits: Vec<SpendLimit>,
+) -> Result<Response, ContractError> {
+    // Only the requester's own instances can be loaded
+    load_workflow_instance(deps.storage, &info.sender, &instance_id).map_err(|_| {
+        ContractError::InstanceNotFound {
+            instance_id: instance_id.to_string(),
+        }
+    })?;
+
+    if limits.iter().any(|limit| limit.period == Some(0)) {
+        return Err(ContractError::GenericError(
+            "Spend limit period must be greater than zero".to_string(),
+        ));
+    }
+
+    // Spends already recorded keep counting against the new limits
+    if limits.is_empty() {
+        remove_workflow_instance_spend_limits(deps.storage, &info.sender, &instance_id)?;
+    } else {
+        save_workflow_instance_spend_limits(deps.storage, &info.sender, &instance_id, &limits)?;
+    }
+
+    Ok(Response::new()
+        .add_event(
+            cosmwasm_std::Event::new("autorujira-workflow-manager/set_instance_spend_limits")
+                .add_attribute("user_address", info.sender.to_string())
+                .add_attribute("instance_id", instance_id.to_string())
+                .add_attribute("limits", limits.len().to_string())
+        ))
+}
@@ -1,10 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Decimal, Decimal256, Timestamp, Uint128};
 
-use crate::state::{PaymentConfig};
+use crate::state::{FeeBatch, InstanceLease, Pause, PaymentConfig, WorkflowStats};
+use auto_access_control::msg::{OwnershipResponse, RoleMembersResponse};
 
 #[cw_serde]
 pub enum WorkflowVisibility {
@@ -12,10 +13,22 @@ pub enum WorkflowVisibility {
     Private,
 }
 
+/// Param value, rendered in templates according to its type. String and BigInt values
+/// can reference other values (#ip., #cp., #out.), typed values are always used as-is.
 #[cw_serde]
 pub enum ActionParamValue {
     String(String),
-    BigInt(String), // Using String to represent BigInt for CosmWasm compatibility
+    BigInt(String), // Using String to represent BigInt for CosmWasm compatibility, must be an integer
+    Uint128(Uint128),
+    Decimal(Decimal),
+    Bool(bool),
+    // Validated with addr_validate when the value is received
+    Address(String),
+    // Rendered as {"denom":"...","amount":"..."}
+    Coin(Coin),
+    Denom(String),
+    // Raw JSON, rendered unquoted
+    Json(String),
 }
 
 #[cw_serde]
@@ -47,12 +60,48 @@ pub enum WorkflowInstanceState {
     Cancelled
 }
 
+#[cw_serde]
+pub enum RunOutcome {
+    Succeeded,
+    Failed {
+        action_id: ActionId,
+        error: String,
+    },
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub allowed_publishers: HashSet<Addr>,
     pub allowed_action_executors: HashSet<Addr>,
     pub referral_memo: String,
     pub fee_manager_address: Addr,
+    // Consecutive action failures after which an instance is paused (None disables auto-pause)
+    pub max_consecutive_failures: Option<u32>,
+    // Permissionless executor registration (None disables bonding)
+    #[serde(default)]
+    pub executor_bonding: Option<ExecutorBondingConfig>,
+}
+
+#[cw_serde]
+pub struct ExecutorBondingConfig {
+    // Minimum bond an executor must keep to execute actions
+    pub min_bond: Coin,
+    // Share of the execution fee rewarded to the executor that performed the actions
+    pub reward_share: Decimal,
+    // Seconds an unbonding executor stays slashable before withdrawing the bond
+    pub unbonding_period: u64,
+}
+
+// USD prices, in the units of ChargeFees, that execution fees are reconciled against
+#[cw_serde]
+pub struct ExecutionFeeTable {
+    // Charged once for every executed action
+    pub base_price: Uint128,
+    // Charged for every message the action dispatches
+    pub per_message: Uint128,
+    // Replaces per_message for messages with these top-level keys, e.g. "swap"
+    #[serde(default)]
+    pub message_kinds: HashMap<String, Uint128>,
 }
 
 pub type WorkflowId = String;
@@ -60,6 +109,7 @@ pub type ActionId = String;
 pub type InstanceId = u64;
 pub type ParamId = String;
 pub type TemplateId = String;
+pub type OutputId = String;
 
 #[cw_serde]
 pub struct Template {
@@ -68,13 +118,120 @@ pub struct Template {
     pub funds: Vec<(String, String)>, // (amount, denom)
 }
 
+/// Value extracted from the events of an executed action, referenced later as #out.<action>.<output>
+#[cw_serde]
+pub struct ActionOutput {
+    pub event_type: String,
+    pub attribute_key: String,
+    // Only consider events that carry this (key, value) attribute
+    pub filter: Option<(String, String)>,
+}
+
 #[cw_serde]
 pub struct ActionMsg {
     pub params: HashMap<ParamId, ActionParamValue>,
     pub next_actions: HashSet<ActionId>,
     pub templates: HashMap<TemplateId, Template>, // Now required, not optional
     pub whitelisted_contracts: HashSet<String>, // Lista de contratos whitelisted por acción
+    // Allowed top-level execute message keys per whitelisted contract (contracts not listed allow any message)
+    #[serde(default)]
+    pub allowed_messages: HashMap<String, HashSet<String>>,
+    #[serde(default)]
+    pub outputs: HashMap<OutputId, ActionOutput>,
+    // #cp params that must be covered by a signature of the instance param signer
+    #[serde(default)]
+    pub signed_params: HashSet<ParamId>,
+    // Constraints on the #cp params sent by executors
+    #[serde(default)]
+    pub param_constraints: HashMap<ParamId, ParamConstraint>,
+    // Runs another published workflow in place of the action's templates
+    #[serde(default)]
+    pub sub_workflow: Option<SubWorkflow>,
+    // Sets the min_return of the action's swaps from oracle prices instead of executor params
+    #[serde(default)]
+    pub min_return_protection: Option<MinReturnProtection>,
+    // When the action can run, enforced on every execution
+    #[serde(default)]
+    pub timing: Option<ActionTiming>,
+}
+
+/// Limits on when an action runs, in seconds. not_before and not_after are relative to the start of
+/// the current run, a start action beginning a run executes at 0
+#[cw_serde]
+#[derive(Default)]
+pub struct ActionTiming {
+    pub not_before: Option<u64>,
+    pub not_after: Option<u64>,
+    // Minimum time between the start of the previous run and a new one, only for start actions
+    pub cooldown: Option<u64>,
+    pub rate_limit: Option<ExecutionRateLimit>,
+}
+
+/// At most max_executions of the action within any rolling window of the instance
+#[cw_serde]
+pub struct ExecutionRateLimit {
+    pub max_executions: u32,
+    pub window: u64,
+}
+
+/// Swap message format the oracle-derived min_return is written into
+#[cw_serde]
+pub enum SwapVenue {
+    // {"swap":{"min_return":"<amount>"}}
+    Fin,
+    // {"swap":{"min_return":{"denom":"<ask_denom>","amount":"<amount>"}}}
+    Bow,
+    ThorchainSwap,
+}
+
+/// Minimum return of a swap computed from the oracle value of the offered funds, both assets must
+/// use the same number of decimals
+#[cw_serde]
+pub struct MinReturnProtection {
+    pub venue: SwapVenue,
+    // Oracle symbols of the offered and returned assets, e.g. "RUNE" and "BTC"
+    pub offer_symbol: String,
+    pub ask_symbol: String,
+    pub ask_denom: String,
+    // Largest accepted loss against the oracle price, 100 = 1%
+    pub max_slippage_bps: u16,
 }
+
+/// Published workflow run as an action, its actions are executed as <action_id>.<sub_action_id>
+/// and the parent continues with the action's next_actions once it reaches an end action
+#[cw_serde]
+pub struct SubWorkflow {
+    pub workflow_id: WorkflowId,
+    // Instance params of the sub-workflow, resolved against the parent instance like action params
+    pub params: HashMap<ParamId, ActionParamValue>,
+}
+
+#[cw_serde]
+pub enum ParamBound {
+    Value(Decimal256),
+    // Value of an #ip param multiplied by ratio
+    InstanceParam { param: ParamId, ratio: Decimal256 },
+}
+
+/// Limits on the value an executor can supply for a #cp param, every set field must hold
+#[cw_serde]
+#[derive(Default)]
+pub struct ParamConstraint {
+    pub allowed_values: Option<HashSet<String>>,
+    pub min: Option<ParamBound>,
+    pub max: Option<ParamBound>,
+    // Must match the whole value
+    pub regex: Option<String>,
+}
+// Caps the funds an instance's actions can send in a denom, over a rolling period in seconds
+// or, without period, over the whole life of the instance
+#[cw_serde]
+pub struct SpendLimit {
+    pub denom: String,
+    pub amount: Uint128,
+    pub period: Option<u64>,
+}
+
 #[cw_serde]
 pub struct NewWorkflowMsg {
     pub id: WorkflowId,
@@ -83,6 +240,34 @@ pub struct NewWorkflowMsg {
     pub visibility: WorkflowVisibility,
     // action_name -> action
     pub actions: HashMap<ActionId, ActionMsg>,
+    // Upper bound of the creator fees charged per instance, unbounded when not set
+    #[serde(default)]
+    pub creator_fee_schedule: Option<CreatorFeeSchedule>,
+}
+
+#[cw_serde]
+pub enum FeeAmount {
+    // Converted with the prices sent to ChargeFees
+    Coin(Coin),
+    // In the unit of the ChargeFees USD amounts and wallet allowances
+    Usd(Uint128),
+}
+
+#[cw_serde]
+pub enum CreatorFee {
+    // For every run the instance starts
+    PerRun { amount: FeeAmount },
+    // Share of the funds sent by an action, <action_id>.<sub_action_id> for sub-workflow steps
+    FundsShare { action_id: ActionId, share: Decimal },
+    // For every period since the instance was created, charged in advance
+    Subscription { amount: FeeAmount, period: u64 },
+}
+
+/// Creator fees a workflow can charge, ChargeFees rejects creator fees of an instance above
+/// the sum of its fees
+#[cw_serde]
+pub struct CreatorFeeSchedule {
+    pub fees: Vec<CreatorFee>,
 }
   
 #[cw_serde]
@@ -93,6 +278,75 @@ pub struct NewInstanceMsg {
     pub execution_type: ExecutionType,
     pub cron_expression: Option<String>,
     pub expiration_time: Timestamp,
+    pub param_signer: Option<ParamSigner>,
+    // Constraints on #cp params, applied on top of the workflow ones
+    #[serde(default)]
+    pub param_constraints: HashMap<ParamId, ParamConstraint>,
+    // Start actions only begin a run while the price condition holds
+    #[serde(default)]
+    pub price_trigger: Option<PriceTrigger>,
+    // Lets bonded executors run the instance when the workflow has no dedicated executors
+    #[serde(default)]
+    pub allow_bonded_executors: bool,
+}
+
+#[cw_serde]
+pub enum PriceSource {
+    // THORChain oracle price of a symbol, e.g. "BTC"
+    Oracle { symbol: String },
+    // TOR price of a THORChain pool asset, e.g. "BTC.BTC"
+    Pool { asset: String },
+}
+
+#[cw_serde]
+pub enum PriceCondition {
+    // Price at or above the trigger price, e.g. take-profit
+    Above,
+    // Price at or below the trigger price, e.g. limit buy or stop-loss
+    Below,
+}
+
+/// Condition on a price checked on-chain whenever a start action begins a new run
+#[cw_serde]
+pub struct PriceTrigger {
+    pub source: PriceSource,
+    pub condition: PriceCondition,
+    pub price: Decimal,
+}
+
+#[cw_serde]
+pub enum SignatureAlgorithm {
+    Secp256k1,
+    Ed25519,
+}
+
+/// Key allowed to sign the #cp params sent by executors for an instance
+#[cw_serde]
+pub struct ParamSigner {
+    pub public_key: Binary,
+    pub algorithm: SignatureAlgorithm,
+    // #cp params that must be signed in every action, on top of the ones required by the workflow
+    pub required_params: HashSet<ParamId>,
+}
+
+#[cw_serde]
+pub struct ParamsSignature {
+    pub signature: Binary,
+    pub nonce: u64,
+    pub expires_at: Timestamp,
+}
+
+/// Payload signed by the param signer: the signature is over sha256(json(payload))
+#[cw_serde]
+pub struct SignedParamsPayload {
+    pub contract_address: String,
+    pub user_address: String,
+    pub instance_id: InstanceId,
+    pub action_id: ActionId,
+    pub template_id: TemplateId,
+    pub params: BTreeMap<ParamId, ActionParamValue>,
+    pub nonce: u64,
+    pub expires_at: Timestamp,
 }
 
 #[cw_serde]
@@ -129,7 +383,10 @@ pub enum ExecuteMsg {
         instance_id: InstanceId,
         action_id: ActionId,
         template_id: TemplateId, // Now required, not optional
-        params: Option<HashMap<ParamId, ActionParamValue>>
+        params: Option<HashMap<ParamId, ActionParamValue>>,
+        params_signature: Option<ParamsSignature>,
+        // Executor-chosen id, an instance rejects ids it has already executed
+        execution_id: Option<String>,
     },
     PurgeInstances {
         instance_ids: Vec<InstanceId>,
@@ -151,6 +408,60 @@ pub enum ExecuteMsg {
         user_address: String,
         instance_id: InstanceId,
     },
+    // Claims or renews the right to execute the instance's actions until the lease expires
+    ClaimLease {
+        user_address: String,
+        instance_id: InstanceId,
+        duration_seconds: u64,
+    },
+    ReleaseLease {
+        user_address: String,
+        instance_id: InstanceId,
+    },
+    // Replaces the spend limits of one of the sender's instances, an empty list removes them
+    SetInstanceSpendLimits {
+        instance_id: InstanceId,
+        limits: Vec<SpendLimit>,
+    },
+    // Assigns dedicated executors to a workflow, None falls back to the global executors
+    SetWorkflowExecutors {
+        workflow_id: WorkflowId,
+        executors: Option<HashSet<Addr>>,
+    },
+    // Registers the sender as an executor, or tops up its bond, with the funds sent
+    BondExecutor {},
+    // Stops the sender from executing actions and starts the unbonding period
+    UnbondExecutor {},
+    WithdrawExecutorBond {},
+    SlashExecutor {
+        executor: String,
+        amount: Uint128,
+        reason: String,
+    },
+    // Owner only, None removes the table and stops bounding execution fees
+    SetExecutionFeeTable {
+        table: Option<ExecutionFeeTable>,
+    },
+    // Circuit breaker, guardians and the owner can pause but only the owner can unpause
+    PauseExecutions {
+        scope: PauseScope,
+        reason: String,
+        // Also block new instances of the paused workflows (global and workflow scopes)
+        #[serde(default)]
+        block_instances: bool,
+        // Also block fee charging (global scope only)
+        #[serde(default)]
+        block_fees: bool,
+    },
+    UnpauseExecutions {
+        scope: PauseScope,
+    },
+    // Access control, see auto-access-control. Roles are "publisher", "executor" and "guardian"
+    TransferOwnership { new_owner: String },
+    AcceptOwnership {},
+    CancelOwnershipTransfer {},
+    GrantRole { role: String, address: String },
+    RevokeRole { role: String, address: String },
 }
 
 #[cw_serde]
@@ -159,6 +470,8 @@ pub enum SudoMsg {
     SetAllowedPublishers(HashSet<Addr>),
     SetAllowedActionExecutors(HashSet<Addr>),
     SetReferralMemo(String),
+    SetMaxConsecutiveFailures(Option<u32>),
+    SetExecutorBonding(Option<ExecutorBondingConfig>),
 }
 
 #[cw_serde]
@@ -167,6 +480,7 @@ pub struct WorkflowResponse {
     pub base: NewWorkflowMsg,
     pub publisher: Addr,
     pub state: WorkflowState,
+    pub executors: Option<HashSet<Addr>>,
 }
 
 #[cw_serde]
@@ -182,6 +496,8 @@ pub struct WorkflowInstanceResponse {
     pub state: WorkflowInstanceState,
     pub requester: Addr,
     pub last_executed_action: Option<String>,
+    pub last_run_outcome: Option<RunOutcome>,
+    pub consecutive_failures: u32,
 }
 
 #[cw_serde]
@@ -194,6 +510,169 @@ pub struct GetWorkflowInstanceResponse {
     pub instance: WorkflowInstanceResponse,
 }
 
+#[cw_serde]
+pub struct GetInstanceLeaseResponse {
+    pub lease: Option<InstanceLease>,
+}
+
+#[cw_serde]
+pub struct SpendLimitResponse {
+    pub limit: SpendLimit,
+    // Spent in the current period
+    pub spent: Uint128,
+}
+
+#[cw_serde]
+pub struct GetInstanceSpendLimitsResponse {
+    pub limits: Vec<SpendLimitResponse>,
+}
+
+#[cw_serde]
+pub enum PauseScope {
+    Global,
+    Workflow { workflow_id: WorkflowId },
+    Contract { contract: String },
+}
+
+impl fmt::Display for PauseScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PauseScope::Global => write!(f, "global"),
+            PauseScope::Workflow { workflow_id } => write!(f, "workflow_{}", workflow_id),
+            PauseScope::Contract { contract } => write!(f, "contract_{}", contract),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PauseResponse {
+    pub scope: PauseScope,
+    pub pause: Pause,
+}
+
+#[cw_serde]
+pub struct GetPausesResponse {
+    pub pauses: Vec<PauseResponse>,
+}
+
+#[cw_serde]
+pub struct GetWorkflowStatsResponse {
+    pub workflow_id: WorkflowId,
+    pub stats: WorkflowStats,
+}
+
+#[cw_serde]
+pub struct GetWorkflowLeaderboardResponse {
+    pub workflows: Vec<GetWorkflowStatsResponse>,
+}
+
+#[cw_serde]
+pub enum InstanceBlockReason {
+    Expired { expiration_time: Timestamp },
+    // The recurrent schedule was paused by the requester
+    SchedulePaused,
+    Finished,
+    Cancelled,
+    ExecutionsPaused { scope: PauseScope, reason: String },
+}
+
+#[cw_serde]
+pub enum ActionBlockReason {
+    // Start actions wait for the instance's price trigger
+    PriceTriggerNotMet { reason: String },
+    // Outside the action's time window, in its cooldown or over its rate limit
+    ActionTimingViolation { reason: String },
+}
+
+#[cw_serde]
+pub struct ExecutableAction {
+    pub action_id: ActionId,
+    pub templates: HashMap<TemplateId, Template>,
+    // Next in sequence but rejected by ExecuteAction for now
+    pub blocked: Option<ActionBlockReason>,
+}
+
+#[cw_serde]
+pub struct GetExecutableActionsResponse {
+    // Empty while the instance is blocked
+    pub actions: Vec<ExecutableAction>,
+    pub blocked: Option<InstanceBlockReason>,
+}
+
+// Check of execute_action that a simulation failed at
+#[cw_serde]
+pub enum SimulationStage {
+    // Instance state, pauses and action sequencing
+    Instance,
+    Params,
+    // Template rendering, contract whitelist and allowed messages
+    Template,
+    Authz,
+    SpendLimit,
+}
+
+#[cw_serde]
+pub struct SimulationError {
+    pub stage: SimulationStage,
+    pub message: String,
+}
+
+#[cw_serde]
+pub struct SimulateActionResponse {
+    // Messages the user would execute through authz, set whenever the template could be rendered
+    pub messages: Vec<CosmosMsg>,
+    pub error: Option<SimulationError>,
+}
+
+#[cw_serde]
+pub struct RequiredGrant {
+    pub grantee: Addr,
+    pub msg_type_url: String,
+    pub contracts: Vec<String>,
+}
+
+#[cw_serde]
+pub struct GetRequiredGrantsResponse {
+    pub grants: Vec<RequiredGrant>,
+}
+
+#[cw_serde]
+pub struct ExecutorResponse {
+    pub address: Addr,
+    pub bond: Coin,
+    pub unbonding_since: Option<Timestamp>,
+    pub active: bool,
+}
+
+#[cw_serde]
+pub struct GetExecutorResponse {
+    pub executor: Option<ExecutorResponse>,
+}
+
+#[cw_serde]
+pub struct GetExecutorsResponse {
+    pub executors: Vec<ExecutorResponse>,
+}
+
+#[cw_serde]
+pub struct GetExecutionFeeTableResponse {
+    pub table: Option<ExecutionFeeTable>,
+}
+
+#[cw_serde]
+pub struct GetAccruedExecutionFeesResponse {
+    pub user_address: Addr,
+    // USD execution fees accrued by the user's actions and not charged yet
+    pub accrued_usd: Uint128,
+}
+
+#[cw_serde]
+pub struct GetFeeBatchResponse {
+    pub batch_id: String,
+    // None if the batch wasn't processed
+    pub batch: Option<FeeBatch>,
+}
+
 #[cw_serde]
 pub struct GetUserPaymentConfigResponse {
     pub payment_config: Option<PaymentConfig>,
@@ -221,6 +700,9 @@ pub struct FeeTotal {
     pub debit_denom: String,
     pub amount: Uint128,
     pub fee_type: FeeType,
+    // Bonded executor that performed the actions covered by an execution fee
+    #[serde(default)]
+    pub executor: Option<String>,
 }
 
 #[cw_serde]
@@ -240,6 +722,47 @@ pub enum QueryMsg {
     GetWorkflowInstance { user_address: String, instance_id: u64 },
     #[returns(GetUserPaymentConfigResponse)]
     GetUserPaymentConfig { user_address: String },
+    #[returns(GetInstanceLeaseResponse)]
+    GetInstanceLease { user_address: String, instance_id: u64 },
+    #[returns(GetInstanceSpendLimitsResponse)]
+    GetInstanceSpendLimits { user_address: String, instance_id: u64 },
+    #[returns(GetExecutorResponse)]
+    GetExecutor { executor_address: String },
+    #[returns(GetExecutorsResponse)]
+    GetExecutors { start_after: Option<String>, limit: Option<u32> },
+    #[returns(GetPausesResponse)]
+    GetPauses {},
+    #[returns(GetExecutionFeeTableResponse)]
+    GetExecutionFeeTable {},
+    #[returns(GetAccruedExecutionFeesResponse)]
+    GetAccruedExecutionFees { user_address: String },
+    #[returns(GetFeeBatchResponse)]
+    GetFeeBatch { batch_id: String },
+    #[returns(GetWorkflowStatsResponse)]
+    GetWorkflowStats { workflow_id: WorkflowId },
+    // Workflows ordered by actions executed, most first
+    #[returns(GetWorkflowLeaderboardResponse)]
+    GetWorkflowLeaderboard { start_after: Option<WorkflowId>, limit: Option<u32> },
+    #[returns(GetExecutableActionsResponse)]
+    GetExecutableActions { user_address: String, instance_id: InstanceId },
+    // Dry run of ExecuteAction, executor authorization, leases and param signatures aren't checked
+    #[returns(SimulateActionResponse)]
+    SimulateAction {
+        user_address: String,
+        instance_id: InstanceId,
+        action_id: ActionId,
+        template_id: TemplateId,
+        params: Option<HashMap<String, ActionParamValue>>,
+    },
+    // Authz grants a user needs to run the workflow, including the fee manager if the user pays from its wallet
+    #[returns(GetRequiredGrantsResponse)]
+    GetRequiredGrants { workflow_id: WorkflowId, user_address: Option<String> },
+    #[returns(OwnershipResponse)]
+    Ownership {},
+    #[returns(bool)]
+    HasRole { role: String, address: String },
+    #[returns(RoleMembersResponse)]
+    RoleMembers { role: String, start_after: Option<String>, limit: Option<u32> },
     #[returns(InstantiateMsg)]
     GetConfig {},
 }
@@ -1,7 +1,12 @@
-use cosmwasm_std::{Addr, Deps, StdResult};
+use std::collections::{BTreeSet, HashMap};
+
+use cosmwasm_std::{Addr, CosmosMsg, Deps, Env, StdResult, WasmMsg};
 use crate::{
-    msg::{ActionMsg, GetInstancesResponse, GetWorkflowInstanceResponse, GetWorkflowResponse, GetUserPaymentConfigResponse, InstanceId, NewInstanceMsg, NewWorkflowMsg, WorkflowInstanceResponse, WorkflowResponse}, 
-    state::{load_workflow, load_workflow_action_params, load_workflow_action_templates, load_workflow_action_contracts, load_workflow_actions, load_workflow_instance, load_workflow_instance_params, load_workflow_instances_by_requester, load_user_payment_config, WorkflowInstance},
+    error::ContractError,
+    execute::{apply_min_return_protection, check_instance_spends, executable_action_ids, execute_dynamic_template, resolve_action_params, resolve_action_target, check_action_timing, validate_action_can_execute, validate_price_trigger, workflow_action_definitions},
+    utils::{find_missing_authz_grant, validate_param_values, MSG_EXECUTE_CONTRACT_TYPE_URL},
+    msg::{ActionBlockReason, ActionId, ActionMsg, ExecutableAction, ExecutionType, GetExecutableActionsResponse, InstanceBlockReason, PauseScope, WorkflowInstanceState, ActionParamValue, ExecutorResponse, SimulateActionResponse, SimulationError, SimulationStage, TemplateId, GetExecutorResponse, GetExecutorsResponse, GetInstanceLeaseResponse, GetPausesResponse, GetExecutionFeeTableResponse, GetAccruedExecutionFeesResponse, GetFeeBatchResponse, GetWorkflowStatsResponse, GetWorkflowLeaderboardResponse, GetRequiredGrantsResponse, GetInstanceSpendLimitsResponse, SpendLimitResponse, PauseResponse, RequiredGrant, GetInstancesResponse, GetWorkflowInstanceResponse, GetWorkflowResponse, GetUserPaymentConfigResponse, InstanceId, NewInstanceMsg, NewWorkflowMsg, WorkflowInstanceResponse, WorkflowResponse}, 
+    state::{load_workflow, load_workflow_executors, load_workflow_creator_fee_schedule, load_workflow_action_params, load_workflow_action_templates, load_workflow_action_contracts, load_workflow_action_allowed_messages, load_workflow_action_outputs, load_workflow_action_signed_params, load_workflow_action_param_constraints, load_workflow_action_sub_workflow, load_workflow_action_min_return_protection, load_workflow_action_timing, load_workflow_actions, load_workflow_instance, load_workflow_instance_params, load_workflow_instance_param_signer, load_workflow_instance_param_constraints, load_workflow_instance_price_trigger, load_workflow_instances_by_requester, load_user_payment_config, load_workflow_instance_lease, load_executor_bond, load_executor_bonds, is_active_bonded_executor, load_pauses, load_execution_fee_table, load_fee_batch, load_user_accrued_execution_fees, load_config, PaymentConfig, load_workflow_instance_spend_limits, load_workflow_instance_spends, spent_within_limit, load_workflow_instance_run_outputs, validate_actions_not_paused, load_pause, load_workflow_stats, load_workflow_stats_ranking, ExecutorBond, WorkflowInstance},
 };
 
 pub fn query_workflow_by_id(deps: Deps, workflow_id: String) -> StdResult<GetWorkflowResponse> {
@@ -17,10 +22,19 @@ pub fn query_workflow_by_id(deps: Deps, workflow_id: String) -> StdResult<GetWor
                 next_actions: action.next_actions.clone(),
                 templates: load_workflow_action_templates(deps.storage, &workflow_id, &action_id).unwrap_or_default(),
                 whitelisted_contracts: load_workflow_action_contracts(deps.storage, &workflow_id, &action_id).unwrap_or_default(),
+                allowed_messages: load_workflow_action_allowed_messages(deps.storage, &workflow_id, action_id).unwrap_or_default(),
+                outputs: load_workflow_action_outputs(deps.storage, &workflow_id, action_id).unwrap_or_default(),
+                signed_params: load_workflow_action_signed_params(deps.storage, &workflow_id, action_id).unwrap_or_default(),
+                param_constraints: load_workflow_action_param_constraints(deps.storage, &workflow_id, action_id).unwrap_or_default(),
+                sub_workflow: load_workflow_action_sub_workflow(deps.storage, &workflow_id, action_id).unwrap_or_default(),
+                min_return_protection: load_workflow_action_min_return_protection(deps.storage, &workflow_id, action_id).unwrap_or_default(),
+                timing: load_workflow_action_timing(deps.storage, &workflow_id, action_id).unwrap_or_default(),
             })).collect(),
+            creator_fee_schedule: load_workflow_creator_fee_schedule(deps.storage, &workflow_id)?,
         },
         publisher: workflow.publisher.clone(),
         state: workflow.state,
+        executors: load_workflow_executors(deps.storage, &workflow_id)?,
     } })
 }
 
@@ -56,11 +70,17 @@ fn to_workflow_instance_response(deps: Deps, requester: &Addr, instance_id: &Ins
             offchain_parameters: std::collections::HashMap::new(),
             // TODO: this field is not in state, we need a new Msg to avoid return None
             cron_expression: None,
+            param_signer: load_workflow_instance_param_signer(deps.storage, requester, instance_id).unwrap_or_default(),
+            param_constraints: load_workflow_instance_param_constraints(deps.storage, requester, instance_id).unwrap_or_default(),
+            price_trigger: load_workflow_instance_price_trigger(deps.storage, requester, instance_id).unwrap_or_default(),
+            allow_bonded_executors: instance.allow_bonded_executors,
         },
         id: instance_id.clone(),
         state: instance.state.clone(),
         requester: requester.clone(),
         last_executed_action: instance.last_executed_action.clone(),
+        last_run_outcome: instance.last_run_outcome.clone(),
+        consecutive_failures: instance.consecutive_failures,
     }
 }
 
@@ -74,4 +94,292 @@ pub fn query_user_payment_config(deps: Deps, user_address: String) -> StdResult<
     };
     
     Ok(GetUserPaymentConfigResponse { payment_config })
-}
\ No newline at end of file
+}
+pub fn query_instance_lease(deps: Deps, env: Env, user_address: String, instance_id: InstanceId) -> StdResult<GetInstanceLeaseResponse> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+
+    // Expired leases are no longer enforced, so they are not returned
+    let lease = load_workflow_instance_lease(deps.storage, &user_addr, &instance_id)?
+        .filter(|lease| env.block.time < lease.expires_at);
+
+    Ok(GetInstanceLeaseResponse { lease })
+}
+
+const DEFAULT_EXECUTORS_LIMIT: u32 = 30;
+const MAX_EXECUTORS_LIMIT: u32 = 100;
+
+pub fn query_instance_spend_limits(deps: Deps, env: Env, user_address: String, instance_id: InstanceId) -> StdResult<GetInstanceSpendLimitsResponse> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let limits = load_workflow_instance_spend_limits(deps.storage, &user_addr, &instance_id)?
+        .into_iter()
+        .map(|limit| {
+            let spends = load_workflow_instance_spends(deps.storage, &user_addr, &instance_id, &limit.denom)?;
+            Ok(SpendLimitResponse {
+                spent: spent_within_limit(&spends, &limit, &env.block.time),
+                limit,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(GetInstanceSpendLimitsResponse { limits })
+}
+
+fn to_executor_response(deps: Deps, address: Addr, executor_bond: ExecutorBond) -> StdResult<ExecutorResponse> {
+    Ok(ExecutorResponse {
+        active: is_active_bonded_executor(deps.storage, &address)?,
+        address,
+        bond: executor_bond.bond,
+        unbonding_since: executor_bond.unbonding_since,
+    })
+}
+
+pub fn query_executor(deps: Deps, executor_address: String) -> StdResult<GetExecutorResponse> {
+    let executor = deps.api.addr_validate(&executor_address)?;
+    let executor = match load_executor_bond(deps.storage, &executor)? {
+        Some(executor_bond) => Some(to_executor_response(deps, executor, executor_bond)?),
+        None => None,
+    };
+    Ok(GetExecutorResponse { executor })
+}
+
+pub fn query_executors(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<GetExecutorsResponse> {
+    let start_after = start_after.map(|address| deps.api.addr_validate(&address)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_EXECUTORS_LIMIT).min(MAX_EXECUTORS_LIMIT) as usize;
+    let executors = load_executor_bonds(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(address, executor_bond)| to_executor_response(deps, address, executor_bond))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(GetExecutorsResponse { executors })
+}
+
+pub fn query_pauses(deps: Deps) -> StdResult<GetPausesResponse> {
+    let pauses = load_pauses(deps.storage)?
+        .into_iter()
+        .map(|(scope, pause)| PauseResponse { scope, pause })
+        .collect();
+    Ok(GetPausesResponse { pauses })
+}
+
+pub fn query_execution_fee_table(deps: Deps) -> StdResult<GetExecutionFeeTableResponse> {
+    Ok(GetExecutionFeeTableResponse {
+        table: load_execution_fee_table(deps.storage)?,
+    })
+}
+
+pub fn query_accrued_execution_fees(deps: Deps, user_address: String) -> StdResult<GetAccruedExecutionFeesResponse> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let accrued_usd = load_user_accrued_execution_fees(deps.storage, &user_addr)?;
+    Ok(GetAccruedExecutionFeesResponse { user_address: user_addr, accrued_usd })
+}
+
+pub fn query_fee_batch(deps: Deps, batch_id: String) -> StdResult<GetFeeBatchResponse> {
+    let batch = load_fee_batch(deps.storage, &batch_id)?;
+    Ok(GetFeeBatchResponse { batch_id, batch })
+}
+
+pub fn query_workflow_stats(deps: Deps, workflow_id: String) -> StdResult<GetWorkflowStatsResponse> {
+    load_workflow(deps.storage, &workflow_id)?;
+    let stats = load_workflow_stats(deps.storage, &workflow_id)?;
+    Ok(GetWorkflowStatsResponse { workflow_id, stats })
+}
+
+const DEFAULT_LEADERBOARD_LIMIT: u32 = 30;
+const MAX_LEADERBOARD_LIMIT: u32 = 100;
+
+pub fn query_workflow_leaderboard(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<GetWorkflowLeaderboardResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT).min(MAX_LEADERBOARD_LIMIT) as usize;
+    let workflows = load_workflow_stats_ranking(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(workflow_id, stats)| GetWorkflowStatsResponse { workflow_id, stats })
+        .collect();
+    Ok(GetWorkflowLeaderboardResponse { workflows })
+}
+
+pub fn query_required_grants(deps: Deps, env: Env, workflow_id: String, user_address: Option<String>) -> StdResult<GetRequiredGrantsResponse> {
+    load_workflow(deps.storage, &workflow_id)?;
+
+    // Authz keeps one grant per message type, so a single grant must cover every contract
+    let mut contracts = BTreeSet::new();
+    let definitions = workflow_action_definitions(deps.storage, &workflow_id)
+        .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?;
+    for (workflow_id, action_id) in definitions {
+        contracts.extend(load_workflow_action_contracts(deps.storage, &workflow_id, &action_id)?);
+    }
+    if let Some(user_address) = user_address {
+        let user_addr = deps.api.addr_validate(&user_address)?;
+        if let Ok(PaymentConfig::Wallet { .. }) = load_user_payment_config(deps.storage, &user_addr) {
+            contracts.insert(load_config(deps.storage)?.fee_manager_address.to_string());
+        }
+    }
+
+    Ok(GetRequiredGrantsResponse {
+        grants: vec![RequiredGrant {
+            grantee: env.contract.address,
+            msg_type_url: MSG_EXECUTE_CONTRACT_TYPE_URL.to_string(),
+            contracts: contracts.into_iter().collect(),
+        }],
+    })
+}
+
+pub fn query_executable_actions(deps: Deps, env: Env, user_address: String, instance_id: InstanceId) -> StdResult<GetExecutableActionsResponse> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let user_instance = load_workflow_instance(deps.storage, &user_addr, &instance_id)?;
+
+    // Same order as execute_action: pauses, expiration, then instance state
+    let mut blocked = None;
+    for scope in [PauseScope::Global, PauseScope::Workflow { workflow_id: user_instance.workflow_id.clone() }] {
+        if let Some(pause) = load_pause(deps.storage, &scope)? {
+            blocked = Some(InstanceBlockReason::ExecutionsPaused { scope, reason: pause.reason });
+            break;
+        }
+    }
+    if blocked.is_none() {
+        blocked = if env.block.time >= user_instance.expiration_time {
+            Some(InstanceBlockReason::Expired { expiration_time: user_instance.expiration_time })
+        } else {
+            match user_instance.state {
+                WorkflowInstanceState::Running => None,
+                WorkflowInstanceState::Paused => Some(InstanceBlockReason::SchedulePaused),
+                WorkflowInstanceState::Cancelled => Some(InstanceBlockReason::Cancelled),
+                // Recurrent instances keep running after finishing a run
+                WorkflowInstanceState::Finished if user_instance.execution_type == ExecutionType::Recurrent => None,
+                WorkflowInstanceState::Finished => Some(InstanceBlockReason::Finished),
+            }
+        };
+    }
+    if blocked.is_some() {
+        return Ok(GetExecutableActionsResponse { actions: vec![], blocked });
+    }
+
+    let workflow = load_workflow(deps.storage, &user_instance.workflow_id)?;
+    let mut action_ids: Vec<ActionId> = executable_action_ids(deps.storage, &workflow, &user_instance)
+        .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?
+        .into_iter()
+        .collect();
+    action_ids.sort();
+    let actions = action_ids
+        .into_iter()
+        .map(|action_id| {
+            let target = resolve_action_target(deps.storage, &user_instance.workflow_id, &action_id)
+                .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?;
+            let starts_new_run = validate_action_can_execute(deps.storage, &env, &user_instance, &action_id)
+                .map_err(|e| cosmwasm_std::StdError::generic_err(e.to_string()))?;
+            let price_trigger = if starts_new_run {
+                validate_price_trigger(deps.storage, deps.querier, &user_addr, &instance_id)
+            } else {
+                Ok(())
+            };
+            let blocked = match price_trigger {
+                Err(e) => Some(ActionBlockReason::PriceTriggerNotMet { reason: e.to_string() }),
+                Ok(()) => check_action_timing(deps.storage, &env, &user_addr, &instance_id, &user_instance, &action_id, starts_new_run)
+                    .err()
+                    .map(|e| ActionBlockReason::ActionTimingViolation { reason: e.to_string() }),
+            };
+            Ok(ExecutableAction {
+                templates: load_workflow_action_templates(deps.storage, &target.workflow_id, &target.action_id)?,
+                action_id,
+                blocked,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(GetExecutableActionsResponse { actions, blocked: None })
+}
+
+pub fn query_simulate_action(
+    deps: Deps,
+    env: Env,
+    user_address: String,
+    instance_id: InstanceId,
+    action_id: ActionId,
+    template_id: TemplateId,
+    params: Option<HashMap<String, ActionParamValue>>,
+) -> StdResult<SimulateActionResponse> {
+    let user_addr = deps.api.addr_validate(&user_address)?;
+    let (msgs, error) = simulate_action(deps, &env, &user_addr, instance_id, &action_id, &template_id, &params);
+    Ok(SimulateActionResponse {
+        messages: msgs.into_iter().map(CosmosMsg::Wasm).collect(),
+        error,
+    })
+}
+
+// Runs the checks of execute_action in the same order, stopping at the first failure
+fn simulate_action(
+    deps: Deps,
+    env: &Env,
+    user_addr: &Addr,
+    instance_id: InstanceId,
+    action_id: &ActionId,
+    template_id: &TemplateId,
+    params: &Option<HashMap<String, ActionParamValue>>,
+) -> (Vec<WasmMsg>, Option<SimulationError>) {
+    let error = |stage: SimulationStage, error: ContractError| Some(SimulationError {
+        stage,
+        message: error.to_string(),
+    });
+
+    let user_instance = match load_workflow_instance(deps.storage, user_addr, &instance_id) {
+        Ok(user_instance) => user_instance,
+        Err(_) => {
+            return (vec![], error(SimulationStage::Instance, ContractError::InstanceNotFound {
+                instance_id: instance_id.to_string(),
+            }))
+        }
+    };
+    let checked_action = validate_actions_not_paused(deps.storage, &user_instance.workflow_id)
+        .and_then(|_| validate_action_can_execute(deps.storage, env, &user_instance, action_id))
+        .and_then(|starts_new_run| {
+            if starts_new_run {
+                validate_price_trigger(deps.storage, deps.querier, user_addr, &instance_id)?;
+            }
+            check_action_timing(deps.storage, env, user_addr, &instance_id, &user_instance, action_id, starts_new_run)?;
+            let target = resolve_action_target(deps.storage, &user_instance.workflow_id, action_id)?;
+            if target.sub_workflow.is_some() {
+                validate_actions_not_paused(deps.storage, &target.workflow_id)?;
+            }
+            Ok((starts_new_run, target))
+        });
+    let (starts_new_run, target) = match checked_action {
+        Ok(checked_action) => checked_action,
+        Err(e) => return (vec![], error(SimulationStage::Instance, e)),
+    };
+
+    // A new run starts without outputs
+    let run_outputs = if starts_new_run {
+        HashMap::new()
+    } else {
+        match load_workflow_instance_run_outputs(deps.storage, user_addr, &instance_id) {
+            Ok(run_outputs) => run_outputs,
+            Err(e) => return (vec![], error(SimulationStage::Params, e.into())),
+        }
+    };
+    if let Some(params) = params {
+        if let Err(e) = validate_param_values(deps.api, params) {
+            return (vec![], error(SimulationStage::Params, e));
+        }
+    }
+    let resolved_params = match resolve_action_params(deps.storage, deps.querier, user_addr, &instance_id, &target, params, &run_outputs) {
+        Ok(resolved_params) => resolved_params,
+        Err(e) => return (vec![], error(SimulationStage::Params, e)),
+    };
+
+    let msgs = match execute_dynamic_template(deps.storage, &target.workflow_id, &target.action_id, template_id, &resolved_params, params, &run_outputs)
+        .and_then(|msgs| apply_min_return_protection(deps.storage, deps.querier, &target, msgs))
+    {
+        Ok(msgs) => msgs,
+        Err(e) => return (vec![], error(SimulationStage::Template, e)),
+    };
+
+    for msg in &msgs {
+        if let WasmMsg::Execute { contract_addr, .. } = msg {
+            if let Some(reason) = find_missing_authz_grant(&deps.querier, env, user_addr, contract_addr) {
+                return (msgs.clone(), Some(SimulationError {
+                    stage: SimulationStage::Authz,
+                    message: format!("{} for {} to execute {}", reason, env.contract.address, contract_addr),
+                }));
+            }
+        }
+    }
+    if let Err(e) = check_instance_spends(deps.storage, env, user_addr, &instance_id, &msgs) {
+        return (msgs, error(SimulationStage::SpendLimit, e));
+    }
+    (msgs, None)
+}
@@ -1,22 +1,25 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
-use cosmwasm_std::{Addr, Order, StdResult, Storage, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Coin, Order, StdResult, Storage, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
 
 use cosmwasm_schema::cw_serde;
 
-use crate::msg::{ActionId, ActionParamValue, ExecutionType, InstanceId, ParamId, WorkflowId, WorkflowInstanceState, WorkflowState, WorkflowVisibility, TemplateId, Template};
+use crate::msg::{ActionId, ActionTiming, CreatorFeeSchedule, ActionOutput, ActionParamValue, ExecutionFeeTable, ExecutionType, ExecutorBondingConfig, InstanceId, PauseScope, MinReturnProtection, OutputId, ParamConstraint, ParamId, ParamSigner, PriceTrigger, RunOutcome, SpendLimit, SubWorkflow, WorkflowId, WorkflowInstanceState, WorkflowState, WorkflowVisibility, TemplateId, Template};
 
 use crate::ContractError;
+use auto_access_control::state::{has_role, is_owner};
 
+// Owner, publishers and executors live in the access control storage
 #[cw_serde]
 pub struct Config {
-    pub owner: Addr,
-    pub allowed_publishers: HashSet<Addr>,
-    pub allowed_action_executors: HashSet<Addr>,
     pub referral_memo: String,
     pub fee_manager_address: Addr,
+    #[serde(default)]
+    pub max_consecutive_failures: Option<u32>,
+    #[serde(default)]
+    pub executor_bonding: Option<ExecutorBondingConfig>,
 }
 
 #[cw_serde]
@@ -41,6 +44,89 @@ pub struct WorkflowInstance {
     pub execution_type: ExecutionType,
     pub expiration_time: Timestamp,
     // pub requester: Addr,
+    #[serde(default)]
+    pub last_run_outcome: Option<RunOutcome>,
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    // Start of the current run, for action timings
+    #[serde(default)]
+    pub run_started_at: Option<Timestamp>,
+    #[serde(default)]
+    pub allow_bonded_executors: bool,
+}
+
+#[cw_serde]
+pub struct InstanceLease {
+    pub holder: Addr,
+    pub expires_at: Timestamp,
+}
+
+/// What a scheduled creator fee instance did so far, to bound its creator fees
+#[cw_serde]
+pub struct CreatorFeeUsage {
+    pub created_at: Timestamp,
+    pub runs: u64,
+    // Funds sent by the actions with a funds share fee
+    pub action_funds: BTreeMap<ActionId, BTreeMap<String, Uint128>>,
+    pub charged_usd: Uint128,
+}
+
+#[cw_serde]
+pub struct Spend {
+    pub at: Timestamp,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct ExecutorBond {
+    pub bond: Coin,
+    // Set when the executor starts unbonding, it can't execute actions from then on
+    pub unbonding_since: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct Pause {
+    pub paused_by: Addr,
+    pub paused_at: Timestamp,
+    pub reason: String,
+    pub block_instances: bool,
+    pub block_fees: bool,
+}
+
+// Usage counters of a workflow. Instances are counted in the bucket of their current state,
+// so an instance finished after being cancelled moves from cancelled to finished
+#[cw_serde]
+#[derive(Default)]
+pub struct WorkflowStats {
+    pub instances_created: u64,
+    // Running or paused
+    pub instances_active: u64,
+    pub instances_finished: u64,
+    pub instances_cancelled: u64,
+    // Actions whose first message succeeded
+    pub actions_executed: u64,
+    // Creator fees charged in the debit denoms, sorted by denom
+    pub creator_fees: Vec<Coin>,
+}
+
+/// Outcome of a processed ChargeFees batch, completed by the fee manager replies
+#[cw_serde]
+pub struct FeeBatch {
+    pub processed_at: Timestamp,
+    // Users whose fees the fee manager accepted
+    pub charged_users: Vec<String>,
+    // Debit denom amounts the fee manager accepted, sorted by denom
+    pub totals: Vec<Coin>,
+    pub failures: Vec<FeeBatchFailure>,
+}
+
+/// Fee of a batch that wasn't charged, skipped by ChargeFees or rejected by the fee manager
+#[cw_serde]
+pub struct FeeBatchFailure {
+    pub user_address: String,
+    pub denom: String,
+    pub amount: Uint128,
+    pub error: String,
 }
 
 #[cw_serde]
@@ -112,15 +198,112 @@ pub fn remove_user_payment_config(storage: &mut dyn Storage, user: &Addr) -> Std
     Ok(())
 }
 
+// ==================================== 
+// ========== EXECUTION FEES ==========
+// ==================================== 
+
+// Published by the owner, execution fees aren't bounded without it
+pub const EXECUTION_FEE_TABLE: Item<ExecutionFeeTable> = Item::new("eft");
+// USD execution fees accrued by each user's executed actions and not charged yet
+pub const USER_ACCRUED_EXECUTION_FEES: Map<Addr, Uint128> = Map::new("uaef");
+// Part of a user's accrued execution fees performed by each executor, bounding executor rewards
+pub const EXECUTOR_ACCRUED_EXECUTION_FEES: Map<(Addr, Addr), Uint128> = Map::new("xaef");
+
+pub fn save_execution_fee_table(storage: &mut dyn Storage, table: &ExecutionFeeTable) -> StdResult<()> {
+    EXECUTION_FEE_TABLE.save(storage, table)
+}
+
+pub fn load_execution_fee_table(storage: &dyn Storage) -> StdResult<Option<ExecutionFeeTable>> {
+    EXECUTION_FEE_TABLE.may_load(storage)
+}
+
+pub fn remove_execution_fee_table(storage: &mut dyn Storage) -> StdResult<()> {
+    EXECUTION_FEE_TABLE.remove(storage);
+    Ok(())
+}
+
+pub fn load_user_accrued_execution_fees(storage: &dyn Storage, user: &Addr) -> StdResult<Uint128> {
+    Ok(USER_ACCRUED_EXECUTION_FEES.may_load(storage, user.clone())?.unwrap_or_default())
+}
+
+pub fn save_user_accrued_execution_fees(storage: &mut dyn Storage, user: &Addr, accrued: Uint128) -> StdResult<()> {
+    if accrued.is_zero() {
+        USER_ACCRUED_EXECUTION_FEES.remove(storage, user.clone());
+        return Ok(());
+    }
+    USER_ACCRUED_EXECUTION_FEES.save(storage, user.clone(), &accrued)
+}
+
+pub fn load_executor_accrued_execution_fees(storage: &dyn Storage, user: &Addr, executor: &Addr) -> StdResult<Uint128> {
+    Ok(EXECUTOR_ACCRUED_EXECUTION_FEES.may_load(storage, (user.clone(), executor.clone()))?.unwrap_or_default())
+}
+
+pub fn save_executor_accrued_execution_fees(storage: &mut dyn Storage, user: &Addr, executor: &Addr, accrued: Uint128) -> StdResult<()> {
+    if accrued.is_zero() {
+        EXECUTOR_ACCRUED_EXECUTION_FEES.remove(storage, (user.clone(), executor.clone()));
+        return Ok(());
+    }
+    EXECUTOR_ACCRUED_EXECUTION_FEES.save(storage, (user.clone(), executor.clone()), &accrued)
+}
+
+// =============================== 
+// ========== FEE BATCHES ========
+// =============================== 
+
+// Processed ChargeFees batches by batch id, a batch id can't be charged twice
+pub const FEE_BATCHES: Map<String, FeeBatch> = Map::new("fb");
+
+pub fn save_fee_batch(storage: &mut dyn Storage, batch_id: &str, batch: &FeeBatch) -> StdResult<()> {
+    FEE_BATCHES.save(storage, batch_id.to_string(), batch)
+}
+
+pub fn load_fee_batch(storage: &dyn Storage, batch_id: &str) -> StdResult<Option<FeeBatch>> {
+    FEE_BATCHES.may_load(storage, batch_id.to_string())
+}
+
+pub fn record_fee_batch_charge(storage: &mut dyn Storage, batch_id: &str, user_address: &str, fees: Vec<Coin>) -> StdResult<()> {
+    let Some(mut batch) = load_fee_batch(storage, batch_id)? else {
+        return Ok(());
+    };
+    if !batch.charged_users.iter().any(|charged_user| charged_user == user_address) {
+        batch.charged_users.push(user_address.to_string());
+    }
+    for fee in fees {
+        match batch.totals.binary_search_by(|coin| coin.denom.cmp(&fee.denom)) {
+            Ok(index) => batch.totals[index].amount += fee.amount,
+            Err(index) => batch.totals.insert(index, fee),
+        }
+    }
+    save_fee_batch(storage, batch_id, &batch)
+}
+
+pub fn record_fee_batch_failures(storage: &mut dyn Storage, batch_id: &str, failures: Vec<FeeBatchFailure>) -> StdResult<()> {
+    let Some(mut batch) = load_fee_batch(storage, batch_id)? else {
+        return Ok(());
+    };
+    batch.failures.extend(failures);
+    save_fee_batch(storage, batch_id, &batch)
+}
+
 // =============================== 
 // ========== WORKFLOWS ==========
 // =============================== 
 
 pub const WORKFLOWS: Map<WorkflowId, Workflow> = Map::new("w");
 pub const WORKFLOW_ACTIONS: Map<(WorkflowId, ActionId), Action> = Map::new("wa");
+// Dedicated executors of a workflow, replacing the global allowed_action_executors
+pub const WORKFLOW_EXECUTORS: Map<WorkflowId, HashSet<Addr>> = Map::new("wex");
+pub const WORKFLOW_CREATOR_FEE_SCHEDULES: Map<WorkflowId, CreatorFeeSchedule> = Map::new("wcfs");
 pub const WORKFLOW_ACTION_PARAMS: Map<(WorkflowId, ActionId), HashMap<ParamId, ActionParamValue>> = Map::new("wap");
 pub const WORKFLOW_ACTION_TEMPLATES: Map<(WorkflowId, ActionId, TemplateId), Template> = Map::new("wat");
-pub const WORKFLOW_ACTION_CONTRACTS: Map<(WorkflowId, ActionId, String), ()> = Map::new("wac");
+// Whitelisted contracts with their allowed top-level message keys (None allows any message)
+pub const WORKFLOW_ACTION_CONTRACTS: Map<(WorkflowId, ActionId, String), Option<HashSet<String>>> = Map::new("wac");
+pub const WORKFLOW_ACTION_OUTPUTS: Map<(WorkflowId, ActionId), HashMap<OutputId, ActionOutput>> = Map::new("wao");
+pub const WORKFLOW_ACTION_SIGNED_PARAMS: Map<(WorkflowId, ActionId), HashSet<ParamId>> = Map::new("wasp");
+pub const WORKFLOW_ACTION_PARAM_CONSTRAINTS: Map<(WorkflowId, ActionId), HashMap<ParamId, ParamConstraint>> = Map::new("wapc");
+pub const WORKFLOW_ACTION_SUB_WORKFLOWS: Map<(WorkflowId, ActionId), SubWorkflow> = Map::new("wasw");
+pub const WORKFLOW_ACTION_MIN_RETURN_PROTECTIONS: Map<(WorkflowId, ActionId), MinReturnProtection> = Map::new("wamr");
+pub const WORKFLOW_ACTION_TIMINGS: Map<(WorkflowId, ActionId), ActionTiming> = Map::new("wati");
 
 pub fn save_workflow(storage: &mut dyn Storage, id: &WorkflowId, workflow: &Workflow) -> StdResult<()> {
     WORKFLOWS.save(storage, id.clone(), workflow)
@@ -132,6 +315,8 @@ pub fn load_workflow(storage: &dyn Storage, workflow_id: &WorkflowId) -> StdResu
 
 pub fn remove_workflow(storage: &mut dyn Storage, workflow_id: &WorkflowId) -> StdResult<()> {
     WORKFLOWS.remove(storage, workflow_id.clone());
+    remove_workflow_executors(storage, workflow_id)?;
+    remove_workflow_creator_fee_schedule(storage, workflow_id)?;
     // Remove all actions for this workflow
     let actions = WORKFLOW_ACTIONS.prefix(workflow_id.clone()).keys(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
     for action_id in actions {
@@ -140,6 +325,32 @@ pub fn remove_workflow(storage: &mut dyn Storage, workflow_id: &WorkflowId) -> S
     Ok(())
 }
 
+pub fn save_workflow_creator_fee_schedule(storage: &mut dyn Storage, workflow_id: &WorkflowId, schedule: &CreatorFeeSchedule) -> StdResult<()> {
+    WORKFLOW_CREATOR_FEE_SCHEDULES.save(storage, workflow_id.clone(), schedule)
+}
+
+pub fn load_workflow_creator_fee_schedule(storage: &dyn Storage, workflow_id: &WorkflowId) -> StdResult<Option<CreatorFeeSchedule>> {
+    WORKFLOW_CREATOR_FEE_SCHEDULES.may_load(storage, workflow_id.clone())
+}
+
+pub fn remove_workflow_creator_fee_schedule(storage: &mut dyn Storage, workflow_id: &WorkflowId) -> StdResult<()> {
+    WORKFLOW_CREATOR_FEE_SCHEDULES.remove(storage, workflow_id.clone());
+    Ok(())
+}
+
+pub fn save_workflow_executors(storage: &mut dyn Storage, workflow_id: &WorkflowId, executors: &HashSet<Addr>) -> StdResult<()> {
+    WORKFLOW_EXECUTORS.save(storage, workflow_id.clone(), executors)
+}
+
+pub fn load_workflow_executors(storage: &dyn Storage, workflow_id: &WorkflowId) -> StdResult<Option<HashSet<Addr>>> {
+    WORKFLOW_EXECUTORS.may_load(storage, workflow_id.clone())
+}
+
+pub fn remove_workflow_executors(storage: &mut dyn Storage, workflow_id: &WorkflowId) -> StdResult<()> {
+    WORKFLOW_EXECUTORS.remove(storage, workflow_id.clone());
+    Ok(())
+}
+
 pub fn save_workflow_action(storage: &mut dyn Storage, id: &WorkflowId, action_id: &ActionId, action: &Action) -> StdResult<()> {
     WORKFLOW_ACTIONS.save(storage, (id.clone(), action_id.clone()), action)
 }
@@ -163,6 +374,12 @@ pub fn remove_workflow_action(storage: &mut dyn Storage, workflow_id: &WorkflowI
     remove_workflow_action_params(storage, workflow_id, action_id)?;
     remove_workflow_action_templates(storage, workflow_id, action_id)?;
     remove_workflow_action_contracts(storage, workflow_id, action_id)?;
+    remove_workflow_action_outputs(storage, workflow_id, action_id)?;
+    remove_workflow_action_signed_params(storage, workflow_id, action_id)?;
+    remove_workflow_action_param_constraints(storage, workflow_id, action_id)?;
+    remove_workflow_action_sub_workflow(storage, workflow_id, action_id)?;
+    remove_workflow_action_min_return_protection(storage, workflow_id, action_id)?;
+    remove_workflow_action_timing(storage, workflow_id, action_id)?;
     Ok(())
 }
 
@@ -208,17 +425,28 @@ pub fn remove_workflow_action_templates(storage: &mut dyn Storage, workflow_id:
     Ok(())
 }
 
-pub fn save_workflow_action_contracts(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, contracts: &HashSet<String>) -> StdResult<()> {
+pub fn save_workflow_action_contracts(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, contracts: &HashSet<String>, allowed_messages: &HashMap<String, HashSet<String>>) -> StdResult<()> {
     for contract in contracts {
-        WORKFLOW_ACTION_CONTRACTS.save(storage, (workflow_id.clone(), action_id.clone(), contract.clone()), &())?;
+        WORKFLOW_ACTION_CONTRACTS.save(storage, (workflow_id.clone(), action_id.clone(), contract.clone()), &allowed_messages.get(contract).cloned())?;
     }
     Ok(())
 }
 
-pub fn load_workflow_action_contract(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, contract_addr: &str) -> StdResult<()> {
+pub fn load_workflow_action_contract(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, contract_addr: &str) -> StdResult<Option<HashSet<String>>> {
     WORKFLOW_ACTION_CONTRACTS.load(storage, (workflow_id.clone(), action_id.clone(), contract_addr.to_string()))
 }
 
+pub fn load_workflow_action_allowed_messages(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<HashMap<String, HashSet<String>>> {
+    WORKFLOW_ACTION_CONTRACTS.prefix((workflow_id.clone(), action_id.clone()))
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((contract, Some(messages))) => Some(Ok((contract, messages))),
+            Ok((_, None)) => None,
+            Err(e) => Some(Err(e)),
+        })
+        .collect()
+}
+
 pub fn load_workflow_action_contracts(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<HashSet<String>> {
     let contracts = WORKFLOW_ACTION_CONTRACTS.prefix((workflow_id.clone(), action_id.clone())).keys(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
     Ok(contracts.into_iter().collect())
@@ -232,12 +460,108 @@ pub fn remove_workflow_action_contracts(storage: &mut dyn Storage, workflow_id:
     Ok(())
 }
 
+pub fn save_workflow_action_outputs(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, outputs: &HashMap<OutputId, ActionOutput>) -> StdResult<()> {
+    WORKFLOW_ACTION_OUTPUTS.save(storage, (workflow_id.clone(), action_id.clone()), outputs)
+}
+
+pub fn load_workflow_action_outputs(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<HashMap<OutputId, ActionOutput>> {
+    Ok(WORKFLOW_ACTION_OUTPUTS.may_load(storage, (workflow_id.clone(), action_id.clone()))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_action_outputs(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<()> {
+    WORKFLOW_ACTION_OUTPUTS.remove(storage, (workflow_id.clone(), action_id.clone()));
+    Ok(())
+}
+
+pub fn save_workflow_action_signed_params(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, signed_params: &HashSet<ParamId>) -> StdResult<()> {
+    WORKFLOW_ACTION_SIGNED_PARAMS.save(storage, (workflow_id.clone(), action_id.clone()), signed_params)
+}
+
+pub fn load_workflow_action_signed_params(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<HashSet<ParamId>> {
+    Ok(WORKFLOW_ACTION_SIGNED_PARAMS.may_load(storage, (workflow_id.clone(), action_id.clone()))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_action_signed_params(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<()> {
+    WORKFLOW_ACTION_SIGNED_PARAMS.remove(storage, (workflow_id.clone(), action_id.clone()));
+    Ok(())
+}
+
+pub fn save_workflow_action_param_constraints(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, constraints: &HashMap<ParamId, ParamConstraint>) -> StdResult<()> {
+    WORKFLOW_ACTION_PARAM_CONSTRAINTS.save(storage, (workflow_id.clone(), action_id.clone()), constraints)
+}
+
+pub fn load_workflow_action_param_constraints(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<HashMap<ParamId, ParamConstraint>> {
+    Ok(WORKFLOW_ACTION_PARAM_CONSTRAINTS.may_load(storage, (workflow_id.clone(), action_id.clone()))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_action_param_constraints(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<()> {
+    WORKFLOW_ACTION_PARAM_CONSTRAINTS.remove(storage, (workflow_id.clone(), action_id.clone()));
+    Ok(())
+}
+
+pub fn save_workflow_action_sub_workflow(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, sub_workflow: &SubWorkflow) -> StdResult<()> {
+    WORKFLOW_ACTION_SUB_WORKFLOWS.save(storage, (workflow_id.clone(), action_id.clone()), sub_workflow)
+}
+
+pub fn load_workflow_action_sub_workflow(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<Option<SubWorkflow>> {
+    WORKFLOW_ACTION_SUB_WORKFLOWS.may_load(storage, (workflow_id.clone(), action_id.clone()))
+}
+
+pub fn remove_workflow_action_sub_workflow(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<()> {
+    WORKFLOW_ACTION_SUB_WORKFLOWS.remove(storage, (workflow_id.clone(), action_id.clone()));
+    Ok(())
+}
+
+pub fn save_workflow_action_min_return_protection(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, protection: &MinReturnProtection) -> StdResult<()> {
+    WORKFLOW_ACTION_MIN_RETURN_PROTECTIONS.save(storage, (workflow_id.clone(), action_id.clone()), protection)
+}
+
+pub fn load_workflow_action_min_return_protection(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<Option<MinReturnProtection>> {
+    WORKFLOW_ACTION_MIN_RETURN_PROTECTIONS.may_load(storage, (workflow_id.clone(), action_id.clone()))
+}
+
+pub fn remove_workflow_action_min_return_protection(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<()> {
+    WORKFLOW_ACTION_MIN_RETURN_PROTECTIONS.remove(storage, (workflow_id.clone(), action_id.clone()));
+    Ok(())
+}
+
+pub fn save_workflow_action_timing(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId, timing: &ActionTiming) -> StdResult<()> {
+    WORKFLOW_ACTION_TIMINGS.save(storage, (workflow_id.clone(), action_id.clone()), timing)
+}
+
+pub fn load_workflow_action_timing(storage: &dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<Option<ActionTiming>> {
+    WORKFLOW_ACTION_TIMINGS.may_load(storage, (workflow_id.clone(), action_id.clone()))
+}
+
+pub fn remove_workflow_action_timing(storage: &mut dyn Storage, workflow_id: &WorkflowId, action_id: &ActionId) -> StdResult<()> {
+    WORKFLOW_ACTION_TIMINGS.remove(storage, (workflow_id.clone(), action_id.clone()));
+    Ok(())
+}
+
 // ========================================
 // ========== WORKFLOW INSTANCES ==========
 // ========================================
 
 pub const WORKFLOW_INSTANCES: Map<(Addr, InstanceId), WorkflowInstance> = Map::new("wi");
 pub const WORKFLOW_INSTANCE_PARAMS: Map<(Addr, InstanceId), HashMap<ParamId, ActionParamValue>>= Map::new("wip");
+// Outputs captured during the current run, keyed by "<action_id>.<output_id>"
+pub const WORKFLOW_INSTANCE_RUN_OUTPUTS: Map<(Addr, InstanceId), HashMap<String, String>> = Map::new("wiro");
+pub const WORKFLOW_INSTANCE_PARAM_SIGNERS: Map<(Addr, InstanceId), ParamSigner> = Map::new("wips");
+pub const WORKFLOW_INSTANCE_PARAM_CONSTRAINTS: Map<(Addr, InstanceId), HashMap<ParamId, ParamConstraint>> = Map::new("wipc");
+pub const WORKFLOW_INSTANCE_PRICE_TRIGGERS: Map<(Addr, InstanceId), PriceTrigger> = Map::new("wipt");
+pub const WORKFLOW_INSTANCE_LEASES: Map<(Addr, InstanceId), InstanceLease> = Map::new("wil");
+// Execution ids already accepted by execute_action, with the block time they were executed at
+pub const WORKFLOW_INSTANCE_EXECUTIONS: Map<(Addr, InstanceId, String), Timestamp> = Map::new("wie");
+// Seconds an execution id is kept, executors must not resubmit an execution after that
+pub const EXECUTION_ID_RETENTION: u64 = 7 * 24 * 60 * 60;
+// Last nonce accepted in a params signature, nonces must be strictly increasing
+pub const WORKFLOW_INSTANCE_PARAMS_NONCES: Map<(Addr, InstanceId), u64> = Map::new("wipn");
+pub const WORKFLOW_INSTANCE_SPEND_LIMITS: Map<(Addr, InstanceId), Vec<SpendLimit>> = Map::new("wisl");
+// Funds sent by the instance's actions per denom, only kept while a limit still covers them
+pub const WORKFLOW_INSTANCE_SPENDS: Map<(Addr, InstanceId, String), Vec<Spend>> = Map::new("wisp");
+pub const WORKFLOW_INSTANCE_CREATOR_FEE_USAGES: Map<(Addr, InstanceId), CreatorFeeUsage> = Map::new("wicf");
+// Recent executions of the rate limited actions of an instance
+pub const WORKFLOW_INSTANCE_ACTION_EXECUTIONS: Map<(Addr, InstanceId, ActionId), Vec<Timestamp>> = Map::new("wiae");
 
 // requester_addr -> HashMap<instance_id, WorkflowInstance>
 
@@ -252,6 +576,16 @@ pub fn load_workflow_instance(storage: &dyn Storage, requester: &Addr, instance_
 pub fn remove_workflow_instance(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
     WORKFLOW_INSTANCES.remove(storage, (requester.clone(), instance_id.clone()));
     remove_workflow_instance_params(storage, requester, instance_id)?;
+    remove_workflow_instance_run_outputs(storage, requester, instance_id)?;
+    remove_workflow_instance_param_signer(storage, requester, instance_id)?;
+    remove_workflow_instance_param_constraints(storage, requester, instance_id)?;
+    remove_workflow_instance_price_trigger(storage, requester, instance_id)?;
+    remove_workflow_instance_executions(storage, requester, instance_id)?;
+    remove_workflow_instance_lease(storage, requester, instance_id)?;
+    remove_workflow_instance_spend_limits(storage, requester, instance_id)?;
+    remove_workflow_instance_spends(storage, requester, instance_id)?;
+    remove_workflow_instance_action_executions(storage, requester, instance_id)?;
+    remove_workflow_instance_creator_fee_usage(storage, requester, instance_id)?;
     Ok(())
 }
 
@@ -278,6 +612,396 @@ pub fn remove_workflow_instance_params(storage: &mut dyn Storage, requester: &Ad
     Ok(())
 }
 
+pub fn save_workflow_instance_run_outputs(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, outputs: &HashMap<String, String>) -> StdResult<()> {
+    WORKFLOW_INSTANCE_RUN_OUTPUTS.save(storage, (requester.clone(), *instance_id), outputs)
+}
+
+pub fn load_workflow_instance_run_outputs(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<HashMap<String, String>> {
+    Ok(WORKFLOW_INSTANCE_RUN_OUTPUTS.may_load(storage, (requester.clone(), *instance_id))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_instance_run_outputs(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_RUN_OUTPUTS.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_param_signer(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, signer: &ParamSigner) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PARAM_SIGNERS.save(storage, (requester.clone(), *instance_id), signer)
+}
+
+pub fn load_workflow_instance_param_signer(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<Option<ParamSigner>> {
+    WORKFLOW_INSTANCE_PARAM_SIGNERS.may_load(storage, (requester.clone(), *instance_id))
+}
+
+pub fn remove_workflow_instance_param_signer(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PARAM_SIGNERS.remove(storage, (requester.clone(), *instance_id));
+    WORKFLOW_INSTANCE_PARAMS_NONCES.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_param_constraints(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, constraints: &HashMap<ParamId, ParamConstraint>) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PARAM_CONSTRAINTS.save(storage, (requester.clone(), *instance_id), constraints)
+}
+
+pub fn load_workflow_instance_param_constraints(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<HashMap<ParamId, ParamConstraint>> {
+    Ok(WORKFLOW_INSTANCE_PARAM_CONSTRAINTS.may_load(storage, (requester.clone(), *instance_id))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_instance_param_constraints(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PARAM_CONSTRAINTS.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_price_trigger(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, trigger: &PriceTrigger) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PRICE_TRIGGERS.save(storage, (requester.clone(), *instance_id), trigger)
+}
+
+pub fn load_workflow_instance_price_trigger(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<Option<PriceTrigger>> {
+    WORKFLOW_INSTANCE_PRICE_TRIGGERS.may_load(storage, (requester.clone(), *instance_id))
+}
+
+pub fn remove_workflow_instance_price_trigger(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PRICE_TRIGGERS.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_lease(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, lease: &InstanceLease) -> StdResult<()> {
+    WORKFLOW_INSTANCE_LEASES.save(storage, (requester.clone(), *instance_id), lease)
+}
+
+pub fn load_workflow_instance_lease(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<Option<InstanceLease>> {
+    WORKFLOW_INSTANCE_LEASES.may_load(storage, (requester.clone(), *instance_id))
+}
+
+pub fn remove_workflow_instance_lease(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_LEASES.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_spend_limits(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, limits: &Vec<SpendLimit>) -> StdResult<()> {
+    WORKFLOW_INSTANCE_SPEND_LIMITS.save(storage, (requester.clone(), *instance_id), limits)
+}
+
+pub fn load_workflow_instance_spend_limits(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<Vec<SpendLimit>> {
+    Ok(WORKFLOW_INSTANCE_SPEND_LIMITS.may_load(storage, (requester.clone(), *instance_id))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_instance_spend_limits(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_SPEND_LIMITS.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_spends(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, denom: &str, spends: &Vec<Spend>) -> StdResult<()> {
+    WORKFLOW_INSTANCE_SPENDS.save(storage, (requester.clone(), *instance_id, denom.to_string()), spends)
+}
+
+pub fn load_workflow_instance_spends(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId, denom: &str) -> StdResult<Vec<Spend>> {
+    Ok(WORKFLOW_INSTANCE_SPENDS.may_load(storage, (requester.clone(), *instance_id, denom.to_string()))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_instance_spends(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    let denoms = WORKFLOW_INSTANCE_SPENDS.prefix((requester.clone(), *instance_id)).keys(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for denom in denoms {
+        WORKFLOW_INSTANCE_SPENDS.remove(storage, (requester.clone(), *instance_id, denom));
+    }
+    Ok(())
+}
+
+pub fn save_workflow_instance_creator_fee_usage(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, usage: &CreatorFeeUsage) -> StdResult<()> {
+    WORKFLOW_INSTANCE_CREATOR_FEE_USAGES.save(storage, (requester.clone(), *instance_id), usage)
+}
+
+pub fn load_workflow_instance_creator_fee_usage(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<Option<CreatorFeeUsage>> {
+    WORKFLOW_INSTANCE_CREATOR_FEE_USAGES.may_load(storage, (requester.clone(), *instance_id))
+}
+
+pub fn remove_workflow_instance_creator_fee_usage(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    WORKFLOW_INSTANCE_CREATOR_FEE_USAGES.remove(storage, (requester.clone(), *instance_id));
+    Ok(())
+}
+
+pub fn save_workflow_instance_action_executions(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, action_id: &ActionId, executions: &Vec<Timestamp>) -> StdResult<()> {
+    WORKFLOW_INSTANCE_ACTION_EXECUTIONS.save(storage, (requester.clone(), *instance_id, action_id.clone()), executions)
+}
+
+pub fn load_workflow_instance_action_executions(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId, action_id: &ActionId) -> StdResult<Vec<Timestamp>> {
+    Ok(WORKFLOW_INSTANCE_ACTION_EXECUTIONS.may_load(storage, (requester.clone(), *instance_id, action_id.clone()))?.unwrap_or_default())
+}
+
+pub fn remove_workflow_instance_action_executions(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    let action_ids = WORKFLOW_INSTANCE_ACTION_EXECUTIONS.prefix((requester.clone(), *instance_id)).keys(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for action_id in action_ids {
+        WORKFLOW_INSTANCE_ACTION_EXECUTIONS.remove(storage, (requester.clone(), *instance_id, action_id));
+    }
+    Ok(())
+}
+
+// Total spent inside the limit's period, or over the whole instance life without period
+pub fn spent_within_limit(spends: &[Spend], limit: &SpendLimit, now: &Timestamp) -> Uint128 {
+    spends
+        .iter()
+        .filter(|spend| match limit.period {
+            Some(period) => spend.at.plus_seconds(period) > *now,
+            None => true,
+        })
+        .map(|spend| spend.amount)
+        .sum()
+}
+
+// Also drops the instance's execution ids older than the retention, recurrent instances would grow without bound
+pub fn save_workflow_instance_execution(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, execution_id: &str, executed_at: &Timestamp) -> StdResult<()> {
+    let expired = WORKFLOW_INSTANCE_EXECUTIONS
+        .prefix((requester.clone(), *instance_id))
+        .range(storage, None, None, Order::Ascending)
+        .filter(|entry| match entry {
+            Ok((_, at)) => at.plus_seconds(EXECUTION_ID_RETENTION) <= *executed_at,
+            Err(_) => true,
+        })
+        .map(|entry| entry.map(|(expired_id, _)| expired_id))
+        .collect::<StdResult<Vec<_>>>()?;
+    for expired_id in expired {
+        WORKFLOW_INSTANCE_EXECUTIONS.remove(storage, (requester.clone(), *instance_id, expired_id));
+    }
+    WORKFLOW_INSTANCE_EXECUTIONS.save(storage, (requester.clone(), *instance_id, execution_id.to_string()), executed_at)
+}
+
+pub fn has_workflow_instance_execution(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId, execution_id: &str) -> bool {
+    WORKFLOW_INSTANCE_EXECUTIONS.has(storage, (requester.clone(), *instance_id, execution_id.to_string()))
+}
+
+pub fn remove_workflow_instance_execution(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, execution_id: &str) -> StdResult<()> {
+    WORKFLOW_INSTANCE_EXECUTIONS.remove(storage, (requester.clone(), *instance_id, execution_id.to_string()));
+    Ok(())
+}
+
+pub fn remove_workflow_instance_executions(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<()> {
+    let execution_ids = WORKFLOW_INSTANCE_EXECUTIONS.prefix((requester.clone(), *instance_id)).keys(storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+    for execution_id in execution_ids {
+        WORKFLOW_INSTANCE_EXECUTIONS.remove(storage, (requester.clone(), *instance_id, execution_id));
+    }
+    Ok(())
+}
+
+pub fn save_workflow_instance_params_nonce(storage: &mut dyn Storage, requester: &Addr, instance_id: &InstanceId, nonce: u64) -> StdResult<()> {
+    WORKFLOW_INSTANCE_PARAMS_NONCES.save(storage, (requester.clone(), *instance_id), &nonce)
+}
+
+pub fn load_workflow_instance_params_nonce(storage: &dyn Storage, requester: &Addr, instance_id: &InstanceId) -> StdResult<Option<u64>> {
+    WORKFLOW_INSTANCE_PARAMS_NONCES.may_load(storage, (requester.clone(), *instance_id))
+}
+
+// =============================== 
+// ========== EXECUTORS ==========
+// =============================== 
+
+// Executors that joined permissionlessly by bonding funds
+pub const EXECUTOR_BONDS: Map<Addr, ExecutorBond> = Map::new("eb");
+
+pub fn save_executor_bond(storage: &mut dyn Storage, executor: &Addr, bond: &ExecutorBond) -> StdResult<()> {
+    EXECUTOR_BONDS.save(storage, executor.clone(), bond)
+}
+
+pub fn load_executor_bond(storage: &dyn Storage, executor: &Addr) -> StdResult<Option<ExecutorBond>> {
+    EXECUTOR_BONDS.may_load(storage, executor.clone())
+}
+
+pub fn remove_executor_bond(storage: &mut dyn Storage, executor: &Addr) -> StdResult<()> {
+    EXECUTOR_BONDS.remove(storage, executor.clone());
+    Ok(())
+}
+
+pub fn load_executor_bonds(storage: &dyn Storage, start_after: Option<Addr>, limit: usize) -> StdResult<Vec<(Addr, ExecutorBond)>> {
+    EXECUTOR_BONDS
+        .range(storage, start_after.map(cw_storage_plus::Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+// A bonded executor can execute actions while bonding is enabled, it's not unbonding
+// and its bond (after slashing) still covers the minimum
+pub fn is_active_bonded_executor(storage: &dyn Storage, executor: &Addr) -> StdResult<bool> {
+    let Some(bonding) = load_config(storage)?.executor_bonding else {
+        return Ok(false);
+    };
+    Ok(match load_executor_bond(storage, executor)? {
+        Some(bond) => bond.unbonding_since.is_none()
+            && bond.bond.denom == bonding.min_bond.denom
+            && bond.bond.amount >= bonding.min_bond.amount,
+        None => false,
+    })
+}
+
+// =============================== 
+// ========== PAUSES =============
+// =============================== 
+
+pub const GLOBAL_PAUSE: Item<Pause> = Item::new("gp");
+pub const WORKFLOW_PAUSES: Map<WorkflowId, Pause> = Map::new("wp");
+pub const CONTRACT_PAUSES: Map<String, Pause> = Map::new("cp");
+
+pub fn save_pause(storage: &mut dyn Storage, scope: &PauseScope, pause: &Pause) -> StdResult<()> {
+    match scope {
+        PauseScope::Global => GLOBAL_PAUSE.save(storage, pause),
+        PauseScope::Workflow { workflow_id } => WORKFLOW_PAUSES.save(storage, workflow_id.clone(), pause),
+        PauseScope::Contract { contract } => CONTRACT_PAUSES.save(storage, contract.clone(), pause),
+    }
+}
+
+pub fn load_pause(storage: &dyn Storage, scope: &PauseScope) -> StdResult<Option<Pause>> {
+    match scope {
+        PauseScope::Global => GLOBAL_PAUSE.may_load(storage),
+        PauseScope::Workflow { workflow_id } => WORKFLOW_PAUSES.may_load(storage, workflow_id.clone()),
+        PauseScope::Contract { contract } => CONTRACT_PAUSES.may_load(storage, contract.clone()),
+    }
+}
+
+pub fn remove_pause(storage: &mut dyn Storage, scope: &PauseScope) {
+    match scope {
+        PauseScope::Global => GLOBAL_PAUSE.remove(storage),
+        PauseScope::Workflow { workflow_id } => WORKFLOW_PAUSES.remove(storage, workflow_id.clone()),
+        PauseScope::Contract { contract } => CONTRACT_PAUSES.remove(storage, contract.clone()),
+    }
+}
+
+pub fn load_pauses(storage: &dyn Storage) -> StdResult<Vec<(PauseScope, Pause)>> {
+    let mut pauses = vec![];
+    if let Some(pause) = GLOBAL_PAUSE.may_load(storage)? {
+        pauses.push((PauseScope::Global, pause));
+    }
+    for entry in WORKFLOW_PAUSES.range(storage, None, None, Order::Ascending) {
+        let (workflow_id, pause) = entry?;
+        pauses.push((PauseScope::Workflow { workflow_id }, pause));
+    }
+    for entry in CONTRACT_PAUSES.range(storage, None, None, Order::Ascending) {
+        let (contract, pause) = entry?;
+        pauses.push((PauseScope::Contract { contract }, pause));
+    }
+    Ok(pauses)
+}
+
+fn paused_error(scope: PauseScope, pause: Pause) -> ContractError {
+    ContractError::ExecutionsPaused {
+        scope: scope.to_string(),
+        reason: pause.reason,
+    }
+}
+
+// Actions can't be executed while the contract or their workflow is paused
+pub fn validate_actions_not_paused(storage: &dyn Storage, workflow_id: &WorkflowId) -> Result<(), ContractError> {
+    for scope in [PauseScope::Global, PauseScope::Workflow { workflow_id: workflow_id.clone() }] {
+        if let Some(pause) = load_pause(storage, &scope)? {
+            return Err(paused_error(scope, pause));
+        }
+    }
+    Ok(())
+}
+
+// New instances are only blocked by pauses that opted into it
+pub fn validate_instances_not_paused(storage: &dyn Storage, workflow_id: &WorkflowId) -> Result<(), ContractError> {
+    for scope in [PauseScope::Global, PauseScope::Workflow { workflow_id: workflow_id.clone() }] {
+        if let Some(pause) = load_pause(storage, &scope)? {
+            if pause.block_instances {
+                return Err(paused_error(scope, pause));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_fees_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    match GLOBAL_PAUSE.may_load(storage)? {
+        Some(pause) if pause.block_fees => Err(paused_error(PauseScope::Global, pause)),
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_contract_not_paused(storage: &dyn Storage, contract_addr: &str) -> Result<(), ContractError> {
+    let scope = PauseScope::Contract { contract: contract_addr.to_string() };
+    match load_pause(storage, &scope)? {
+        Some(pause) => Err(paused_error(scope, pause)),
+        None => Ok(()),
+    }
+}
+
+// =============================== 
+// ========== STATS ==============
+// =============================== 
+
+pub const WORKFLOW_STATS: Map<WorkflowId, WorkflowStats> = Map::new("ws");
+// Workflows ranked by actions executed: (actions_executed, workflow_id) -> ()
+pub const WORKFLOW_STATS_RANKING: Map<(u64, WorkflowId), ()> = Map::new("wsr");
+
+pub fn load_workflow_stats(storage: &dyn Storage, workflow_id: &WorkflowId) -> StdResult<WorkflowStats> {
+    Ok(WORKFLOW_STATS.may_load(storage, workflow_id.clone())?.unwrap_or_default())
+}
+
+fn update_workflow_stats(storage: &mut dyn Storage, workflow_id: &WorkflowId, update: impl FnOnce(&mut WorkflowStats)) -> StdResult<()> {
+    let mut stats = load_workflow_stats(storage, workflow_id)?;
+    let previous_actions_executed = stats.actions_executed;
+    update(&mut stats);
+    if stats.actions_executed != previous_actions_executed || !WORKFLOW_STATS.has(storage, workflow_id.clone()) {
+        WORKFLOW_STATS_RANKING.remove(storage, (previous_actions_executed, workflow_id.clone()));
+        WORKFLOW_STATS_RANKING.save(storage, (stats.actions_executed, workflow_id.clone()), &())?;
+    }
+    WORKFLOW_STATS.save(storage, workflow_id.clone(), &stats)
+}
+
+fn instance_state_counter<'a>(stats: &'a mut WorkflowStats, state: &WorkflowInstanceState) -> &'a mut u64 {
+    match state {
+        WorkflowInstanceState::Running | WorkflowInstanceState::Paused => &mut stats.instances_active,
+        WorkflowInstanceState::Finished => &mut stats.instances_finished,
+        WorkflowInstanceState::Cancelled => &mut stats.instances_cancelled,
+    }
+}
+
+pub fn record_workflow_instance_created(storage: &mut dyn Storage, workflow_id: &WorkflowId) -> StdResult<()> {
+    update_workflow_stats(storage, workflow_id, |stats| {
+        stats.instances_created += 1;
+        stats.instances_active += 1;
+    })
+}
+
+// Instances created before stats were tracked aren't counted, hence the saturating decrement
+pub fn record_workflow_instance_state_change(storage: &mut dyn Storage, workflow_id: &WorkflowId, from: &WorkflowInstanceState, to: &WorkflowInstanceState) -> StdResult<()> {
+    update_workflow_stats(storage, workflow_id, |stats| {
+        let previous = instance_state_counter(stats, from);
+        *previous = previous.saturating_sub(1);
+        *instance_state_counter(stats, to) += 1;
+    })
+}
+
+pub fn record_workflow_action_executed(storage: &mut dyn Storage, workflow_id: &WorkflowId) -> StdResult<()> {
+    update_workflow_stats(storage, workflow_id, |stats| stats.actions_executed += 1)
+}
+
+pub fn record_workflow_creator_fee(storage: &mut dyn Storage, workflow_id: &WorkflowId, fee: Coin) -> StdResult<()> {
+    update_workflow_stats(storage, workflow_id, |stats| {
+        match stats.creator_fees.binary_search_by(|coin| coin.denom.cmp(&fee.denom)) {
+            Ok(index) => stats.creator_fees[index].amount += fee.amount,
+            Err(index) => stats.creator_fees.insert(index, fee),
+        }
+    })
+}
+
+// Workflows with the most actions executed first, ties broken by descending workflow id
+pub fn load_workflow_stats_ranking(storage: &dyn Storage, start_after: Option<WorkflowId>, limit: usize) -> StdResult<Vec<(WorkflowId, WorkflowStats)>> {
+    let max = match start_after {
+        Some(workflow_id) => {
+            let stats = load_workflow_stats(storage, &workflow_id)?;
+            Some(cw_storage_plus::Bound::exclusive((stats.actions_executed, workflow_id)))
+        }
+        None => None,
+    };
+    WORKFLOW_STATS_RANKING
+        .keys(storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|key| {
+            let (_, workflow_id) = key?;
+            let stats = load_workflow_stats(storage, &workflow_id)?;
+            Ok((workflow_id, stats))
+        })
+        .collect()
+}
 
 // =============================== 
 // ========== COUNTERS ==========
@@ -298,6 +1022,27 @@ pub fn load_next_instance_id(storage: &mut dyn Storage) -> StdResult<u64> {
 
 pub const CONFIG: Item<Config> = Item::new("conf");
 
+pub const PUBLISHER_ROLE: &str = "publisher";
+pub const EXECUTOR_ROLE: &str = "executor";
+pub const GUARDIAN_ROLE: &str = "guardian";
+pub const ROLES: &[&str] = &[PUBLISHER_ROLE, EXECUTOR_ROLE, GUARDIAN_ROLE];
+
+// Config as stored before roles moved to access control, only read by migrate
+#[cw_serde]
+pub struct LegacyConfig {
+    pub owner: Addr,
+    pub allowed_publishers: HashSet<Addr>,
+    pub allowed_action_executors: HashSet<Addr>,
+    pub referral_memo: String,
+    pub fee_manager_address: Addr,
+    #[serde(default)]
+    pub max_consecutive_failures: Option<u32>,
+    #[serde(default)]
+    pub executor_bonding: Option<ExecutorBondingConfig>,
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("conf");
+
 pub fn save_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
     CONFIG.save(storage, config)
 }
@@ -310,8 +1055,7 @@ pub fn validate_sender_is_publisher(
     storage: &dyn Storage,
     info: &cosmwasm_std::MessageInfo,
 ) -> Result<(), ContractError> {
-    let state = load_config(storage)?;
-    if !state.allowed_publishers.contains(&info.sender) {
+    if !has_role(storage, PUBLISHER_ROLE, &info.sender) {
         Err(ContractError::Unauthorized {})
     } else {
         Ok(())
@@ -321,9 +1065,18 @@ pub fn validate_sender_is_publisher(
 pub fn validate_sender_is_action_executor(
     storage: &dyn Storage,
     info: &cosmwasm_std::MessageInfo,
+    instance: &WorkflowInstance,
 ) -> Result<(), ContractError> {
-    let state = load_config(storage)?;
-    if !state.allowed_action_executors.contains(&info.sender) {
+    // Workflows with dedicated executors don't fall back to the global set,
+    // bonded executors only run the instances that opted in
+    let authorized = match load_workflow_executors(storage, &instance.workflow_id)? {
+        Some(executors) => executors.contains(&info.sender),
+        None => {
+            has_role(storage, EXECUTOR_ROLE, &info.sender)
+                || (instance.allow_bonded_executors && is_active_bonded_executor(storage, &info.sender)?)
+        }
+    };
+    if !authorized {
         Err(ContractError::Unauthorized {})
     } else {
         Ok(())
@@ -334,14 +1087,44 @@ pub fn validate_sender_is_owner(
     storage: &dyn Storage,
     info: &cosmwasm_std::MessageInfo,
 ) -> Result<(), ContractError> {
-    let state = load_config(storage)?;
-    if info.sender != state.owner {
+    if !is_owner(storage, &info.sender)? {
         Err(ContractError::Unauthorized {})
     } else {
         Ok(())
     }
 }
 
+pub fn validate_sender_is_guardian(
+    storage: &dyn Storage,
+    info: &cosmwasm_std::MessageInfo,
+) -> Result<(), ContractError> {
+    // The owner can always pull the circuit breaker itself
+    if !has_role(storage, GUARDIAN_ROLE, &info.sender) && !is_owner(storage, &info.sender)? {
+        Err(ContractError::Unauthorized {})
+    } else {
+        Ok(())
+    }
+}
+
+pub fn validate_sender_can_use_lease(
+    storage: &dyn Storage,
+    env: &cosmwasm_std::Env,
+    info: &cosmwasm_std::MessageInfo,
+    requester: &Addr,
+    instance_id: &InstanceId,
+) -> Result<(), ContractError> {
+    match load_workflow_instance_lease(storage, requester, instance_id)? {
+        Some(lease) if lease.holder != info.sender && env.block.time < lease.expires_at => {
+            Err(ContractError::InstanceLeased {
+                instance_id: instance_id.to_string(),
+                holder: lease.holder.to_string(),
+                expires_at: lease.expires_at.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 pub fn validate_contract_is_whitelisted(
     storage: &dyn Storage,
     workflow_id: &WorkflowId,
@@ -356,3 +1139,36 @@ pub fn validate_contract_is_whitelisted(
     })?;
     Ok(())
 }
+
+pub fn validate_message_is_allowed(
+    storage: &dyn Storage,
+    workflow_id: &WorkflowId,
+    action_id: &ActionId,
+    contract_addr: &str,
+    message: &str,
+) -> Result<(), ContractError> {
+    let Some(allowed_messages) = load_workflow_action_contract(storage, workflow_id, action_id, contract_addr)? else {
+        return Ok(());
+    };
+
+    // Execute messages are externally tagged enums: a single top-level key
+    let parsed: serde_json::Value = serde_json::from_str(message).map_err(|e| ContractError::InvalidTemplate {
+        reason: format!("Rendered message is not valid JSON: {}", e),
+    })?;
+    let message_key = match parsed.as_object() {
+        Some(object) if object.len() == 1 => object.keys().next().cloned().unwrap_or_default(),
+        _ => {
+            return Err(ContractError::InvalidTemplate {
+                reason: "Rendered message must have a single top-level key".to_string(),
+            })
+        }
+    };
+
+    if !allowed_messages.contains(&message_key) {
+        return Err(ContractError::MessageNotAllowed {
+            message: message_key,
+            contract: contract_addr.to_string(),
+        });
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
-use anybuf::Anybuf;
-use cosmwasm_std::{Addr, CosmosMsg, Env, Reply, StdResult, SubMsgResponse, SubMsgResult, Uint128};
+use anybuf::{Anybuf, Bufany};
+use cosmwasm_std::{Addr, Api, CosmosMsg, Env, QuerierWrapper, Reply, StdResult, SubMsgResponse, SubMsgResult, Uint128};
 
 /// Function to extract the value of a specific attribute from events of a specific type in a Reply response.
 ///
@@ -37,6 +37,7 @@ use cosmwasm_std::Coin;
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use crate::msg::ActionParamValue;
 use crate::ContractError;
 
 /// Splits a concatenated string into `amount` and `denom` and creates a `Coin`.
@@ -226,3 +227,143 @@ pub fn build_authz_execute_contract_msg(
 
     Ok(cosmos_msg)
 }
+
+pub const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+const GENERIC_AUTHORIZATION_TYPE_URL: &str = "/cosmos.authz.v1beta1.GenericAuthorization";
+const CONTRACT_EXECUTION_AUTHORIZATION_TYPE_URL: &str = "/cosmwasm.wasm.v1.ContractExecutionAuthorization";
+
+/// Checks that `granter` gave this contract an authz grant to execute `contract_addr` on its behalf.
+///
+/// The grants are read with the `cosmos.authz.v1beta1.Query/Grants` gRPC query. Spend limits and
+/// message filters of contract execution grants are not checked, the authz module still enforces them.
+///
+/// # Returns
+///
+/// * `Some(reason)` if the grant is missing, expired or doesn't cover the contract.
+/// * `None` if the grant is valid, or if the node can't answer the query (authz still enforces the grant when the message is dispatched).
+pub fn find_missing_authz_grant(
+    querier: &QuerierWrapper,
+    env: &Env,
+    granter: &Addr,
+    contract_addr: &str,
+) -> Option<String> {
+    let request = Anybuf::new()
+        .append_string(1, granter) // granter (field 1)
+        .append_string(2, &env.contract.address) // grantee (field 2)
+        .append_string(3, MSG_EXECUTE_CONTRACT_TYPE_URL); // msg_type_url (field 3)
+    let response = querier
+        .query_grpc("/cosmos.authz.v1beta1.Query/Grants".to_string(), request.as_bytes().into())
+        .ok()?;
+    let response = Bufany::deserialize(&response).ok()?;
+    let grants = response.repeated_message(1).ok()?; // grants (field 1)
+    if grants.is_empty() {
+        return Some("No authz grant".to_string());
+    }
+
+    for grant in &grants {
+        // Grants without expiration don't serialize field 2
+        if grant.value(2).is_some() {
+            let expiration = grant.message(2)?;
+            if expiration.int64(1).unwrap_or_default() <= env.block.time.seconds() as i64 {
+                continue;
+            }
+        }
+        let authorization = grant.message(1)?; // authorization (field 1), an Any
+        let authorization_value = authorization.bytes(2)?;
+        match authorization.string(1)?.as_str() {
+            GENERIC_AUTHORIZATION_TYPE_URL => return None,
+            CONTRACT_EXECUTION_AUTHORIZATION_TYPE_URL => {
+                let authorization_value = Bufany::deserialize(&authorization_value).ok()?;
+                // ContractGrant.contract (field 1) of each grant (field 1)
+                let covers_contract = authorization_value
+                    .repeated_message(1)
+                    .ok()?
+                    .iter()
+                    .any(|contract_grant| contract_grant.string(1).as_deref() == Some(contract_addr));
+                if covers_contract {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(format!("No valid authz grant covers contract {}", contract_addr))
+}
+
+const PARAM_REFERENCE_PREFIXES: [&str; 6] = ["#ip.", "#cp.", "#out.", "#bal.", "#q.", "#price."];
+
+/// Whether the value references another value instead of being used as-is
+pub fn is_param_reference(value: &ActionParamValue) -> bool {
+    match value {
+        ActionParamValue::String(s) | ActionParamValue::BigInt(s) => {
+            PARAM_REFERENCE_PREFIXES.iter().any(|prefix| s.starts_with(prefix))
+        }
+        _ => false,
+    }
+}
+
+/// Renders a param value into template text, failing if it isn't valid for its type.
+/// Addresses can't be checked without the api, `validate_param_value` does it when values are received.
+pub fn render_param_value(param_id: &str, value: &ActionParamValue) -> Result<String, ContractError> {
+    let invalid = |reason: String| ContractError::InvalidParamValue {
+        param: param_id.to_string(),
+        reason,
+    };
+    match value {
+        ActionParamValue::String(s) | ActionParamValue::Address(s) => Ok(s.clone()),
+        ActionParamValue::BigInt(s) => {
+            let digits = s.strip_prefix('-').unwrap_or(s);
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid(format!("'{}' is not an integer", s)));
+            }
+            Ok(s.clone())
+        }
+        ActionParamValue::Uint128(amount) => Ok(amount.to_string()),
+        ActionParamValue::Decimal(decimal) => Ok(decimal.to_string()),
+        ActionParamValue::Bool(b) => Ok(b.to_string()),
+        ActionParamValue::Coin(coin) => {
+            validate_denom(&coin.denom).map_err(invalid)?;
+            Ok(cosmwasm_std::to_json_string(coin)?)
+        }
+        ActionParamValue::Denom(denom) => {
+            validate_denom(denom).map_err(invalid)?;
+            Ok(denom.clone())
+        }
+        ActionParamValue::Json(json) => {
+            serde_json::from_str::<serde_json::Value>(json).map_err(|e| invalid(format!("invalid JSON: {}", e)))?;
+            Ok(json.clone())
+        }
+    }
+}
+
+/// Checks a param value received by the contract, including addresses
+pub fn validate_param_value(api: &dyn Api, param_id: &str, value: &ActionParamValue) -> Result<(), ContractError> {
+    render_param_value(param_id, value)?;
+    if let ActionParamValue::Address(address) = value {
+        api.addr_validate(address).map_err(|e| ContractError::InvalidParamValue {
+            param: param_id.to_string(),
+            reason: e.to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+pub fn validate_param_values(api: &dyn Api, params: &HashMap<String, ActionParamValue>) -> Result<(), ContractError> {
+    for (param_id, value) in params {
+        validate_param_value(api, param_id, value)?;
+    }
+    Ok(())
+}
+
+// Same format as the cosmos sdk bank module
+fn validate_denom(denom: &str) -> Result<(), String> {
+    let mut chars = denom.chars();
+    let valid = (3..=128).contains(&denom.len())
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a valid denom", denom))
+    }
+}
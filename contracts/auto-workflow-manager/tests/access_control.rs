@@ -0,0 +1,115 @@
+use auto_access_control::{msg::OwnershipResponse, AccessControlError};
+use auto_workflow_manager::{
+    contract::{execute, migrate, query},
+    error::ContractError,
+    msg::{ExecuteMsg, QueryMsg},
+    state::{LegacyConfig, LEGACY_CONFIG},
+};
+use cosmwasm_std::{from_json, testing::message_info, Addr, Env};
+use std::collections::HashSet;
+
+mod utils;
+use utils::{create_simple_test_workflow, create_test_environment, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn execute_as(deps: &mut TestDeps, env: Env, sender: &Addr, execute_msg: ExecuteMsg) -> Result<cosmwasm_std::Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(sender, &[]), execute_msg)
+}
+
+fn has_role(deps: &TestDeps, env: Env, role: &str, address: &Addr) -> bool {
+    from_json(query(deps.as_ref(), env, QueryMsg::HasRole { role: role.to_string(), address: address.to_string() }).unwrap()).unwrap()
+}
+
+#[test]
+fn test_owner_grants_and_revokes_publishers() {
+    let (mut deps, env, api, admin_address, _publisher_address, _executor_address) = create_test_environment();
+    let new_publisher_address = api.addr_make("new_publisher");
+    let grant_msg = ExecuteMsg::GrantRole {
+        role: "publisher".to_string(),
+        address: new_publisher_address.to_string(),
+    };
+
+    let result = execute_as(&mut deps, env.clone(), &new_publisher_address, grant_msg.clone());
+    assert!(matches!(result, Err(ContractError::AccessControl(AccessControlError::Unauthorized {}))));
+
+    let response = execute_as(&mut deps, env.clone(), &admin_address, grant_msg.clone()).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-workflow-manager/grant_role");
+    assert!(has_role(&deps, env.clone(), "publisher", &new_publisher_address));
+    publish_workflow(deps.as_mut(), env.clone(), new_publisher_address.clone(), create_simple_test_workflow(api)).unwrap();
+
+    let result = execute_as(&mut deps, env.clone(), &admin_address, grant_msg);
+    assert!(matches!(result, Err(ContractError::AccessControl(AccessControlError::RoleAlreadyGranted { .. }))));
+
+    let result = execute_as(&mut deps, env.clone(), &admin_address, ExecuteMsg::GrantRole {
+        role: "admin".to_string(),
+        address: new_publisher_address.to_string(),
+    });
+    assert!(matches!(result, Err(ContractError::AccessControl(AccessControlError::UnknownRole { .. }))));
+
+    execute_as(&mut deps, env.clone(), &admin_address, ExecuteMsg::RevokeRole {
+        role: "publisher".to_string(),
+        address: new_publisher_address.to_string(),
+    }).unwrap();
+    assert!(!has_role(&deps, env, "publisher", &new_publisher_address));
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    let (mut deps, env, api, admin_address, _publisher_address, _executor_address) = create_test_environment();
+    let new_owner_address = api.addr_make("new_owner");
+
+    execute_as(&mut deps, env.clone(), &admin_address, ExecuteMsg::TransferOwnership {
+        new_owner: new_owner_address.to_string(),
+    }).unwrap();
+
+    // The previous owner keeps control until the transfer is accepted
+    let ownership: OwnershipResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Ownership {}).unwrap()).unwrap();
+    assert_eq!(ownership.owner, admin_address);
+    assert_eq!(ownership.pending_owner, Some(new_owner_address.clone()));
+
+    let result = execute_as(&mut deps, env.clone(), &api.addr_make("someone"), ExecuteMsg::AcceptOwnership {});
+    assert!(matches!(result, Err(ContractError::AccessControl(AccessControlError::Unauthorized {}))));
+
+    execute_as(&mut deps, env.clone(), &new_owner_address, ExecuteMsg::AcceptOwnership {}).unwrap();
+
+    let ownership: OwnershipResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Ownership {}).unwrap()).unwrap();
+    assert_eq!(ownership.owner, new_owner_address);
+    assert_eq!(ownership.pending_owner, None);
+
+    let result = execute_as(&mut deps, env, &admin_address, ExecuteMsg::GrantRole {
+        role: "executor".to_string(),
+        address: admin_address.to_string(),
+    });
+    assert!(matches!(result, Err(ContractError::AccessControl(AccessControlError::Unauthorized {}))));
+}
+
+#[test]
+fn test_migrate_moves_legacy_config_to_roles() {
+    let mut deps = cosmwasm_std::testing::mock_dependencies();
+    let env = cosmwasm_std::testing::mock_env();
+    let api = deps.api;
+    let owner_address = api.addr_make("owner");
+    let publisher_address = api.addr_make("publisher");
+    let executor_address = api.addr_make("executor");
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:workflow-manager", "0.2.0").unwrap();
+    LEGACY_CONFIG.save(deps.as_mut().storage, &LegacyConfig {
+        owner: owner_address.clone(),
+        allowed_publishers: HashSet::from([publisher_address.clone()]),
+        allowed_action_executors: HashSet::from([executor_address.clone()]),
+        referral_memo: "memo".to_string(),
+        fee_manager_address: api.addr_make("fee_manager"),
+        max_consecutive_failures: None,
+        executor_bonding: None,
+    }).unwrap();
+
+    migrate(deps.as_mut(), env.clone(), ()).unwrap();
+
+    let ownership: OwnershipResponse = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Ownership {}).unwrap()).unwrap();
+    assert_eq!(ownership.owner, owner_address);
+    assert!(has_role(&deps, env.clone(), "publisher", &publisher_address));
+    assert!(has_role(&deps, env.clone(), "executor", &executor_address));
+    assert!(!has_role(&deps, env.clone(), "executor", &publisher_address));
+    query(deps.as_ref(), env, QueryMsg::GetConfig {}).unwrap();
+}
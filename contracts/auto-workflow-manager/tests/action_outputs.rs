@@ -0,0 +1,224 @@
+use anybuf::Bufany;
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::ACTION_REPLY_ID_START,
+    msg::{ActionMsg, ActionOutput, ActionParamValue, ExecuteMsg, ExecutionType, NewInstanceMsg, NewWorkflowMsg, Template, WorkflowVisibility},
+};
+use cosmwasm_std::{Addr, Binary, CosmosMsg, Event, Reply, SubMsgResponse, SubMsgResult, Timestamp};
+use std::collections::{HashMap, HashSet};
+
+mod utils;
+use utils::{create_test_environment, execute_instance, publish_workflow};
+
+fn create_claim_and_stake_workflow(claim_contract: &Addr, stake_contract: &Addr, filter: Option<(String, String)>) -> NewWorkflowMsg {
+    NewWorkflowMsg {
+        id: "claim-and-stake".to_string(),
+        start_actions: HashSet::from(["claim".to_string()]),
+        end_actions: HashSet::from(["stake".to_string()]),
+        visibility: WorkflowVisibility::Public,
+        actions: HashMap::from([
+            (
+                "claim".to_string(),
+                ActionMsg {
+                    params: HashMap::new(),
+                    next_actions: HashSet::from(["stake".to_string()]),
+                    templates: HashMap::from([(
+                        "default".to_string(),
+                        Template {
+                            contract: claim_contract.to_string(),
+                            message: "{\"claim\":{}}".to_string(),
+                            funds: vec![],
+                        },
+                    )]),
+                    whitelisted_contracts: HashSet::from([claim_contract.to_string()]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::from([(
+                        "amount".to_string(),
+                        ActionOutput {
+                            event_type: "wasm".to_string(),
+                            attribute_key: "return_amount".to_string(),
+                            filter,
+                        },
+                    )]),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
+                },
+            ),
+            (
+                "stake".to_string(),
+                ActionMsg {
+                    params: HashMap::from([(
+                        "amount".to_string(),
+                        ActionParamValue::String("#out.claim.amount".to_string()),
+                    )]),
+                    next_actions: HashSet::new(),
+                    templates: HashMap::from([(
+                        "default".to_string(),
+                        Template {
+                            contract: stake_contract.to_string(),
+                            message: "{\"stake\":{\"amount\":\"{{amount}}\"}}".to_string(),
+                            funds: vec![("#out.claim.amount".to_string(), "rune".to_string())],
+                        },
+                    )]),
+                    whitelisted_contracts: HashSet::from([stake_contract.to_string()]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
+                },
+            ),
+        ]),
+        creator_fee_schedule: None,
+    }
+}
+
+fn create_instance(execution_type: ExecutionType) -> NewInstanceMsg {
+    NewInstanceMsg {
+        workflow_id: "claim-and-stake".to_string(),
+        onchain_parameters: HashMap::new(),
+        offchain_parameters: HashMap::new(),
+        execution_type,
+        expiration_time: Timestamp::from_seconds(1000000000),
+        cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
+    }
+}
+
+fn execute_action(
+    deps: &mut cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>,
+    env: cosmwasm_std::Env,
+    executor: &Addr,
+    user_address: &Addr,
+    action_id: &str,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: action_id.to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    let execute_info = cosmwasm_std::testing::message_info(executor, &[]);
+    execute(deps.as_mut(), env, execute_info, execute_msg)
+}
+
+#[allow(deprecated)]
+fn reply_with_events(events: Vec<Event>) -> Reply {
+    Reply {
+        id: ACTION_REPLY_ID_START,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events,
+            msg_responses: vec![],
+            data: None,
+        }),
+        gas_used: 0,
+        payload: Binary::default(),
+    }
+}
+
+/// Extracts the contract message and funds wrapped in the authz MsgExec
+fn decode_authz_execute(msg: &CosmosMsg) -> (String, Vec<(String, String)>) {
+    let CosmosMsg::Any(any_msg) = msg else {
+        panic!("Expected an Any message");
+    };
+    let msg_exec = Bufany::deserialize(any_msg.value.as_slice()).unwrap();
+    let inner_any = msg_exec.message(2).unwrap();
+    let execute_contract = inner_any.message(2).unwrap();
+    let funds = execute_contract
+        .repeated_message(5)
+        .unwrap()
+        .iter()
+        .map(|fund| (fund.string(2).unwrap(), fund.string(1).unwrap()))
+        .collect();
+    (execute_contract.string(3).unwrap(), funds)
+}
+
+#[test]
+fn test_action_output_is_used_by_next_action() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let claim_contract = api.addr_make("claim_contract");
+    let stake_contract = api.addr_make("stake_contract");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_claim_and_stake_workflow(&claim_contract, &stake_contract, None)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(ExecutionType::OneShot)).unwrap();
+
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "claim").unwrap();
+    let response = reply(deps.as_mut(), env.clone(), reply_with_events(vec![
+        Event::new("wasm").add_attribute("return_amount", "12345"),
+    ])).unwrap();
+    assert!(response.events[0].attributes.iter().any(|attr| attr.key == "outputs" && attr.value == "amount"));
+
+    let response = execute_action(&mut deps, env, &executor_address, &user_address, "stake").unwrap();
+    let (message, funds) = decode_authz_execute(&response.messages[0].msg);
+    assert_eq!(message, "{\"stake\":{\"amount\":\"12345\"}}");
+    assert_eq!(funds, vec![("12345".to_string(), "rune".to_string())]);
+}
+
+#[test]
+fn test_action_output_filter_mismatch_is_not_captured() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let claim_contract = api.addr_make("claim_contract");
+    let stake_contract = api.addr_make("stake_contract");
+    env.block.time = Timestamp::from_seconds(100);
+
+    let filter = Some(("_contract_address".to_string(), claim_contract.to_string()));
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_claim_and_stake_workflow(&claim_contract, &stake_contract, filter)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(ExecutionType::OneShot)).unwrap();
+
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "claim").unwrap();
+    reply(deps.as_mut(), env.clone(), reply_with_events(vec![
+        Event::new("wasm")
+            .add_attribute("_contract_address", api.addr_make("other_contract").to_string())
+            .add_attribute("return_amount", "12345"),
+    ])).unwrap();
+
+    let result = execute_action(&mut deps, env, &executor_address, &user_address, "stake");
+    match result {
+        Err(ContractError::GenericError(message)) => {
+            assert_eq!(message, "Output 'claim.amount' not found in current run");
+        }
+        _ => panic!("Expected GenericError for missing output"),
+    }
+}
+
+#[test]
+fn test_action_outputs_are_cleared_on_new_run() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let claim_contract = api.addr_make("claim_contract");
+    let stake_contract = api.addr_make("stake_contract");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_claim_and_stake_workflow(&claim_contract, &stake_contract, None)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(ExecutionType::Recurrent)).unwrap();
+
+    // First run captures the claimed amount and stakes it
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "claim").unwrap();
+    reply(deps.as_mut(), env.clone(), reply_with_events(vec![
+        Event::new("wasm").add_attribute("return_amount", "12345"),
+    ])).unwrap();
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "stake").unwrap();
+    reply(deps.as_mut(), env.clone(), reply_with_events(vec![])).unwrap();
+
+    // Second run claims nothing, so the previous amount must not leak into it
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "claim").unwrap();
+    reply(deps.as_mut(), env.clone(), reply_with_events(vec![])).unwrap();
+
+    let result = execute_action(&mut deps, env, &executor_address, &user_address, "stake");
+    assert!(result.is_err());
+}
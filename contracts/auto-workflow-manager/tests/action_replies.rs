@@ -0,0 +1,146 @@
+use auto_workflow_manager::{
+    contract::{execute, reply, sudo},
+    execute::ACTION_REPLY_ID_START,
+    msg::{ExecuteMsg, RunOutcome, SudoMsg, WorkflowInstanceState},
+    query::query_workflow_instance,
+};
+use cosmwasm_std::{Addr, Binary, Reply, ReplyOn, SubMsgResponse, SubMsgResult, Timestamp};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+fn execute_stake_action(
+    deps: &mut cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>,
+    env: cosmwasm_std::Env,
+    executor: Addr,
+    user_address: String,
+) -> Result<cosmwasm_std::Response, auto_workflow_manager::error::ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address,
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    let execute_info = cosmwasm_std::testing::message_info(&executor, &[]);
+    execute(deps.as_mut(), env, execute_info, execute_msg)
+}
+
+#[allow(deprecated)]
+fn action_reply(result: SubMsgResult) -> Reply {
+    Reply {
+        id: ACTION_REPLY_ID_START,
+        result,
+        gas_used: 0,
+        payload: Binary::default(),
+    }
+}
+
+fn ok_result() -> SubMsgResult {
+    #[allow(deprecated)]
+    SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        msg_responses: vec![],
+        data: None,
+    })
+}
+
+#[test]
+fn test_execute_action_dispatches_reply_always_submessage() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    let response = execute_stake_action(&mut deps, env, executor_address, user_address.to_string()).unwrap();
+
+    assert_eq!(response.messages.len(), 1);
+    assert_eq!(response.messages[0].id, ACTION_REPLY_ID_START);
+    assert_eq!(response.messages[0].reply_on, ReplyOn::Always);
+}
+
+#[test]
+fn test_action_reply_success_records_outcome() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    execute_stake_action(&mut deps, env.clone(), executor_address, user_address.to_string()).unwrap();
+
+    let response = reply(deps.as_mut(), env, action_reply(ok_result())).unwrap();
+    assert_eq!(response.events.len(), 1);
+    assert_eq!(response.events[0].ty, "autorujira-workflow-manager/action-succeeded");
+
+    let instance = query_workflow_instance(deps.as_ref(), user_address.to_string(), 1).unwrap().instance;
+    assert_eq!(instance.last_executed_action, Some("stake_tokens".to_string()));
+    assert_eq!(instance.last_run_outcome, Some(RunOutcome::Succeeded));
+    assert_eq!(instance.consecutive_failures, 0);
+}
+
+#[test]
+fn test_action_reply_failure_restores_previous_action() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    execute_stake_action(&mut deps, env.clone(), executor_address.clone(), user_address.to_string()).unwrap();
+
+    let response = reply(deps.as_mut(), env.clone(), action_reply(SubMsgResult::Err("out of funds".to_string()))).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-workflow-manager/action-failed");
+    assert!(response.events[0].attributes.iter().any(|attr| attr.key == "error" && attr.value == "out of funds"));
+    assert!(response.events[0].attributes.iter().any(|attr| attr.key == "paused" && attr.value == "false"));
+
+    let instance = query_workflow_instance(deps.as_ref(), user_address.to_string(), 1).unwrap().instance;
+    assert_eq!(instance.last_executed_action, None);
+    assert_eq!(instance.state, WorkflowInstanceState::Running);
+    assert_eq!(instance.consecutive_failures, 1);
+    assert_eq!(instance.last_run_outcome, Some(RunOutcome::Failed {
+        action_id: "stake_tokens".to_string(),
+        error: "out of funds".to_string(),
+    }));
+
+    // The start action can be retried
+    execute_stake_action(&mut deps, env, executor_address, user_address.to_string()).unwrap();
+}
+
+#[test]
+fn test_action_reply_failures_pause_instance() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    sudo(deps.as_mut(), env.clone(), SudoMsg::SetMaxConsecutiveFailures(Some(2))).unwrap();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    for _ in 0..2 {
+        execute_stake_action(&mut deps, env.clone(), executor_address.clone(), user_address.to_string()).unwrap();
+        reply(deps.as_mut(), env.clone(), action_reply(SubMsgResult::Err("failed".to_string()))).unwrap();
+    }
+
+    let instance = query_workflow_instance(deps.as_ref(), user_address.to_string(), 1).unwrap().instance;
+    assert_eq!(instance.state, WorkflowInstanceState::Paused);
+    assert_eq!(instance.consecutive_failures, 2);
+
+    // Paused instances can't execute actions
+    let result = execute_stake_action(&mut deps, env.clone(), executor_address.clone(), user_address.to_string());
+    assert!(result.is_err());
+
+    // The user can resume the instance, even if it is one shot
+    let resume_info = cosmwasm_std::testing::message_info(&user_address, &[]);
+    execute(deps.as_mut(), env.clone(), resume_info, ExecuteMsg::ResumeSchedule { instance_id: 1 }).unwrap();
+
+    let instance = query_workflow_instance(deps.as_ref(), user_address.to_string(), 1).unwrap().instance;
+    assert_eq!(instance.state, WorkflowInstanceState::Running);
+    assert_eq!(instance.consecutive_failures, 0);
+
+    execute_stake_action(&mut deps, env, executor_address, user_address.to_string()).unwrap();
+}
@@ -0,0 +1,164 @@
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::ACTION_REPLY_ID_START,
+    msg::{ActionTiming, ExecuteMsg, ExecutionRateLimit, ExecutionType, NewWorkflowMsg},
+};
+use cosmwasm_std::{testing::message_info, Addr, Binary, Env, Reply, Response, SubMsgResponse, SubMsgResult, Timestamp};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, create_test_workflow, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup(workflow: NewWorkflowMsg, execution_type: ExecutionType, instances: u64) -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    let workflow_id = workflow.id.clone();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, workflow).unwrap();
+    for _ in 0..instances {
+        let mut instance = create_oneshot_test_instance(workflow_id.clone());
+        instance.execution_type = execution_type.clone();
+        execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+    }
+    (deps, env, executor_address, user_address)
+}
+
+fn execute_action_at(deps: &mut TestDeps, env: &Env, seconds: u64, executor: &Addr, user_address: &Addr, instance_id: u64, action_id: &str) -> Result<Response, ContractError> {
+    let mut env = env.clone();
+    env.block.time = Timestamp::from_seconds(seconds);
+    execute(deps.as_mut(), env, message_info(executor, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id,
+        action_id: action_id.to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    })
+}
+
+#[allow(deprecated)]
+fn reply_to_action(deps: &mut TestDeps, env: &Env, result: SubMsgResult) {
+    reply(deps.as_mut(), env.clone(), Reply { id: ACTION_REPLY_ID_START, result, gas_used: 0, payload: Binary::default() }).unwrap();
+}
+
+#[allow(deprecated)]
+fn ok_result() -> SubMsgResult {
+    SubMsgResult::Ok(SubMsgResponse { events: vec![], msg_responses: vec![], data: None })
+}
+
+fn simple_workflow_with_timing(timing: ActionTiming) -> NewWorkflowMsg {
+    let mut workflow = create_simple_test_workflow(cosmwasm_std::testing::MockApi::default());
+    workflow.actions.get_mut("stake_tokens").unwrap().timing = Some(timing);
+    workflow
+}
+
+#[test]
+fn test_action_runs_within_its_window() {
+    // Claim between 1 and 10 minutes after staking
+    let mut workflow = create_test_workflow(cosmwasm_std::testing::MockApi::default());
+    workflow.actions.get_mut("claim_rewards").unwrap().timing = Some(ActionTiming {
+        not_before: Some(60),
+        not_after: Some(600),
+        ..Default::default()
+    });
+    let (mut deps, env, executor_address, user_address) = setup(workflow, ExecutionType::OneShot, 2);
+
+    execute_action_at(&mut deps, &env, 100, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    let result = execute_action_at(&mut deps, &env, 130, &executor_address, &user_address, 1, "claim_rewards");
+    assert!(matches!(result, Err(ContractError::ActionTimingViolation { .. })));
+    execute_action_at(&mut deps, &env, 160, &executor_address, &user_address, 1, "claim_rewards").unwrap();
+
+    execute_action_at(&mut deps, &env, 100, &executor_address, &user_address, 2, "stake_tokens").unwrap();
+    let result = execute_action_at(&mut deps, &env, 701, &executor_address, &user_address, 2, "claim_rewards");
+    assert!(matches!(result, Err(ContractError::ActionTimingViolation { .. })));
+}
+
+#[test]
+fn test_cooldown_between_runs() {
+    let workflow = simple_workflow_with_timing(ActionTiming {
+        cooldown: Some(3600),
+        ..Default::default()
+    });
+    let (mut deps, env, executor_address, user_address) = setup(workflow, ExecutionType::Recurrent, 1);
+
+    execute_action_at(&mut deps, &env, 100, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    let result = execute_action_at(&mut deps, &env, 200, &executor_address, &user_address, 1, "stake_tokens");
+    assert!(matches!(result, Err(ContractError::ActionTimingViolation { .. })));
+    execute_action_at(&mut deps, &env, 3700, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+}
+
+#[test]
+fn test_failed_start_action_keeps_the_previous_run() {
+    let workflow = simple_workflow_with_timing(ActionTiming {
+        cooldown: Some(3600),
+        ..Default::default()
+    });
+    let (mut deps, env, executor_address, user_address) = setup(workflow, ExecutionType::Recurrent, 1);
+
+    execute_action_at(&mut deps, &env, 100, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    reply_to_action(&mut deps, &env, ok_result());
+
+    // The run that failed didn't start, so it doesn't restart the cooldown
+    execute_action_at(&mut deps, &env, 5000, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    reply_to_action(&mut deps, &env, SubMsgResult::Err("failed".to_string()));
+    execute_action_at(&mut deps, &env, 5000, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+}
+
+#[test]
+fn test_executions_are_rate_limited() {
+    let workflow = simple_workflow_with_timing(ActionTiming {
+        rate_limit: Some(ExecutionRateLimit { max_executions: 2, window: 1000 }),
+        ..Default::default()
+    });
+    let (mut deps, env, executor_address, user_address) = setup(workflow, ExecutionType::Recurrent, 1);
+
+    execute_action_at(&mut deps, &env, 100, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    execute_action_at(&mut deps, &env, 200, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    let result = execute_action_at(&mut deps, &env, 300, &executor_address, &user_address, 1, "stake_tokens");
+    assert!(matches!(result, Err(ContractError::ActionTimingViolation { .. })));
+
+    // The first execution left the window
+    execute_action_at(&mut deps, &env, 1100, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+}
+
+#[test]
+fn test_failed_execution_frees_its_rate_limit_slot() {
+    let workflow = simple_workflow_with_timing(ActionTiming {
+        rate_limit: Some(ExecutionRateLimit { max_executions: 1, window: 1000 }),
+        ..Default::default()
+    });
+    let (mut deps, env, executor_address, user_address) = setup(workflow, ExecutionType::Recurrent, 1);
+
+    execute_action_at(&mut deps, &env, 100, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    reply_to_action(&mut deps, &env, SubMsgResult::Err("failed".to_string()));
+
+    execute_action_at(&mut deps, &env, 200, &executor_address, &user_address, 1, "stake_tokens").unwrap();
+    reply_to_action(&mut deps, &env, ok_result());
+    let result = execute_action_at(&mut deps, &env, 300, &executor_address, &user_address, 1, "stake_tokens");
+    assert!(matches!(result, Err(ContractError::ActionTimingViolation { .. })));
+}
+
+#[test]
+fn test_publish_validates_timings() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+
+    let mut workflow = create_test_workflow(api);
+    workflow.actions.get_mut("claim_rewards").unwrap().timing = Some(ActionTiming {
+        cooldown: Some(60),
+        ..Default::default()
+    });
+    let result = publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), workflow);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    let workflow = simple_workflow_with_timing(ActionTiming {
+        not_before: Some(600),
+        not_after: Some(60),
+        ..Default::default()
+    });
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, workflow);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
@@ -21,6 +21,10 @@ fn create_test_instance(workflow_id: String) -> NewInstanceMsg {
         execution_type: ExecutionType::OneShot,
         expiration_time: Timestamp::from_seconds(1000000000), // Far future
         cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     }
 }
 
@@ -32,6 +36,10 @@ fn create_test_instance_with_params(workflow_id: String, params: HashMap<String,
         execution_type: ExecutionType::OneShot,
         expiration_time: Timestamp::from_seconds(10000000000), // Far future
         cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     }
 }
 
@@ -43,6 +51,10 @@ fn create_test_instance_with_expiration(workflow_id: String, expiration_time: Ti
         execution_type: ExecutionType::OneShot,
         expiration_time,
         cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     }
 }
 
@@ -73,6 +85,8 @@ fn execute_action(
         action_id,
         template_id,
         params,
+        params_signature: None,
+        execution_id: None,
     };
     let execute_info = cosmwasm_std::testing::message_info(&executor, &[]);
     execute(deps.as_mut(), env, execute_info, execute_msg)
@@ -336,6 +350,13 @@ fn test_execute_action_invalid_action_sequence() {
             whitelisted_contracts: HashSet::from([
                 "osmo1contract123456789".to_string(),
             ]),
+            allowed_messages: HashMap::new(),
+            outputs: HashMap::new(),
+            signed_params: HashSet::new(),
+            param_constraints: HashMap::new(),
+            sub_workflow: None,
+            min_return_protection: None,
+            timing: None,
         },
     );
     // Update the first action to have next_actions
@@ -525,9 +546,17 @@ fn test_execute_action_with_dynamic_template() {
                     whitelisted_contracts: HashSet::from([
                         contract_to_call.to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
         ]),
+        creator_fee_schedule: None,
     };
 
     // Publish the workflow
@@ -613,9 +642,17 @@ fn test_execute_action_template_not_found() {
                     whitelisted_contracts: HashSet::from([
                         token_address.to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
         ]),
+        creator_fee_schedule: None,
     };
 
     // Publish the workflow
@@ -706,6 +743,13 @@ fn test_execute_action_with_dynamic_template_echo() {
                     whitelisted_contracts: HashSet::from([
                         contract_to_call.to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
             (
@@ -741,9 +785,17 @@ fn test_execute_action_with_dynamic_template_echo() {
                     whitelisted_contracts: HashSet::from([
                         contract_to_call.to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
         ]),
+        creator_fee_schedule: None,
     };
 
     // Publish the workflow
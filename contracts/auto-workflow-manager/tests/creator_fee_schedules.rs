@@ -0,0 +1,186 @@
+use auto_fee_manager::msg::{ExecuteMsg as FeeManagerExecuteMsg, FeeType as FeeManagerFeeType};
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::{ACTION_REPLY_ID_START, FEE_REPLY_ID_START},
+    msg::{CreatorFee, CreatorFeeSchedule, ExecuteMsg, FeeAmount, FeeTotal, FeeType, NewWorkflowMsg, UserFee},
+    state::PaymentConfig,
+};
+use cosmwasm_std::{coin, from_json, Addr, Binary, CosmosMsg, Decimal, Env, Reply, Response, SubMsgResult, Timestamp, Uint128, WasmMsg};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+// Simple workflow whose stake action sends 1000 uusdc
+fn create_scheduled_workflow(api: cosmwasm_std::testing::MockApi, fees: Vec<CreatorFee>) -> NewWorkflowMsg {
+    let mut workflow = create_simple_test_workflow(api);
+    let stake_action = workflow.actions.get_mut("stake_tokens").unwrap();
+    stake_action.templates.get_mut("default").unwrap().funds = vec![("1000".to_string(), "uusdc".to_string())];
+    workflow.creator_fee_schedule = Some(CreatorFeeSchedule { fees });
+    workflow
+}
+
+fn setup(fees: Vec<CreatorFee>) -> (TestDeps, Env, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_scheduled_workflow(api, fees)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        cosmwasm_std::testing::message_info(&user_address, &[]),
+        ExecuteMsg::SetUserPaymentConfig { payment_config: PaymentConfig::Prepaid },
+    ).unwrap();
+
+    (deps, env, admin_address, executor_address, user_address)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(executor, &[]), execute_msg).unwrap();
+}
+
+fn charge_creator_fee(deps: &mut TestDeps, env: Env, admin_address: &Addr, user_address: &Addr, batch_id: &str, amount: u128) -> Response {
+    let charge_fees_msg = ExecuteMsg::ChargeFees {
+        batch_id: batch_id.to_string(),
+        prices: HashMap::from([("uusdc".to_string(), ("".to_string(), Decimal::one()))]),
+        fees: vec![UserFee {
+            address: user_address.to_string(),
+            totals: vec![FeeTotal {
+                denom: "uusdc".to_string(),
+                debit_denom: "uusdc".to_string(),
+                amount: Uint128::from(amount),
+                fee_type: FeeType::Creator { instance_id: 1 },
+                executor: None,
+            }],
+        }],
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(admin_address, &[]), charge_fees_msg).unwrap()
+}
+
+fn charged_creator_fee(response: &Response) -> Option<Uint128> {
+    let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &response.messages.first()?.msg else {
+        panic!("Expected WasmMsg::Execute");
+    };
+    let FeeManagerExecuteMsg::ChargeFeesFromUserBalance { batch } = from_json(msg).unwrap() else {
+        panic!("Expected ChargeFeesFromUserBalance");
+    };
+    let fee = &batch[0].fees[0];
+    assert!(matches!(fee.fee_type, FeeManagerFeeType::Creator { .. }));
+    Some(fee.amount)
+}
+
+fn is_rejected(response: &Response) -> bool {
+    response.events.iter().any(|event| event.ty == "autorujira-workflow-manager/creator-fee-error")
+}
+
+#[test]
+fn test_per_run_and_funds_share_fees_are_bounded() {
+    let (mut deps, env, admin_address, executor_address, user_address) = setup(vec![
+        CreatorFee::PerRun { amount: FeeAmount::Usd(Uint128::from(100u128)) },
+        CreatorFee::FundsShare { action_id: "stake_tokens".to_string(), share: Decimal::from_str("0.1").unwrap() },
+    ]);
+
+    // Nothing ran yet
+    let response = charge_creator_fee(&mut deps, env.clone(), &admin_address, &user_address, "batch-1", 1);
+    assert!(is_rejected(&response));
+    assert_eq!(charged_creator_fee(&response), None);
+
+    // One run (100) plus 10% of the 1000 uusdc sent by the stake action
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    let response = charge_creator_fee(&mut deps, env.clone(), &admin_address, &user_address, "batch-2", 150);
+    assert_eq!(charged_creator_fee(&response), Some(Uint128::from(150u128)));
+
+    let response = charge_creator_fee(&mut deps, env.clone(), &admin_address, &user_address, "batch-3", 51);
+    assert!(is_rejected(&response));
+
+    let response = charge_creator_fee(&mut deps, env, &admin_address, &user_address, "batch-4", 50);
+    assert_eq!(charged_creator_fee(&response), Some(Uint128::from(50u128)));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_failed_action_is_not_charged() {
+    let (mut deps, env, admin_address, executor_address, user_address) = setup(vec![
+        CreatorFee::PerRun { amount: FeeAmount::Usd(Uint128::from(100u128)) },
+        CreatorFee::FundsShare { action_id: "stake_tokens".to_string(), share: Decimal::from_str("0.1").unwrap() },
+    ]);
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    reply(deps.as_mut(), env.clone(), Reply {
+        id: ACTION_REPLY_ID_START,
+        result: SubMsgResult::Err("failed".to_string()),
+        gas_used: 0,
+        payload: Binary::default(),
+    }).unwrap();
+
+    let response = charge_creator_fee(&mut deps, env, &admin_address, &user_address, "batch-1", 1);
+    assert!(is_rejected(&response));
+}
+
+#[test]
+fn test_subscription_fee_accrues_per_period() {
+    let (mut deps, mut env, admin_address, _executor_address, user_address) = setup(vec![
+        CreatorFee::Subscription { amount: FeeAmount::Coin(coin(20, "uusdc")), period: 1000 },
+    ]);
+
+    // The first period is due when the instance is created
+    let response = charge_creator_fee(&mut deps, env.clone(), &admin_address, &user_address, "batch-1", 20);
+    assert_eq!(charged_creator_fee(&response), Some(Uint128::from(20u128)));
+    let response = charge_creator_fee(&mut deps, env.clone(), &admin_address, &user_address, "batch-2", 20);
+    assert!(is_rejected(&response));
+
+    env.block.time = Timestamp::from_seconds(1100);
+    let response = charge_creator_fee(&mut deps, env, &admin_address, &user_address, "batch-3", 20);
+    assert_eq!(charged_creator_fee(&response), Some(Uint128::from(20u128)));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_rejected_creator_fee_can_be_charged_again() {
+    let (mut deps, env, admin_address, _executor_address, user_address) = setup(vec![
+        CreatorFee::Subscription { amount: FeeAmount::Usd(Uint128::from(20u128)), period: 1000 },
+    ]);
+
+    charge_creator_fee(&mut deps, env.clone(), &admin_address, &user_address, "batch-1", 20);
+    reply(deps.as_mut(), env.clone(), Reply {
+        id: FEE_REPLY_ID_START,
+        result: SubMsgResult::Err("insufficient balance".to_string()),
+        gas_used: 0,
+        payload: Binary::default(),
+    }).unwrap();
+
+    let response = charge_creator_fee(&mut deps, env, &admin_address, &user_address, "batch-2", 20);
+    assert_eq!(charged_creator_fee(&response), Some(Uint128::from(20u128)));
+}
+
+#[test]
+fn test_publish_workflow_with_invalid_schedule_fails() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+
+    let workflow = create_scheduled_workflow(api, vec![
+        CreatorFee::FundsShare { action_id: "unknown".to_string(), share: Decimal::from_str("0.1").unwrap() },
+    ]);
+    let result = publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), workflow);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    let workflow = create_scheduled_workflow(api, vec![
+        CreatorFee::Subscription { amount: FeeAmount::Usd(Uint128::from(10u128)), period: 0 },
+    ]);
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, workflow);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
@@ -0,0 +1,129 @@
+use auto_workflow_manager::{
+    contract::execute,
+    msg::{ActionBlockReason, ActionTiming, ExecuteMsg, ExecutionType, InstanceBlockReason, PriceCondition, PriceSource, PriceTrigger},
+    query::query_executable_actions,
+};
+use cosmwasm_std::{testing::message_info, Addr, Decimal, Env, Timestamp};
+use std::str::FromStr;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow, with_oracle_prices};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup(execution_type: ExecutionType) -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = execution_type;
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    (deps, env, executor_address, user_address)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) {
+    execute(deps.as_mut(), env, message_info(executor, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    }).unwrap();
+}
+
+#[test]
+fn test_executable_actions_follow_the_workflow() {
+    let (mut deps, env, executor_address, user_address) = setup(ExecutionType::OneShot);
+
+    let response = query_executable_actions(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap();
+    assert_eq!(response.blocked, None);
+    assert_eq!(response.actions.len(), 1);
+    assert_eq!(response.actions[0].action_id, "stake_tokens");
+    assert!(response.actions[0].templates.contains_key("default"));
+
+    // The end action of a one-shot instance has nothing after it
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    let response = query_executable_actions(deps.as_ref(), env, user_address.to_string(), 1).unwrap();
+    assert!(response.actions.is_empty());
+}
+
+#[test]
+fn test_recurrent_instance_wraps_around() {
+    let (mut deps, env, executor_address, user_address) = setup(ExecutionType::Recurrent);
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    let response = query_executable_actions(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap();
+    assert_eq!(response.actions.len(), 1);
+    assert_eq!(response.actions[0].action_id, "stake_tokens");
+
+    execute(deps.as_mut(), env.clone(), message_info(&user_address, &[]), ExecuteMsg::PauseSchedule { instance_id: 1 }).unwrap();
+    let response = query_executable_actions(deps.as_ref(), env, user_address.to_string(), 1).unwrap();
+    assert!(response.actions.is_empty());
+    assert_eq!(response.blocked, Some(InstanceBlockReason::SchedulePaused));
+}
+
+#[test]
+fn test_expired_instance_is_blocked() {
+    let (deps, mut env, _executor_address, user_address) = setup(ExecutionType::OneShot);
+
+    env.block.time = Timestamp::from_seconds(1000000000);
+    let response = query_executable_actions(deps.as_ref(), env, user_address.to_string(), 1).unwrap();
+    assert!(response.actions.is_empty());
+    assert_eq!(response.blocked, Some(InstanceBlockReason::Expired { expiration_time: Timestamp::from_seconds(1000000000) }));
+}
+
+#[test]
+fn test_start_action_waiting_for_price_trigger_is_blocked() {
+    let (deps, mut env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+    let mut deps = with_oracle_prices(deps, &[("RUNE", "5")]);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.price_trigger = Some(PriceTrigger {
+        source: PriceSource::Oracle { symbol: "RUNE".to_string() },
+        condition: PriceCondition::Below,
+        price: Decimal::from_str("4").unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), message_info(&user_address, &[]), ExecuteMsg::ExecuteInstance { instance }).unwrap();
+
+    let response = query_executable_actions(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap();
+    assert_eq!(response.actions[0].action_id, "stake_tokens");
+    assert!(matches!(response.actions[0].blocked, Some(ActionBlockReason::PriceTriggerNotMet { .. })));
+
+    deps.querier.prices.insert("RUNE".to_string(), "4".to_string());
+    let response = query_executable_actions(deps.as_ref(), env, user_address.to_string(), 1).unwrap();
+    assert_eq!(response.actions[0].blocked, None);
+}
+
+#[test]
+fn test_action_outside_its_timing_is_blocked() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    let mut workflow = create_simple_test_workflow(api);
+    workflow.actions.get_mut("stake_tokens").unwrap().timing = Some(ActionTiming {
+        cooldown: Some(3600),
+        ..Default::default()
+    });
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, workflow).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    let response = query_executable_actions(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap();
+    assert_eq!(response.actions[0].action_id, "stake_tokens");
+    assert!(matches!(response.actions[0].blocked, Some(ActionBlockReason::ActionTimingViolation { .. })));
+
+    env.block.time = Timestamp::from_seconds(3700);
+    let response = query_executable_actions(deps.as_ref(), env, user_address.to_string(), 1).unwrap();
+    assert_eq!(response.actions[0].blocked, None);
+}
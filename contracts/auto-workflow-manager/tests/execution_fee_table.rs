@@ -0,0 +1,147 @@
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::{ACTION_REPLY_ID_START, FEE_REPLY_ID_START},
+    msg::{ExecuteMsg, ExecutionFeeTable, FeeTotal, FeeType, UserFee},
+    query::{query_accrued_execution_fees, query_execution_fee_table},
+    state::PaymentConfig,
+};
+use cosmwasm_std::{Addr, Binary, Decimal, Env, Reply, Response, SubMsgResponse, SubMsgResult, Timestamp, Uint128};
+use std::collections::HashMap;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn create_fee_table() -> ExecutionFeeTable {
+    ExecutionFeeTable {
+        base_price: Uint128::from(10u128),
+        per_message: Uint128::from(5u128),
+        message_kinds: HashMap::from([("stake".to_string(), Uint128::from(30u128))]),
+    }
+}
+
+fn setup() -> (TestDeps, Env, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    execute_as(&mut deps, env.clone(), &user_address, ExecuteMsg::SetUserPaymentConfig { payment_config: PaymentConfig::Prepaid }).unwrap();
+    execute_as(&mut deps, env.clone(), &admin_address, ExecuteMsg::SetExecutionFeeTable { table: Some(create_fee_table()) }).unwrap();
+
+    (deps, env, admin_address, executor_address, user_address)
+}
+
+fn execute_as(deps: &mut TestDeps, env: Env, sender: &Addr, execute_msg: ExecuteMsg) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(sender, &[]), execute_msg)
+}
+
+#[allow(deprecated)]
+fn run_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr, result: SubMsgResult) {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    execute_as(deps, env.clone(), executor, execute_msg).unwrap();
+    reply(deps.as_mut(), env, Reply {
+        id: ACTION_REPLY_ID_START,
+        result,
+        gas_used: 0,
+        payload: Binary::default(),
+    }).unwrap();
+}
+
+#[allow(deprecated)]
+fn ok_result() -> SubMsgResult {
+    SubMsgResult::Ok(SubMsgResponse { events: vec![], msg_responses: vec![], data: None })
+}
+
+fn charge_execution_fee(deps: &mut TestDeps, env: Env, admin_address: &Addr, user_address: &Addr, amount: u128) -> Response {
+    let charge_fees_msg = ExecuteMsg::ChargeFees {
+        batch_id: format!("batch-{}", amount),
+        prices: HashMap::from([("uusdc".to_string(), ("".to_string(), Decimal::one()))]),
+        fees: vec![UserFee {
+            address: user_address.to_string(),
+            totals: vec![FeeTotal {
+                denom: "uusdc".to_string(),
+                debit_denom: "uusdc".to_string(),
+                amount: Uint128::from(amount),
+                fee_type: FeeType::Execution,
+                executor: None,
+            }],
+        }],
+    };
+    execute_as(deps, env, admin_address, charge_fees_msg).unwrap()
+}
+
+fn accrued(deps: &TestDeps, user_address: &Addr) -> Uint128 {
+    query_accrued_execution_fees(deps.as_ref(), user_address.to_string()).unwrap().accrued_usd
+}
+
+#[test]
+fn test_only_owner_sets_execution_fee_table() {
+    let (mut deps, env, admin_address, executor_address, _user_address) = setup();
+    assert_eq!(query_execution_fee_table(deps.as_ref()).unwrap().table, Some(create_fee_table()));
+
+    let result = execute_as(&mut deps, env.clone(), &executor_address, ExecuteMsg::SetExecutionFeeTable { table: None });
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    execute_as(&mut deps, env, &admin_address, ExecuteMsg::SetExecutionFeeTable { table: None }).unwrap();
+    assert_eq!(query_execution_fee_table(deps.as_ref()).unwrap().table, None);
+}
+
+#[test]
+fn test_successful_actions_accrue_execution_fees() {
+    let (mut deps, env, _admin_address, executor_address, user_address) = setup();
+
+    run_stake_action(&mut deps, env.clone(), &executor_address, &user_address, SubMsgResult::Err("out of funds".to_string()));
+    assert_eq!(accrued(&deps, &user_address), Uint128::zero());
+
+    // Base price plus the "stake" message price
+    run_stake_action(&mut deps, env, &executor_address, &user_address, ok_result());
+    assert_eq!(accrued(&deps, &user_address), Uint128::from(40u128));
+}
+
+#[test]
+fn test_execution_fees_above_accrued_are_rejected() {
+    let (mut deps, env, admin_address, executor_address, user_address) = setup();
+    run_stake_action(&mut deps, env.clone(), &executor_address, &user_address, ok_result());
+
+    let response = charge_execution_fee(&mut deps, env.clone(), &admin_address, &user_address, 41);
+    assert!(response.messages.is_empty());
+    assert!(response.events.iter().any(|event| event.ty == "autorujira-workflow-manager/execution-fee-error"));
+    assert_eq!(accrued(&deps, &user_address), Uint128::from(40u128));
+
+    let response = charge_execution_fee(&mut deps, env.clone(), &admin_address, &user_address, 25);
+    assert_eq!(response.messages.len(), 1);
+    assert_eq!(accrued(&deps, &user_address), Uint128::from(15u128));
+
+    let response = charge_execution_fee(&mut deps, env, &admin_address, &user_address, 16);
+    assert!(response.messages.is_empty());
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_rejected_execution_fee_stays_accrued() {
+    let (mut deps, env, admin_address, executor_address, user_address) = setup();
+    run_stake_action(&mut deps, env.clone(), &executor_address, &user_address, ok_result());
+
+    charge_execution_fee(&mut deps, env.clone(), &admin_address, &user_address, 25);
+    assert_eq!(accrued(&deps, &user_address), Uint128::from(15u128));
+
+    reply(deps.as_mut(), env, Reply {
+        id: FEE_REPLY_ID_START,
+        result: SubMsgResult::Err("insufficient balance".to_string()),
+        gas_used: 0,
+        payload: Binary::default(),
+    }).unwrap();
+    assert_eq!(accrued(&deps, &user_address), Uint128::from(40u128));
+}
@@ -0,0 +1,96 @@
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::ACTION_REPLY_ID_START,
+    msg::{ExecuteMsg, ExecutionType},
+    state::EXECUTION_ID_RETENTION,
+};
+use cosmwasm_std::{Addr, Binary, Reply, SubMsgResult, Timestamp};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+fn execute_stake_action(
+    deps: &mut cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>,
+    env: cosmwasm_std::Env,
+    executor: &Addr,
+    user_address: &Addr,
+    execution_id: &str,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: Some(execution_id.to_string()),
+    };
+    let execute_info = cosmwasm_std::testing::message_info(executor, &[]);
+    execute(deps.as_mut(), env, execute_info, execute_msg)
+}
+
+fn setup_recurrent_instance() -> (cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>, cosmwasm_std::Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+    (deps, env, executor_address, user_address)
+}
+
+#[test]
+fn test_duplicate_execution_id_is_rejected() {
+    let (mut deps, env, executor_address, user_address) = setup_recurrent_instance();
+
+    let response = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, "run-1").unwrap();
+    assert!(response.events[0].attributes.iter().any(|attr| attr.key == "execution_id" && attr.value == "run-1"));
+
+    // The recurrent instance would accept the start action again, but not the same execution
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, "run-1");
+    match result {
+        Err(ContractError::DuplicateExecution { execution_id, instance_id }) => {
+            assert_eq!(execution_id, "run-1");
+            assert_eq!(instance_id, "1");
+        }
+        _ => panic!("Expected DuplicateExecution error"),
+    }
+
+    execute_stake_action(&mut deps, env, &executor_address, &user_address, "run-2").unwrap();
+}
+
+#[test]
+fn test_failed_execution_can_be_retried_with_same_id() {
+    let (mut deps, env, executor_address, user_address) = setup_recurrent_instance();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, "run-1").unwrap();
+
+    #[allow(deprecated)]
+    let failure = Reply {
+        id: ACTION_REPLY_ID_START,
+        result: SubMsgResult::Err("out of funds".to_string()),
+        gas_used: 0,
+        payload: Binary::default(),
+    };
+    reply(deps.as_mut(), env.clone(), failure).unwrap();
+
+    execute_stake_action(&mut deps, env, &executor_address, &user_address, "run-1").unwrap();
+}
+
+#[test]
+fn test_execution_ids_expire_after_retention() {
+    let (mut deps, mut env, executor_address, user_address) = setup_recurrent_instance();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, "run-1").unwrap();
+
+    // Recording a new execution drops the ones past the retention
+    env.block.time = env.block.time.plus_seconds(EXECUTION_ID_RETENTION);
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, "run-2").unwrap();
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, "run-1").unwrap();
+
+    let result = execute_stake_action(&mut deps, env, &executor_address, &user_address, "run-2");
+    assert!(matches!(result, Err(ContractError::DuplicateExecution { .. })));
+}
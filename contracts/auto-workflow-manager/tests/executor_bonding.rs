@@ -0,0 +1,236 @@
+use auto_fee_manager::msg::{ExecuteMsg as FeeManagerExecuteMsg, Fee as FeeManagerFee, FeeType as FeeManagerFeeType};
+use auto_workflow_manager::{
+    contract::{execute, reply, sudo},
+    error::ContractError,
+    execute::{ACTION_REPLY_ID_START, FEE_REPLY_ID_START},
+    msg::{ExecuteMsg, ExecutionFeeTable, ExecutionType, ExecutorBondingConfig, FeeTotal, FeeType, SudoMsg, UserFee},
+    query::{query_executor, query_executors},
+    state::PaymentConfig,
+};
+use cosmwasm_std::{coin, from_json, testing::MockApi, Addr, BankMsg, Binary, CosmosMsg, Decimal, Env, Reply, SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup() -> (TestDeps, Env, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, admin_address, publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let bonded_executor_address = api.addr_make("bonded_executor");
+    env.block.time = Timestamp::from_seconds(100);
+
+    sudo(deps.as_mut(), env.clone(), SudoMsg::SetExecutorBonding(Some(ExecutorBondingConfig {
+        min_bond: coin(1000, "rune"),
+        reward_share: Decimal::from_str("0.2").unwrap(),
+        unbonding_period: 50,
+    }))).unwrap();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    instance.allow_bonded_executors = true;
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    (deps, env, admin_address, bonded_executor_address, user_address)
+}
+
+fn execute_as(deps: &mut TestDeps, env: Env, sender: &Addr, funds: &[cosmwasm_std::Coin], execute_msg: ExecuteMsg) -> Result<cosmwasm_std::Response, ContractError> {
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(sender, funds), execute_msg)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<cosmwasm_std::Response, ContractError> {
+    execute_instance_stake_action(deps, env, executor, user_address, 1)
+}
+
+fn execute_instance_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr, instance_id: u64) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    execute_as(deps, env, executor, &[], execute_msg)
+}
+
+#[test]
+fn test_bonded_executor_can_execute_actions() {
+    let (mut deps, env, _admin_address, executor_address, user_address) = setup();
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    // Below the minimum bond
+    let result = execute_as(&mut deps, env.clone(), &executor_address, &[coin(999, "rune")], ExecuteMsg::BondExecutor {});
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    execute_as(&mut deps, env.clone(), &executor_address, &[coin(1000, "rune")], ExecuteMsg::BondExecutor {}).unwrap();
+
+    let executor = query_executor(deps.as_ref(), executor_address.to_string()).unwrap().executor.unwrap();
+    assert_eq!(executor.bond, coin(1000, "rune"));
+    assert!(executor.active);
+    assert_eq!(query_executors(deps.as_ref(), None, None).unwrap().executors.len(), 1);
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+
+    // Instances that didn't opt in stay with the executor role
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    let result = execute_instance_stake_action(&mut deps, env, &executor_address, &user_address, 2);
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+}
+
+#[test]
+fn test_bond_top_up_requires_the_same_denom() {
+    let (mut deps, mut env, _admin_address, executor_address, _user_address) = setup();
+
+    execute_as(&mut deps, env.clone(), &executor_address, &[coin(1000, "rune")], ExecuteMsg::BondExecutor {}).unwrap();
+    sudo(deps.as_mut(), env.clone(), SudoMsg::SetExecutorBonding(Some(ExecutorBondingConfig {
+        min_bond: coin(1000, "uruji"),
+        reward_share: Decimal::from_str("0.2").unwrap(),
+        unbonding_period: 50,
+    }))).unwrap();
+
+    let result = execute_as(&mut deps, env.clone(), &executor_address, &[coin(1000, "uruji")], ExecuteMsg::BondExecutor {});
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+    assert_eq!(query_executor(deps.as_ref(), executor_address.to_string()).unwrap().executor.unwrap().bond, coin(1000, "rune"));
+
+    // The old bond is withdrawn in its own denom, then the executor bonds in the new one
+    execute_as(&mut deps, env.clone(), &executor_address, &[], ExecuteMsg::UnbondExecutor {}).unwrap();
+    env.block.time = Timestamp::from_seconds(150);
+    execute_as(&mut deps, env.clone(), &executor_address, &[], ExecuteMsg::WithdrawExecutorBond {}).unwrap();
+    execute_as(&mut deps, env.clone(), &executor_address, &[coin(1000, "uruji")], ExecuteMsg::BondExecutor {}).unwrap();
+    assert_eq!(query_executor(deps.as_ref(), executor_address.to_string()).unwrap().executor.unwrap().bond, coin(1000, "uruji"));
+}
+
+#[test]
+fn test_unbonding_executor_withdraws_after_period() {
+    let (mut deps, mut env, _admin_address, executor_address, user_address) = setup();
+
+    execute_as(&mut deps, env.clone(), &executor_address, &[coin(1500, "rune")], ExecuteMsg::BondExecutor {}).unwrap();
+    execute_as(&mut deps, env.clone(), &executor_address, &[], ExecuteMsg::UnbondExecutor {}).unwrap();
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    let result = execute_as(&mut deps, env.clone(), &executor_address, &[], ExecuteMsg::WithdrawExecutorBond {});
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    env.block.time = Timestamp::from_seconds(150);
+    let response = execute_as(&mut deps, env, &executor_address, &[], ExecuteMsg::WithdrawExecutorBond {}).unwrap();
+    match &response.messages[0].msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            assert_eq!(to_address, &executor_address.to_string());
+            assert_eq!(amount, &vec![coin(1500, "rune")]);
+        }
+        _ => panic!("Expected BankMsg::Send"),
+    }
+    assert!(query_executor(deps.as_ref(), executor_address.to_string()).unwrap().executor.is_none());
+}
+
+#[test]
+fn test_owner_slashes_executor_below_minimum() {
+    let (mut deps, env, admin_address, executor_address, user_address) = setup();
+
+    execute_as(&mut deps, env.clone(), &executor_address, &[coin(1200, "rune")], ExecuteMsg::BondExecutor {}).unwrap();
+
+    let slash_msg = ExecuteMsg::SlashExecutor {
+        executor: executor_address.to_string(),
+        amount: Uint128::from(300u128),
+        reason: "executed outside the schedule".to_string(),
+    };
+    let result = execute_as(&mut deps, env.clone(), &user_address, &[], slash_msg.clone());
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    let response = execute_as(&mut deps, env.clone(), &admin_address, &[], slash_msg).unwrap();
+    match &response.messages[0].msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            assert_eq!(to_address, &admin_address.to_string());
+            assert_eq!(amount, &vec![coin(300, "rune")]);
+        }
+        _ => panic!("Expected BankMsg::Send"),
+    }
+
+    // The remaining bond no longer covers the minimum
+    let executor = query_executor(deps.as_ref(), executor_address.to_string()).unwrap().executor.unwrap();
+    assert_eq!(executor.bond, coin(900, "rune"));
+    assert!(!executor.active);
+
+    let result = execute_stake_action(&mut deps, env, &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+}
+
+#[allow(deprecated)]
+fn reply_with(deps: &mut TestDeps, env: Env, reply_id: u64, result: SubMsgResult) {
+    reply(deps.as_mut(), env, Reply { id: reply_id, result, gas_used: 0, payload: Binary::default() }).unwrap();
+}
+
+#[allow(deprecated)]
+fn ok_result() -> SubMsgResult {
+    SubMsgResult::Ok(SubMsgResponse { events: vec![], msg_responses: vec![], data: None })
+}
+
+fn charge_execution_fee(deps: &mut TestDeps, env: Env, admin_address: &Addr, user_address: &Addr, executor_address: &Addr, batch_id: &str, amount: u128) -> Vec<FeeManagerFee> {
+    let charge_fees_msg = ExecuteMsg::ChargeFees {
+        batch_id: batch_id.to_string(),
+        prices: HashMap::from([("uusdc".to_string(), ("".to_string(), Decimal::one()))]),
+        fees: vec![UserFee {
+            address: user_address.to_string(),
+            totals: vec![FeeTotal {
+                denom: "uusdc".to_string(),
+                debit_denom: "uusdc".to_string(),
+                amount: Uint128::from(amount),
+                fee_type: FeeType::Execution,
+                executor: Some(executor_address.to_string()),
+            }],
+        }],
+    };
+    let response = execute_as(deps, env, admin_address, &[], charge_fees_msg).unwrap();
+
+    let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &response.messages[0].msg else {
+        panic!("Expected WasmMsg::Execute");
+    };
+    let FeeManagerExecuteMsg::ChargeFeesFromUserBalance { batch } = from_json(msg).unwrap() else {
+        panic!("Expected ChargeFeesFromUserBalance");
+    };
+    batch[0].fees.clone()
+}
+
+#[test]
+fn test_execution_fee_is_shared_with_bonded_executor() {
+    let (mut deps, env, admin_address, executor_address, user_address) = setup();
+    let role_executor_address = MockApi::default().addr_make("executor");
+
+    execute_as(&mut deps, env.clone(), &executor_address, &[coin(1000, "rune")], ExecuteMsg::BondExecutor {}).unwrap();
+    execute_as(&mut deps, env.clone(), &user_address, &[], ExecuteMsg::SetUserPaymentConfig { payment_config: PaymentConfig::Prepaid }).unwrap();
+    let fee_table = ExecutionFeeTable { base_price: Uint128::from(1000u128), per_message: Uint128::zero(), message_kinds: HashMap::new() };
+    execute_as(&mut deps, env.clone(), &admin_address, &[], ExecuteMsg::SetExecutionFeeTable { table: Some(fee_table) }).unwrap();
+
+    // Each executor accrues 1000 of the user's execution fees
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+    reply_with(&mut deps, env.clone(), ACTION_REPLY_ID_START, ok_result());
+    execute_stake_action(&mut deps, env.clone(), &role_executor_address, &user_address).unwrap();
+    reply_with(&mut deps, env.clone(), ACTION_REPLY_ID_START, ok_result());
+
+    let fees = charge_execution_fee(&mut deps, env.clone(), &admin_address, &user_address, &executor_address, "batch-1", 800);
+    assert_eq!(fees.len(), 2);
+    assert_eq!(fees[0].fee_type, FeeManagerFeeType::Execution);
+    assert_eq!(fees[0].amount, Uint128::from(640u128));
+    assert_eq!(fees[1].fee_type, FeeManagerFeeType::Executor { executor_address: executor_address.clone() });
+    assert_eq!(fees[1].amount, Uint128::from(160u128));
+
+    // The executor only accrued 200 more, the rest of the fee isn't rewarded
+    let fees = charge_execution_fee(&mut deps, env.clone(), &admin_address, &user_address, &executor_address, "batch-2", 1000);
+    assert_eq!(fees[0].amount, Uint128::from(960u128));
+    assert_eq!(fees[1].amount, Uint128::from(40u128));
+
+    // A fee rejected by the fee manager gives the executor back what it accrued
+    reply_with(&mut deps, env.clone(), FEE_REPLY_ID_START, SubMsgResult::Err("insufficient balance".to_string()));
+    execute_stake_action(&mut deps, env.clone(), &role_executor_address, &user_address).unwrap();
+    reply_with(&mut deps, env.clone(), ACTION_REPLY_ID_START, ok_result());
+    let fees = charge_execution_fee(&mut deps, env, &admin_address, &user_address, &executor_address, "batch-3", 1000);
+    assert_eq!(fees[1].amount, Uint128::from(40u128));
+}
@@ -0,0 +1,110 @@
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::FEE_REPLY_ID_START,
+    msg::{ExecuteMsg, FeeTotal, FeeType, UserFee},
+    query::query_fee_batch,
+    state::{FeeBatchFailure, PaymentConfig},
+};
+use cosmwasm_std::{coin, Addr, Binary, Decimal, Env, Reply, Response, SubMsgResponse, SubMsgResult, Timestamp, Uint128};
+use std::collections::HashMap;
+
+mod utils;
+use utils::create_test_environment;
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup() -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, admin_address, _publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        cosmwasm_std::testing::message_info(&user_address, &[]),
+        ExecuteMsg::SetUserPaymentConfig { payment_config: PaymentConfig::Prepaid },
+    ).unwrap();
+    (deps, env, admin_address, user_address)
+}
+
+fn fee_total(denom: &str, amount: u128) -> FeeTotal {
+    FeeTotal {
+        denom: denom.to_string(),
+        debit_denom: "uusdc".to_string(),
+        amount: Uint128::from(amount),
+        fee_type: FeeType::Execution,
+        executor: None,
+    }
+}
+
+// One uusdc fee the batch can charge and one in a denom without a price
+fn charge_fees(deps: &mut TestDeps, env: Env, admin_address: &Addr, user_address: &Addr, batch_id: &str) -> Result<Response, ContractError> {
+    let charge_fees_msg = ExecuteMsg::ChargeFees {
+        batch_id: batch_id.to_string(),
+        prices: HashMap::from([("uusdc".to_string(), ("".to_string(), Decimal::one()))]),
+        fees: vec![UserFee {
+            address: user_address.to_string(),
+            totals: vec![fee_total("uusdc", 1000), fee_total("unknown", 5)],
+        }],
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(admin_address, &[]), charge_fees_msg)
+}
+
+#[allow(deprecated)]
+fn fee_manager_reply(result: SubMsgResult) -> Reply {
+    Reply {
+        id: FEE_REPLY_ID_START,
+        result,
+        gas_used: 0,
+        payload: Binary::default(),
+    }
+}
+
+#[test]
+fn test_resubmitted_batch_is_rejected() {
+    let (mut deps, env, admin_address, user_address) = setup();
+    assert!(query_fee_batch(deps.as_ref(), "batch-1".to_string()).unwrap().batch.is_none());
+
+    charge_fees(&mut deps, env.clone(), &admin_address, &user_address, "batch-1").unwrap();
+
+    let result = charge_fees(&mut deps, env.clone(), &admin_address, &user_address, "batch-1");
+    assert!(matches!(result, Err(ContractError::FeeBatchAlreadyProcessed { batch_id }) if batch_id == "batch-1"));
+
+    charge_fees(&mut deps, env, &admin_address, &user_address, "batch-2").unwrap();
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_batch_outcome_records_charges_and_failures() {
+    let (mut deps, env, admin_address, user_address) = setup();
+
+    charge_fees(&mut deps, env.clone(), &admin_address, &user_address, "batch-1").unwrap();
+    reply(deps.as_mut(), env.clone(), fee_manager_reply(SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        msg_responses: vec![],
+        data: None,
+    }))).unwrap();
+
+    let batch = query_fee_batch(deps.as_ref(), "batch-1".to_string()).unwrap().batch.unwrap();
+    assert_eq!(batch.processed_at, Timestamp::from_seconds(100));
+    assert_eq!(batch.charged_users, vec![user_address.to_string()]);
+    assert_eq!(batch.totals, vec![coin(1000, "uusdc")]);
+    assert_eq!(batch.failures, vec![FeeBatchFailure {
+        user_address: user_address.to_string(),
+        denom: "unknown".to_string(),
+        amount: Uint128::from(5u128),
+        error: "Price not found for fee denom".to_string(),
+    }]);
+
+    // Fees rejected by the fee manager aren't charged
+    charge_fees(&mut deps, env.clone(), &admin_address, &user_address, "batch-2").unwrap();
+    reply(deps.as_mut(), env, fee_manager_reply(SubMsgResult::Err("insufficient balance".to_string()))).unwrap();
+
+    let batch = query_fee_batch(deps.as_ref(), "batch-2".to_string()).unwrap().batch.unwrap();
+    assert!(batch.charged_users.is_empty());
+    assert!(batch.totals.is_empty());
+    assert_eq!(batch.failures.len(), 2);
+    assert_eq!(batch.failures[1].denom, "uusdc");
+    assert_eq!(batch.failures[1].error, "insufficient balance");
+}
@@ -1,6 +1,6 @@
-use anybuf::Bufany;
+use anybuf::{Anybuf, Bufany};
 use cosmwasm_std::{
-  coin, testing::{MockApi, MockStorage}, Addr, AnyMsg, Api, BlockInfo, Coin, CosmosMsg, CustomMsg, CustomQuery, Decimal, Empty, Event, Storage, Timestamp, Uint128, WasmMsg
+  coin, testing::{MockApi, MockStorage}, Addr, AnyMsg, Api, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg, CustomQuery, Decimal, Empty, Event, GrpcQuery, Querier, Storage, Timestamp, Uint128, WasmMsg
 };
 use serde::de::DeserializeOwned;
 use std::{collections::{HashMap, HashSet}, str::FromStr};
@@ -15,7 +15,7 @@ use auto_workflow_manager::{
       sudo as sudo_workflow_manager
     },
     msg::{
-      ExecuteMsg as WorkflowManagerExecuteMsg, ExecutionType, FeeTotal as WorkflowManagerFeeTotal, FeeType as WorkflowManagerFeeType, GetUserPaymentConfigResponse, InstantiateMsg as WorkflowManagerInstantiateMsg, NewInstanceMsg, NewWorkflowMsg, QueryMsg as WorkflowManagerQueryMsg, SudoMsg as WorkflowManagerSudoMsg, UserFee as WorkflowManagerUserFee, WorkflowVisibility
+      ActionMsg, ExecuteMsg as WorkflowManagerExecuteMsg, ExecutionType, GetRequiredGrantsResponse, GetWorkflowStatsResponse, Template, FeeTotal as WorkflowManagerFeeTotal, FeeType as WorkflowManagerFeeType, GetUserPaymentConfigResponse, InstantiateMsg as WorkflowManagerInstantiateMsg, NewInstanceMsg, NewWorkflowMsg, QueryMsg as WorkflowManagerQueryMsg, SudoMsg as WorkflowManagerSudoMsg, UserFee as WorkflowManagerUserFee, WorkflowVisibility
     }, 
     state::PaymentConfig as WorkflowManagerPaymentConfig,
 };
@@ -93,12 +93,14 @@ fn test_charge_fees_ok_prepaid_execution() {
           debit_denom: "uusdc".to_string(),
           amount: Uint128::from(500_000u128),
           fee_type: WorkflowManagerFeeType::Execution,
+          executor: None,
         },
         WorkflowManagerFeeTotal {
           denom: "rune".to_string(),
           debit_denom: "uusdc".to_string(),
           amount: Uint128::from(100_000u128),
           fee_type: WorkflowManagerFeeType::Execution,
+          executor: None,
         },
       ],
     },
@@ -202,6 +204,7 @@ fn test_charge_fees_ok_prepaid_creator() {
       end_actions: HashSet::new(),
       visibility: WorkflowVisibility::Public,
       actions: HashMap::new(),
+      creator_fee_schedule: None,
     },
   };
   app.execute_contract(addresses.workflow_publisher.clone(), addresses.contract_workflow_manager.clone(), &publish_workflow_msg, &[]).unwrap();
@@ -215,6 +218,10 @@ fn test_charge_fees_ok_prepaid_creator() {
       execution_type: ExecutionType::OneShot,
       expiration_time: Timestamp::from_seconds(1000000000),
       cron_expression: None,
+      param_signer: None,
+      param_constraints: HashMap::new(),
+      price_trigger: None,
+      allow_bonded_executors: false,
     },
   };
   let execute_instance_result = app.execute_contract(addresses.workflow_executor.clone(), addresses.contract_workflow_manager.clone(), &execute_instance_msg, &[]).unwrap();
@@ -238,6 +245,7 @@ fn test_charge_fees_ok_prepaid_creator() {
           debit_denom: "uusdc".to_string(),
           amount: Uint128::from(100_000u128),
           fee_type: WorkflowManagerFeeType::Creator { instance_id: instance_id.parse::<u64>().unwrap() },
+          executor: None,
         },
       ],
     },
@@ -265,6 +273,12 @@ fn test_charge_fees_ok_prepaid_creator() {
   assert_eq!(uusdc_charged_fee.clone().unwrap().usd_amount, "25000");
   assert_eq!(uusdc_charged_fee.clone().unwrap().debit_denom, "uusdc");
   assert_eq!(uusdc_charged_fee.clone().unwrap().debit_amount, "25000");
+
+  // The creator fee is attributed to the workflow in the debit denom
+  let workflow_stats: GetWorkflowStatsResponse = app.wrap().query_wasm_smart(
+    addresses.contract_workflow_manager.clone(),
+    &WorkflowManagerQueryMsg::GetWorkflowStats { workflow_id: "workflow_id_1".to_string() }).unwrap();
+  assert_eq!(workflow_stats.stats.creator_fees, vec![coin(25000, "uusdc")]);
     
   // println!("Charge fees done");
   // println!("--------------------------------------------------");
@@ -306,6 +320,9 @@ fn test_charge_fees_ok_wallet() {
   ).unwrap();
   // println!("User payment config set");
 
+  // Grant the workflow manager to pay fees from the wallet
+  grant_contract_execution(&mut app, &addresses.workflow_executor, &addresses.contract_workflow_manager, &[&addresses.contract_fee_manager]);
+
   // Get user payment config
   let _user_payment_config: GetUserPaymentConfigResponse = app.wrap().query_wasm_smart(
     addresses.contract_workflow_manager.clone(), 
@@ -335,12 +352,14 @@ fn test_charge_fees_ok_wallet() {
           debit_denom: "rune".to_string(),
           amount: Uint128::from(500_000u128),
           fee_type: WorkflowManagerFeeType::Execution,
+          executor: None,
         },
         WorkflowManagerFeeTotal {
           denom: "rune".to_string(),
           debit_denom: "rune".to_string(),
           amount: Uint128::from(100_000u128),
           fee_type: WorkflowManagerFeeType::Execution,
+          executor: None,
         },
       ],
     },
@@ -414,6 +433,9 @@ fn test_charge_fees_ok_wallet_creator() {
   ).unwrap();
   // println!("User payment config set");
 
+  // Grant the workflow manager to pay fees from the wallet
+  grant_contract_execution(&mut app, &addresses.workflow_executor, &addresses.contract_workflow_manager, &[&addresses.contract_fee_manager]);
+
   // Get user payment config
   let _user_payment_config: GetUserPaymentConfigResponse = app.wrap().query_wasm_smart(
     addresses.contract_workflow_manager.clone(), 
@@ -438,6 +460,7 @@ fn test_charge_fees_ok_wallet_creator() {
       end_actions: HashSet::new(),
       visibility: WorkflowVisibility::Public,
       actions: HashMap::new(),
+      creator_fee_schedule: None,
     },
   };
   app.execute_contract(addresses.workflow_publisher.clone(), addresses.contract_workflow_manager.clone(), &publish_workflow_msg, &[]).unwrap();
@@ -451,6 +474,10 @@ fn test_charge_fees_ok_wallet_creator() {
       execution_type: ExecutionType::OneShot,
       expiration_time: Timestamp::from_seconds(1000000000),
       cron_expression: None,
+      param_signer: None,
+      param_constraints: HashMap::new(),
+      price_trigger: None,
+      allow_bonded_executors: false,
     },
   };
   let execute_instance_result = app.execute_contract(addresses.workflow_executor.clone(), addresses.contract_workflow_manager.clone(), &execute_instance_msg, &[]).unwrap();
@@ -474,12 +501,14 @@ fn test_charge_fees_ok_wallet_creator() {
           debit_denom: "rune".to_string(),
           amount: Uint128::from(2u128),
           fee_type: WorkflowManagerFeeType::Execution,
+          executor: None,
         },
         WorkflowManagerFeeTotal {
           denom: "uruji".to_string(),
           debit_denom: "rune".to_string(),
           amount: Uint128::from(1_647_083u128),
           fee_type: WorkflowManagerFeeType::Creator { instance_id: instance_id.parse::<u64>().unwrap() },
+          executor: None,
         },
       ],
     },
@@ -534,6 +563,131 @@ fn test_charge_fees_ok_wallet_creator() {
   // println!("--------------------------------------------------");
 }
 
+#[test]
+fn test_charge_fees_wallet_without_grant() {
+  let mut app= BasicAppBuilder::new()
+    .with_stargate(CustomStargate {})
+    .build(|_, _, _| {});
+  let addresses = deploy_contracts(&mut app);
+
+  let set_user_payment_config_msg = WorkflowManagerExecuteMsg::SetUserPaymentConfig {
+    payment_config: WorkflowManagerPaymentConfig::Wallet {
+      usd_allowance: Uint128::from(100_000_000u128),
+    },
+  };
+  app.execute_contract(addresses.workflow_executor.clone(), addresses.contract_workflow_manager.clone(), &set_user_payment_config_msg, &[]).unwrap();
+
+  let charge_fees_msg = WorkflowManagerExecuteMsg::ChargeFees {
+    batch_id: "1".to_string(),
+    prices: HashMap::from([("uusdc".to_string(), ("uusdc".to_string(), Decimal::one()))]),
+    fees: vec![WorkflowManagerUserFee {
+      address: addresses.workflow_executor.to_string(),
+      totals: vec![WorkflowManagerFeeTotal {
+        denom: "uusdc".to_string(),
+        debit_denom: "uusdc".to_string(),
+        amount: Uint128::from(500_000u128),
+        fee_type: WorkflowManagerFeeType::Execution,
+        executor: None,
+      }],
+    }],
+  };
+  let charge_fees_result = app.execute_contract(addresses.crank.clone(), addresses.contract_workflow_manager.clone(), &charge_fees_msg, &[]).unwrap();
+
+  // The user is skipped with an error event instead of failing inside authz
+  let authz_error_event = charge_fees_result.events.iter()
+    .find(|event| event.ty == "wasm-autorujira-workflow-manager/fee-authz-error").unwrap();
+  assert!(authz_error_event.attributes.iter().any(|attr| attr.key == "contract" && attr.value == addresses.contract_fee_manager.to_string()));
+  assert!(!charge_fees_result.events.iter().any(|event| event.ty == "wasm-autorujira-workflow-manager/fee-charged"));
+}
+
+#[test]
+fn test_execute_action_without_grant() {
+  let mut app= BasicAppBuilder::new()
+    .with_stargate(CustomStargate {})
+    .build(|_, _, _| {});
+  let addresses = deploy_contracts(&mut app);
+  let contract_to_call = addresses.contract_fee_manager.to_string();
+
+  // A workflow depositing into the fee manager on behalf of the user
+  let publish_workflow_msg = WorkflowManagerExecuteMsg::PublishWorkflow {
+    workflow: NewWorkflowMsg {
+      id: "workflow_id_1".to_string(),
+      start_actions: HashSet::from(["deposit".to_string()]),
+      end_actions: HashSet::from(["deposit".to_string()]),
+      visibility: WorkflowVisibility::Public,
+      actions: HashMap::from([(
+        "deposit".to_string(),
+        ActionMsg {
+          params: HashMap::new(),
+          next_actions: HashSet::new(),
+          templates: HashMap::from([(
+            "default".to_string(),
+            Template {
+              contract: contract_to_call.clone(),
+              message: "{\"deposit\":{}}".to_string(),
+              funds: vec![],
+            },
+          )]),
+          whitelisted_contracts: HashSet::from([contract_to_call.clone()]),
+          allowed_messages: HashMap::new(),
+          outputs: HashMap::new(),
+          signed_params: HashSet::new(),
+          param_constraints: HashMap::new(),
+          sub_workflow: None,
+          min_return_protection: None,
+          timing: None,
+        },
+      )]),
+      creator_fee_schedule: None,
+    },
+  };
+  app.execute_contract(addresses.workflow_publisher.clone(), addresses.contract_workflow_manager.clone(), &publish_workflow_msg, &[]).unwrap();
+
+  let execute_instance_msg = WorkflowManagerExecuteMsg::ExecuteInstance {
+    instance: NewInstanceMsg {
+      workflow_id: "workflow_id_1".to_string(),
+      onchain_parameters: HashMap::new(),
+      offchain_parameters: HashMap::new(),
+      execution_type: ExecutionType::OneShot,
+      expiration_time: Timestamp::from_seconds(4_000_000_000),
+      cron_expression: None,
+      param_signer: None,
+      param_constraints: HashMap::new(),
+      price_trigger: None,
+      allow_bonded_executors: false,
+    },
+  };
+  app.execute_contract(addresses.workflow_executor.clone(), addresses.contract_workflow_manager.clone(), &execute_instance_msg, &[]).unwrap();
+
+  // The required grants list the workflow contracts
+  let required_grants: GetRequiredGrantsResponse = app.wrap().query_wasm_smart(
+    addresses.contract_workflow_manager.clone(),
+    &WorkflowManagerQueryMsg::GetRequiredGrants { workflow_id: "workflow_id_1".to_string(), user_address: Some(addresses.workflow_executor.to_string()) }).unwrap();
+  assert_eq!(required_grants.grants.len(), 1);
+  assert_eq!(required_grants.grants[0].grantee, addresses.contract_workflow_manager);
+  assert_eq!(required_grants.grants[0].msg_type_url, "/cosmwasm.wasm.v1.MsgExecuteContract");
+  assert_eq!(required_grants.grants[0].contracts, vec![contract_to_call]);
+
+  let execute_action_msg = WorkflowManagerExecuteMsg::ExecuteAction {
+    user_address: addresses.workflow_executor.to_string(),
+    instance_id: 1,
+    action_id: "deposit".to_string(),
+    template_id: "default".to_string(),
+    params: None,
+    params_signature: None,
+    execution_id: Some("execution-1".to_string()),
+  };
+  let execute_action_result = app.execute_contract(addresses.crank.clone(), addresses.contract_workflow_manager.clone(), &execute_action_msg, &[]).unwrap();
+  assert!(execute_action_result.events.iter().any(|event| event.ty == "wasm-autorujira-workflow-manager/action-authz-error"));
+  assert!(!execute_action_result.events.iter().any(|event| event.ty == "wasm-autorujira-workflow-manager/execute_action"));
+
+  // Once granted, the same execution goes through
+  grant_contract_execution(&mut app, &addresses.workflow_executor, &addresses.contract_workflow_manager, &[&addresses.contract_fee_manager]);
+  let execute_action_result = app.execute_contract(addresses.crank.clone(), addresses.contract_workflow_manager.clone(), &execute_action_msg, &[]).unwrap();
+  assert!(!execute_action_result.events.iter().any(|event| event.ty == "wasm-autorujira-workflow-manager/action-authz-error"));
+  assert!(execute_action_result.events.iter().any(|event| event.ty == "wasm-autorujira-workflow-manager/execute_action"));
+}
+
 #[derive(Debug)]
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -603,6 +757,8 @@ fn deploy_contracts(app: &mut CustomApp) -> Addresses {
     allowed_action_executors: HashSet::from([crank_addr.clone()]),
     referral_memo: "test-referral-memo".to_string(),
     fee_manager_address: fee_manager_address.clone(),
+    max_consecutive_failures: None,
+    executor_bonding: None,
   };  
   let workflow_manager_address = app.instantiate_contract(code_id_workflow_manager, contracts_creator_addr.clone(), &workflow_manager_instantiate_msg, &[], "workflow_manager", None).unwrap();
 
@@ -709,12 +865,28 @@ impl Stargate for CustomStargate {
         let serialized = msg.value.to_vec();
         let buf = Bufany::deserialize(&serialized)?;
         match type_url.as_str() {
+            "/cosmos.authz.v1beta1.MsgGrant" => {
+              let granter = buf.string(1).unwrap();
+              let grantee = buf.string(2).unwrap();
+              let grant = buf.bytes(3).unwrap();
+              let grant_buf = buf.message(3).unwrap();
+              let authorization = grant_buf.message(1).unwrap();
+              let msg_type_url = match authorization.string(1).unwrap().as_str() {
+                "/cosmwasm.wasm.v1.ContractExecutionAuthorization" => "/cosmwasm.wasm.v1.MsgExecuteContract".to_string(),
+                _ => Bufany::deserialize(&authorization.bytes(2).unwrap())?.string(1).unwrap(),
+              };
+              storage.set(&grant_key(&granter, &grantee, &msg_type_url), &grant);
+              Ok(AppResponse::default())
+            }
             "/cosmos.authz.v1beta1.MsgExec" => {
-              let _authz_grantee   = buf.string(1).unwrap();
+              let authz_grantee   = buf.string(1).unwrap();
               let buf_2 = buf.message(2).unwrap();
-              let _message_type_url = buf_2.string(1).unwrap();
+              let message_type_url = buf_2.string(1).unwrap();
               let buf_3 = buf_2.message(2).unwrap();
               let authz_granter = buf_3.string(1).unwrap();
+              if storage.get(&grant_key(&authz_granter, &authz_grantee, &message_type_url)).is_none() {
+                anyhow::bail!("authorization not found");
+              }
               let contract_to_call = buf_3.string(2).unwrap();
               let msg_to_call = buf_3.string(3).unwrap();
 
@@ -745,4 +917,51 @@ impl Stargate for CustomStargate {
         }
     }
 
+    fn query_grpc(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        match request.path.as_str() {
+            "/cosmos.authz.v1beta1.Query/Grants" => {
+              let buf = Bufany::deserialize(&request.data)?;
+              let key = grant_key(&buf.string(1).unwrap(), &buf.string(2).unwrap(), &buf.string(3).unwrap());
+              let grants: Vec<Vec<u8>> = storage.get(&key).into_iter().collect();
+              let mut response = Anybuf::new();
+              for grant in grants {
+                response = response.append_bytes(1, grant);
+              }
+              Ok(response.into_vec().into())
+            }
+            _ => {
+                anyhow::bail!("Unexpected grpc query: request={:?}", request)
+            }
+        }
+    }
+}
+
+// Grants are keyed by granter, grantee and message type like in the authz module
+fn grant_key(granter: &str, grantee: &str, msg_type_url: &str) -> Vec<u8> {
+  format!("authz/{}/{}/{}", granter, grantee, msg_type_url).into_bytes()
+}
+
+// Grants a ContractExecutionAuthorization for the given contracts, without expiration
+fn grant_contract_execution(app: &mut CustomApp, granter: &Addr, grantee: &Addr, contracts: &[&Addr]) {
+  let contract_grants: Vec<Anybuf> = contracts.iter()
+    .map(|contract| Anybuf::new().append_string(1, contract))
+    .collect();
+  let authorization = Anybuf::new()
+    .append_string(1, "/cosmwasm.wasm.v1.ContractExecutionAuthorization")
+    .append_bytes(2, Anybuf::new().append_repeated_message(1, &contract_grants).into_vec());
+  let msg_grant = Anybuf::new()
+    .append_string(1, granter)
+    .append_string(2, grantee)
+    .append_message(3, &Anybuf::new().append_message(1, &authorization));
+  app.execute(granter.clone(), CosmosMsg::Any(AnyMsg {
+    type_url: "/cosmos.authz.v1beta1.MsgGrant".to_string(),
+    value: msg_grant.into_vec().into(),
+  })).unwrap();
 }
@@ -0,0 +1,134 @@
+use auto_workflow_manager::{
+    contract::{execute, reply, sudo},
+    error::ContractError,
+    execute::ACTION_REPLY_ID_START,
+    msg::{ExecuteMsg, SudoMsg},
+    query::query_instance_lease,
+};
+use cosmwasm_std::{Addr, Binary, Env, Reply, SubMsgResponse, SubMsgResult, Timestamp};
+use std::collections::HashSet;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup() -> (TestDeps, Env, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let other_executor_address = api.addr_make("other_executor");
+    env.block.time = Timestamp::from_seconds(100);
+
+    sudo(deps.as_mut(), env.clone(), SudoMsg::SetAllowedActionExecutors(HashSet::from([
+        executor_address.clone(),
+        other_executor_address.clone(),
+    ]))).unwrap();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    (deps, env, executor_address, other_executor_address, user_address)
+}
+
+fn claim_lease(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr, duration_seconds: u64) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ClaimLease {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        duration_seconds,
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(executor, &[]), execute_msg)
+}
+
+fn release_lease(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ReleaseLease {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(executor, &[]), execute_msg)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(executor, &[]), execute_msg)
+}
+
+#[test]
+fn test_only_leaseholder_can_execute_actions() {
+    let (mut deps, env, executor_address, other_executor_address, user_address) = setup();
+
+    claim_lease(&mut deps, env.clone(), &executor_address, &user_address, 60).unwrap();
+
+    let result = execute_stake_action(&mut deps, env.clone(), &other_executor_address, &user_address);
+    match result {
+        Err(ContractError::InstanceLeased { holder, .. }) => assert_eq!(holder, executor_address.to_string()),
+        _ => panic!("Expected InstanceLeased error"),
+    }
+
+    // Another executor can't claim an active lease either
+    let result = claim_lease(&mut deps, env.clone(), &other_executor_address, &user_address, 60);
+    assert!(matches!(result, Err(ContractError::InstanceLeased { .. })));
+
+    execute_stake_action(&mut deps, env, &executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_expired_lease_can_be_taken_over() {
+    let (mut deps, mut env, executor_address, other_executor_address, user_address) = setup();
+
+    claim_lease(&mut deps, env.clone(), &executor_address, &user_address, 60).unwrap();
+
+    // The holder renews before expiry
+    env.block.time = Timestamp::from_seconds(150);
+    claim_lease(&mut deps, env.clone(), &executor_address, &user_address, 60).unwrap();
+    let lease = query_instance_lease(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap().lease.unwrap();
+    assert_eq!(lease.expires_at, Timestamp::from_seconds(210));
+
+    env.block.time = Timestamp::from_seconds(210);
+    assert!(query_instance_lease(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap().lease.is_none());
+
+    claim_lease(&mut deps, env.clone(), &other_executor_address, &user_address, 60).unwrap();
+    execute_stake_action(&mut deps, env, &other_executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_release_lease() {
+    let (mut deps, env, executor_address, other_executor_address, user_address) = setup();
+
+    claim_lease(&mut deps, env.clone(), &executor_address, &user_address, 60).unwrap();
+
+    let result = release_lease(&mut deps, env.clone(), &other_executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    release_lease(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+    execute_stake_action(&mut deps, env, &other_executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_lease_is_released_on_run_completion() {
+    let (mut deps, env, executor_address, _other_executor_address, user_address) = setup();
+
+    claim_lease(&mut deps, env.clone(), &executor_address, &user_address, 60).unwrap();
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+
+    #[allow(deprecated)]
+    let success = Reply {
+        id: ACTION_REPLY_ID_START,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            msg_responses: vec![],
+            data: None,
+        }),
+        gas_used: 0,
+        payload: Binary::default(),
+    };
+    reply(deps.as_mut(), env.clone(), success).unwrap();
+
+    assert!(query_instance_lease(deps.as_ref(), env, user_address.to_string(), 1).unwrap().lease.is_none());
+}
@@ -0,0 +1,133 @@
+use auto_workflow_manager::{
+    error::ContractError,
+    msg::{ActionParamValue, NewWorkflowMsg},
+    query::query_simulate_action,
+};
+use cosmwasm_std::{coins, testing::MockApi, to_json_binary, Addr, ContractResult, CosmosMsg, Decimal, Env, SystemResult, Timestamp, WasmMsg, WasmQuery};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn create_live_workflow(api: MockApi, message: &str, params: Vec<(&str, &str)>) -> NewWorkflowMsg {
+    let mut workflow = create_simple_test_workflow(api);
+    let action = workflow.actions.get_mut("stake_tokens").unwrap();
+    action.params = params.into_iter().map(|(param_id, value)| (param_id.to_string(), ActionParamValue::String(value.to_string()))).collect();
+    action.templates.get_mut("default").unwrap().message = message.to_string();
+    workflow
+}
+
+fn setup(message: &str, params: Vec<(&str, &str)>) -> (TestDeps, Env, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_live_workflow(api, message, params)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    deps.querier.bank.update_balance(&user_address, coins(1001, "rune"));
+    let contract_address = api.addr_make("contract_to_call").to_string();
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if *contract_addr == contract_address && msg.as_slice() == b"{\"pool\":{}}" => {
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&serde_json::json!({ "pool": { "reserves": ["300", "700"], "active": true } })).unwrap()))
+        }
+        _ => SystemResult::Ok(ContractResult::Err("unknown query".to_string())),
+    });
+
+    (deps, env, user_address)
+}
+
+fn simulate(deps: &TestDeps, env: Env, user_address: &Addr) -> Result<String, String> {
+    let simulation = query_simulate_action(deps.as_ref(), env, user_address.to_string(), 1, "stake_tokens".to_string(), "default".to_string(), None).unwrap();
+    if let Some(error) = simulation.error {
+        return Err(error.message);
+    }
+    match &simulation.messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => Ok(String::from_utf8(msg.to_vec()).unwrap()),
+        _ => panic!("Expected WasmMsg::Execute"),
+    }
+}
+
+#[test]
+fn test_balance_and_query_placeholders() {
+    let contract_address = MockApi::default().addr_make("contract_to_call");
+    let (deps, env, user_address) = setup(
+        "{\"balance\":\"{{balance}}\",\"reserve\":\"{{reserve}}\",\"active\":{{active}}}",
+        vec![
+            ("balance", "#bal.rune"),
+            ("reserve", &format!("#q.{}.pool.pool.reserves.1", contract_address)),
+            ("active", &format!("#q.{}.pool.pool.active", contract_address)),
+        ],
+    );
+
+    assert_eq!(simulate(&deps, env, &user_address).unwrap(), "{\"balance\":\"1001\",\"reserve\":\"700\",\"active\":true}");
+}
+
+#[test]
+fn test_placeholder_arithmetic() {
+    let contract_address = MockApi::default().addr_make("contract_to_call");
+    let (deps, env, user_address) = setup(
+        "{\"half\":\"{{half}}\",\"minus\":\"{{minus}}\",\"ratio\":\"{{ratio}}\"}",
+        vec![
+            ("half", "#bal.rune * 50%"),
+            ("minus", "#bal.rune - 1"),
+            ("ratio", &format!("#q.{}.pool.pool.reserves.0 / 7", contract_address)),
+        ],
+    );
+
+    // Integer values are rounded down, 300 / 7 renders as 42
+    assert_eq!(simulate(&deps, env, &user_address).unwrap(), "{\"half\":\"500\",\"minus\":\"1000\",\"ratio\":\"42\"}");
+}
+
+#[test]
+fn test_query_placeholder_requires_whitelisted_contract() {
+    let other_contract = MockApi::default().addr_make("other_contract");
+    let (deps, env, user_address) = setup("{\"amount\":\"{{amount}}\"}", vec![("amount", &format!("#q.{}.pool.pool.active", other_contract))]);
+
+    let error = simulate(&deps, env, &user_address).unwrap_err();
+    assert_eq!(error, ContractError::ContractNotWhitelisted {
+        contract: other_contract.to_string(),
+        workflow_id: "simple-test-workflow".to_string(),
+    }.to_string());
+}
+
+#[test]
+fn test_decimal_arithmetic_stays_decimal() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    let workflow = create_live_workflow(
+        api,
+        "{\"whole\":\"{{whole}}\",\"zero\":\"{{zero}}\"}",
+        vec![("whole", "#ip.whole / 4"), ("zero", "#ip.zero + 0.5")],
+    );
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, workflow).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.onchain_parameters = HashMap::from([
+        ("whole".to_string(), ActionParamValue::Decimal(Decimal::from_str("2").unwrap())),
+        ("zero".to_string(), ActionParamValue::Decimal(Decimal::zero())),
+    ]);
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    // Whole and zero decimals aren't rounded like integers
+    assert_eq!(simulate(&deps, env, &user_address).unwrap(), "{\"whole\":\"0.5\",\"zero\":\"0.5\"}");
+}
+
+#[test]
+fn test_template_with_param_only_placeholder_is_rejected() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+
+    let workflow = create_live_workflow(api, "{\"amount\":\"#bal.rune\"}", vec![]);
+    let result = publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), workflow);
+    assert!(matches!(result, Err(ContractError::GenericError(error)) if error.contains("#bal.")));
+
+    let mut workflow = create_live_workflow(api, "{\"stake\":{}}", vec![]);
+    let template = workflow.actions.get_mut("stake_tokens").unwrap().templates.get_mut("default").unwrap();
+    template.funds = vec![("#q.pool.amount".to_string(), "rune".to_string())];
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, workflow);
+    assert!(matches!(result, Err(ContractError::GenericError(error)) if error.contains("#q.")));
+}
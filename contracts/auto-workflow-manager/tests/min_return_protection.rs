@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ExecuteMsg, MinReturnProtection, NewWorkflowMsg, SimulationStage, SwapVenue},
+    query::query_simulate_action,
+};
+use cosmwasm_std::{testing::{message_info, MockApi, MockStorage}, Addr, CosmosMsg, Empty, Env, OwnedDeps, Timestamp, WasmMsg};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, publish_workflow, with_oracle_prices, OracleQuerier};
+
+type TestDeps = OwnedDeps<MockStorage, MockApi, OracleQuerier, Empty>;
+
+// Swaps 1 RUNE (8 decimals) for BTC through the given venue, the executor sends its own min_return
+fn create_swap_workflow(api: MockApi, message: &str, protection: MinReturnProtection) -> NewWorkflowMsg {
+    let mut workflow = create_simple_test_workflow(api);
+    let action = workflow.actions.get_mut("stake_tokens").unwrap();
+    action.params = HashMap::new();
+    action.min_return_protection = Some(protection);
+    let template = action.templates.get_mut("default").unwrap();
+    template.message = message.to_string();
+    template.funds = vec![("100000000".to_string(), "rune".to_string())];
+    workflow
+}
+
+fn protection(venue: SwapVenue, max_slippage_bps: u16) -> MinReturnProtection {
+    MinReturnProtection {
+        venue,
+        offer_symbol: "RUNE".to_string(),
+        ask_symbol: "BTC".to_string(),
+        ask_denom: "btc-btc".to_string(),
+        max_slippage_bps,
+    }
+}
+
+fn setup(message: &str, protection: MinReturnProtection, prices: &[(&str, &str)]) -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_swap_workflow(api, message, protection)).unwrap();
+    execute(deps.as_mut(), env.clone(), message_info(&user_address, &[]), ExecuteMsg::ExecuteInstance {
+        instance: create_oneshot_test_instance("simple-test-workflow".to_string()),
+    }).unwrap();
+
+    (with_oracle_prices(deps, prices), env, executor_address, user_address)
+}
+
+fn simulate(deps: &TestDeps, env: Env, user_address: &Addr) -> auto_workflow_manager::msg::SimulateActionResponse {
+    query_simulate_action(deps.as_ref(), env, user_address.to_string(), 1, "stake_tokens".to_string(), "default".to_string(), None).unwrap()
+}
+
+fn rendered_message(deps: &TestDeps, env: Env, user_address: &Addr) -> String {
+    let simulation = simulate(deps, env, user_address);
+    assert_eq!(simulation.error, None);
+    match &simulation.messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => String::from_utf8(msg.to_vec()).unwrap(),
+        _ => panic!("Expected WasmMsg::Execute"),
+    }
+}
+
+#[test]
+fn test_min_return_is_set_from_oracle_prices() {
+    let prices = [("RUNE", "5"), ("BTC", "100000")];
+
+    // 1 RUNE = 0.00005 BTC, minus 1% slippage
+    let (deps, env, _executor_address, user_address) = setup("{\"swap\":{\"min_return\":\"1\",\"to\":null}}", protection(SwapVenue::Fin, 100), &prices);
+    assert_eq!(rendered_message(&deps, env, &user_address), "{\"swap\":{\"min_return\":\"4950\",\"to\":null}}");
+
+    for venue in [SwapVenue::Bow, SwapVenue::ThorchainSwap] {
+        let (deps, env, _executor_address, user_address) = setup("{\"swap\":{\"min_return\":{\"denom\":\"btc-btc\",\"amount\":\"1\"}}}", protection(venue, 100), &prices);
+        assert_eq!(rendered_message(&deps, env, &user_address), "{\"swap\":{\"min_return\":{\"amount\":\"4950\",\"denom\":\"btc-btc\"}}}");
+    }
+}
+
+#[test]
+fn test_action_fails_without_oracle_price() {
+    let (mut deps, env, executor_address, user_address) = setup("{\"swap\":{\"min_return\":\"1\"}}", protection(SwapVenue::Fin, 100), &[("RUNE", "5")]);
+
+    let simulation = simulate(&deps, env.clone(), &user_address);
+    assert_eq!(simulation.error.unwrap().stage, SimulationStage::Template);
+
+    let result = execute(deps.as_mut(), env, message_info(&executor_address, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    });
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
+
+#[test]
+fn test_protection_requires_a_swap() {
+    let (deps, env, _executor_address, user_address) = setup("{\"stake\":{}}", protection(SwapVenue::Fin, 100), &[("RUNE", "5"), ("BTC", "100000")]);
+    assert_eq!(simulate(&deps, env, &user_address).error.unwrap().stage, SimulationStage::Template);
+
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, create_swap_workflow(api, "{\"swap\":{}}", protection(SwapVenue::Fin, 10001)));
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
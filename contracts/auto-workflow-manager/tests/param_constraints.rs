@@ -0,0 +1,183 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ActionMsg, ActionParamValue, ExecuteMsg, ExecutionType, NewInstanceMsg, NewWorkflowMsg, ParamBound, ParamConstraint, Template, WorkflowVisibility},
+};
+use cosmwasm_std::{Addr, Decimal256, Env, Timestamp};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+mod utils;
+use utils::{create_test_environment, execute_instance, publish_workflow};
+
+fn create_bounded_swap_workflow(contract: &Addr, amount_regex: Option<String>) -> NewWorkflowMsg {
+    NewWorkflowMsg {
+        id: "bounded-swap".to_string(),
+        start_actions: HashSet::from(["swap".to_string()]),
+        end_actions: HashSet::from(["swap".to_string()]),
+        visibility: WorkflowVisibility::Public,
+        actions: HashMap::from([(
+            "swap".to_string(),
+            ActionMsg {
+                params: HashMap::from([
+                    ("amount".to_string(), ActionParamValue::String("#cp.amount".to_string())),
+                    ("denom".to_string(), ActionParamValue::String("#cp.denom".to_string())),
+                ]),
+                next_actions: HashSet::new(),
+                templates: HashMap::from([(
+                    "default".to_string(),
+                    Template {
+                        contract: contract.to_string(),
+                        message: "{\"swap\":{\"amount\":\"{{amount}}\",\"denom\":\"{{denom}}\"}}".to_string(),
+                        funds: vec![],
+                    },
+                )]),
+                whitelisted_contracts: HashSet::from([contract.to_string()]),
+                allowed_messages: HashMap::new(),
+                outputs: HashMap::new(),
+                signed_params: HashSet::new(),
+                param_constraints: HashMap::from([
+                    (
+                        "denom".to_string(),
+                        ParamConstraint {
+                            allowed_values: Some(HashSet::from(["rune".to_string(), "tcy".to_string()])),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        "amount".to_string(),
+                        ParamConstraint {
+                            min: Some(ParamBound::Value(Decimal256::one())),
+                            regex: amount_regex,
+                            ..Default::default()
+                        },
+                    ),
+                ]),
+                sub_workflow: None,
+                min_return_protection: None,
+                timing: None,
+            },
+        )]),
+        creator_fee_schedule: None,
+    }
+}
+
+// Instance caps the amount at half of its max_amount param
+fn create_bounded_instance() -> NewInstanceMsg {
+    NewInstanceMsg {
+        workflow_id: "bounded-swap".to_string(),
+        onchain_parameters: HashMap::from([(
+            "max_amount".to_string(),
+            ActionParamValue::BigInt("1000".to_string()),
+        )]),
+        offchain_parameters: HashMap::new(),
+        execution_type: ExecutionType::Recurrent,
+        expiration_time: Timestamp::from_seconds(1000000000),
+        cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::from([(
+            "amount".to_string(),
+            ParamConstraint {
+                max: Some(ParamBound::InstanceParam {
+                    param: "max_amount".to_string(),
+                    ratio: Decimal256::from_str("0.5").unwrap(),
+                }),
+                ..Default::default()
+            },
+        )]),
+        price_trigger: None,
+        allow_bonded_executors: false,
+    }
+}
+
+fn execute_swap(
+    deps: &mut cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>,
+    env: Env,
+    executor: &Addr,
+    user_address: &Addr,
+    amount: &str,
+    denom: &str,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "swap".to_string(),
+        template_id: "default".to_string(),
+        params: Some(HashMap::from([
+            ("amount".to_string(), ActionParamValue::String(amount.to_string())),
+            ("denom".to_string(), ActionParamValue::String(denom.to_string())),
+        ])),
+        params_signature: None,
+        execution_id: None,
+    };
+    let execute_info = cosmwasm_std::testing::message_info(executor, &[]);
+    execute(deps.as_mut(), env, execute_info, execute_msg)
+}
+
+fn assert_violation(result: Result<cosmwasm_std::Response, ContractError>, expected_param: &str) {
+    match result {
+        Err(ContractError::ParamConstraintViolation { param, .. }) => assert_eq!(param, expected_param),
+        _ => panic!("Expected ParamConstraintViolation for {}", expected_param),
+    }
+}
+
+fn setup(amount_regex: Option<String>) -> (cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_bounded_swap_workflow(&contract, amount_regex)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_bounded_instance()).unwrap();
+    (deps, env, executor_address, user_address)
+}
+
+#[test]
+fn test_params_within_constraints_are_accepted() {
+    let (mut deps, env, executor_address, user_address) = setup(None);
+    execute_swap(&mut deps, env, &executor_address, &user_address, "500", "rune").unwrap();
+}
+
+#[test]
+fn test_param_not_in_allowed_values_is_rejected() {
+    let (mut deps, env, executor_address, user_address) = setup(None);
+    let result = execute_swap(&mut deps, env, &executor_address, &user_address, "100", "btc");
+    assert_violation(result, "denom");
+}
+
+#[test]
+fn test_param_outside_numeric_bounds_is_rejected() {
+    let (mut deps, env, executor_address, user_address) = setup(None);
+
+    // Below the workflow minimum
+    let result = execute_swap(&mut deps, env.clone(), &executor_address, &user_address, "0", "rune");
+    assert_violation(result, "amount");
+
+    // Above the instance maximum (half of max_amount)
+    let result = execute_swap(&mut deps, env.clone(), &executor_address, &user_address, "501", "rune");
+    assert_violation(result, "amount");
+
+    // Not a number
+    let result = execute_swap(&mut deps, env, &executor_address, &user_address, "all", "rune");
+    assert_violation(result, "amount");
+}
+
+#[test]
+fn test_param_not_matching_regex_is_rejected() {
+    let (mut deps, env, executor_address, user_address) = setup(Some("[0-9]+".to_string()));
+
+    // Only a prefix matches, the whole value must match
+    let result = execute_swap(&mut deps, env.clone(), &executor_address, &user_address, "100.5", "rune");
+    assert_violation(result, "amount");
+
+    execute_swap(&mut deps, env, &executor_address, &user_address, "100", "rune").unwrap();
+}
+
+#[test]
+fn test_publish_workflow_with_invalid_regex_fails() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let contract = api.addr_make("swap_contract");
+
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, create_bounded_swap_workflow(&contract, Some("[0-9".to_string())));
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
@@ -0,0 +1,108 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ActionParamValue, ExecuteMsg, NewWorkflowMsg},
+    query::query_simulate_action,
+};
+use cosmwasm_std::{coin, testing::{message_info, MockApi}, Addr, CosmosMsg, Decimal, Env, Timestamp, Uint128, WasmMsg};
+use std::{collections::HashMap, str::FromStr};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+// The simple workflow with its stake_tokens action rendering the given params
+fn create_typed_workflow(api: MockApi, message: &str, params: Vec<(&str, ActionParamValue)>) -> NewWorkflowMsg {
+    let mut workflow = create_simple_test_workflow(api);
+    let action = workflow.actions.get_mut("stake_tokens").unwrap();
+    action.params = params.into_iter().map(|(param_id, value)| (param_id.to_string(), value)).collect();
+    action.templates.get_mut("default").unwrap().message = message.to_string();
+    workflow
+}
+
+fn setup(message: &str, params: Vec<(&str, ActionParamValue)>, instance_params: Vec<(&str, ActionParamValue)>) -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_typed_workflow(api, message, params)).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.onchain_parameters = instance_params.into_iter().map(|(param_id, value)| (param_id.to_string(), value)).collect();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    (deps, env, executor_address, user_address)
+}
+
+fn rendered_message(deps: &TestDeps, env: Env, user_address: &Addr) -> String {
+    let simulation = query_simulate_action(deps.as_ref(), env, user_address.to_string(), 1, "stake_tokens".to_string(), "default".to_string(), None).unwrap();
+    assert_eq!(simulation.error, None);
+    match &simulation.messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => String::from_utf8(msg.to_vec()).unwrap(),
+        _ => panic!("Expected WasmMsg::Execute"),
+    }
+}
+
+#[test]
+fn test_typed_params_render_by_type() {
+    let recipient = MockApi::default().addr_make("recipient");
+    let (deps, env, _executor_address, user_address) = setup(
+        "{\"amount\":\"{{amount}}\",\"ratio\":\"{{ratio}}\",\"auto\":{{auto}},\"fee\":{{fee}},\"denom\":\"{{denom}}\",\"to\":\"{{to}}\",\"hook\":{{hook}}}",
+        vec![
+            ("amount", ActionParamValue::Uint128(Uint128::new(1000))),
+            ("ratio", ActionParamValue::Decimal(Decimal::from_str("0.25").unwrap())),
+            ("auto", ActionParamValue::Bool(true)),
+            ("fee", ActionParamValue::Coin(coin(5, "uusdc"))),
+            ("denom", ActionParamValue::Denom("x/ruji".to_string())),
+            ("to", ActionParamValue::String("#ip.to".to_string())),
+            ("hook", ActionParamValue::Json("{\"notify\":[1,2]}".to_string())),
+        ],
+        vec![("to", ActionParamValue::Address(recipient.to_string()))],
+    );
+
+    assert_eq!(
+        rendered_message(&deps, env, &user_address),
+        format!("{{\"amount\":\"1000\",\"ratio\":\"0.25\",\"auto\":true,\"fee\":{{\"denom\":\"uusdc\",\"amount\":\"5\"}},\"denom\":\"x/ruji\",\"to\":\"{}\",\"hook\":{{\"notify\":[1,2]}}}}", recipient),
+    );
+}
+
+#[test]
+fn test_referenced_big_int_must_be_a_number() {
+    let (mut deps, env, executor_address, user_address) = setup(
+        "{\"stake\":{\"amount\":\"{{amount}}\"}}",
+        vec![("amount", ActionParamValue::BigInt("#ip.amount".to_string()))],
+        vec![("amount", ActionParamValue::String("1e6".to_string()))],
+    );
+
+    let result = execute(deps.as_mut(), env, message_info(&executor_address, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    });
+    assert!(matches!(result, Err(ContractError::InvalidParamValue { .. })));
+}
+
+#[test]
+fn test_invalid_values_are_rejected_when_received() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+
+    for value in [
+        ActionParamValue::BigInt("12a".to_string()),
+        ActionParamValue::Denom("1rune".to_string()),
+        ActionParamValue::Json("{not json".to_string()),
+    ] {
+        let result = publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), create_typed_workflow(api, "{}", vec![("amount", value)]));
+        assert!(matches!(result, Err(ContractError::InvalidParamValue { .. })));
+    }
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.onchain_parameters = HashMap::from([("to".to_string(), ActionParamValue::Address("not-an-address".to_string()))]);
+    let result = execute_instance(&mut deps, env, user_address, instance);
+    assert!(matches!(result, Err(ContractError::InvalidParamValue { .. })));
+}
@@ -0,0 +1,134 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ExecuteMsg, PauseScope},
+    query::query_pauses,
+};
+use cosmwasm_std::{testing::message_info, Addr, Env, Timestamp};
+use std::collections::HashMap;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup() -> (TestDeps, Env, Addr, Addr, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let guardian_address = api.addr_make("guardian");
+    env.block.time = Timestamp::from_seconds(100);
+
+    execute_as(&mut deps, env.clone(), &admin_address, ExecuteMsg::GrantRole {
+        role: "guardian".to_string(),
+        address: guardian_address.to_string(),
+    }).unwrap();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    (deps, env, admin_address, guardian_address, executor_address, user_address, api.addr_make("contract_to_call"))
+}
+
+fn execute_as(deps: &mut TestDeps, env: Env, sender: &Addr, execute_msg: ExecuteMsg) -> Result<cosmwasm_std::Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(sender, &[]), execute_msg)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<cosmwasm_std::Response, ContractError> {
+    execute_as(deps, env, executor, ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    })
+}
+
+fn pause_msg(scope: PauseScope, block_instances: bool, block_fees: bool) -> ExecuteMsg {
+    ExecuteMsg::PauseExecutions {
+        scope,
+        reason: "target exploited".to_string(),
+        block_instances,
+        block_fees,
+    }
+}
+
+#[test]
+fn test_guardian_pauses_and_only_owner_unpauses() {
+    let (mut deps, env, admin_address, guardian_address, executor_address, user_address, _contract_address) = setup();
+
+    let result = execute_as(&mut deps, env.clone(), &user_address, pause_msg(PauseScope::Global, false, false));
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    let response = execute_as(&mut deps, env.clone(), &guardian_address, pause_msg(PauseScope::Global, false, false)).unwrap();
+    assert_eq!(response.events[0].ty, "autorujira-workflow-manager/pause_executions");
+
+    let pauses = query_pauses(deps.as_ref()).unwrap().pauses;
+    assert_eq!(pauses.len(), 1);
+    assert_eq!(pauses[0].scope, PauseScope::Global);
+    assert_eq!(pauses[0].pause.paused_by, guardian_address);
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::ExecutionsPaused { .. })));
+
+    // New instances and self-service actions aren't blocked by default
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    execute_as(&mut deps, env.clone(), &user_address, ExecuteMsg::CancelInstance { instance_id: 2 }).unwrap();
+
+    let unpause_msg = ExecuteMsg::UnpauseExecutions { scope: PauseScope::Global };
+    let result = execute_as(&mut deps, env.clone(), &guardian_address, unpause_msg.clone());
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    execute_as(&mut deps, env.clone(), &admin_address, unpause_msg).unwrap();
+    assert!(query_pauses(deps.as_ref()).unwrap().pauses.is_empty());
+    execute_stake_action(&mut deps, env, &executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_workflow_pause_blocks_instances_when_requested() {
+    let (mut deps, env, _admin_address, guardian_address, executor_address, user_address, _contract_address) = setup();
+    let scope = PauseScope::Workflow { workflow_id: "simple-test-workflow".to_string() };
+
+    // Fees can only be blocked globally
+    let result = execute_as(&mut deps, env.clone(), &guardian_address, pause_msg(scope.clone(), true, true));
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    execute_as(&mut deps, env.clone(), &guardian_address, pause_msg(scope, true, false)).unwrap();
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::ExecutionsPaused { .. })));
+
+    let result = execute_instance(&mut deps, env, user_address, create_oneshot_test_instance("simple-test-workflow".to_string()));
+    assert!(matches!(result, Err(ContractError::ExecutionsPaused { .. })));
+}
+
+#[test]
+fn test_contract_pause_blocks_actions_targeting_it() {
+    let (mut deps, env, admin_address, guardian_address, executor_address, user_address, contract_address) = setup();
+    let scope = PauseScope::Contract { contract: contract_address.to_string() };
+
+    execute_as(&mut deps, env.clone(), &guardian_address, pause_msg(scope.clone(), false, false)).unwrap();
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::ExecutionsPaused { .. })));
+
+    execute_as(&mut deps, env.clone(), &admin_address, ExecuteMsg::UnpauseExecutions { scope }).unwrap();
+    execute_stake_action(&mut deps, env, &executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_global_pause_blocks_fees_when_requested() {
+    let (mut deps, env, admin_address, guardian_address, _executor_address, _user_address, _contract_address) = setup();
+    let charge_fees_msg = ExecuteMsg::ChargeFees {
+        batch_id: "batch-1".to_string(),
+        prices: HashMap::new(),
+        fees: vec![],
+    };
+
+    execute_as(&mut deps, env.clone(), &guardian_address, pause_msg(PauseScope::Global, false, false)).unwrap();
+    execute_as(&mut deps, env.clone(), &admin_address, charge_fees_msg.clone()).unwrap();
+
+    execute_as(&mut deps, env.clone(), &guardian_address, pause_msg(PauseScope::Global, false, true)).unwrap();
+    let result = execute_as(&mut deps, env, &admin_address, charge_fees_msg);
+    assert!(matches!(result, Err(ContractError::ExecutionsPaused { .. })));
+}
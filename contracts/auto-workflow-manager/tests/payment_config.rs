@@ -313,12 +313,14 @@ fn test_charge_fees_events() {
                     debit_denom: "uusdc".to_string(),
                     amount: Uint128::new(100000000),
                     fee_type: FeeType::Execution,
+                    executor: None,
                 },
                 FeeTotal {
                     denom: "AUTO".to_string(),
                     debit_denom: "AUTO".to_string(),
                     amount: Uint128::new(10000000000),
                     fee_type: FeeType::Creator { instance_id: 1 },
+                    executor: None,
                 },
             ],
         },
@@ -329,6 +331,7 @@ fn test_charge_fees_events() {
                 debit_denom: "TCY".to_string(),
                 amount: Uint128::new(1000000000000),
                 fee_type: FeeType::Execution,
+                executor: None,
             }],
         },
     ];
@@ -372,6 +375,12 @@ fn test_handle_fee_manager_reply() {
         debit_amount: Uint128::new(1000),
         creator_address: Some("thor1test".to_string()),
         fee_type: FeeType::Execution,
+        executor_address: None,
+        workflow_id: None,
+        batch_id: "test-batch".to_string(),
+        executor_accrued_usd: Uint128::zero(),
+        creator_fee_usd: Uint128::zero(),
+        execution_fee_usd: Uint128::zero(),
     };
     
     // Store the data in the temporary storage
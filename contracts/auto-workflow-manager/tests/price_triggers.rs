@@ -0,0 +1,85 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ExecuteMsg, ExecutionType, NewInstanceMsg, PriceCondition, PriceSource, PriceTrigger, SimulationStage},
+    query::query_simulate_action,
+};
+use cosmwasm_std::{testing::{message_info, MockApi, MockStorage}, Addr, Decimal, Empty, Env, OwnedDeps, Response, Timestamp};
+use std::str::FromStr;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, publish_workflow, with_oracle_prices, OracleQuerier};
+
+type TestDeps = OwnedDeps<MockStorage, MockApi, OracleQuerier, Empty>;
+
+fn price_triggered_instance(source: PriceSource, condition: PriceCondition, price: &str) -> NewInstanceMsg {
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    instance.price_trigger = Some(PriceTrigger {
+        source,
+        condition,
+        price: Decimal::from_str(price).unwrap(),
+    });
+    instance
+}
+
+fn execute_instance(deps: &mut TestDeps, env: Env, user_address: &Addr, instance: NewInstanceMsg) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(user_address, &[]), ExecuteMsg::ExecuteInstance { instance })
+}
+
+fn execute_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(executor, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    })
+}
+
+// Buys RUNE when its oracle price drops to 4 or less
+fn setup() -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+
+    let mut deps = with_oracle_prices(deps, &[("RUNE", "5")]);
+    let instance = price_triggered_instance(PriceSource::Oracle { symbol: "RUNE".to_string() }, PriceCondition::Below, "4");
+    execute_instance(&mut deps, env.clone(), &user_address, instance).unwrap();
+    (deps, env, executor_address, user_address)
+}
+
+#[test]
+fn test_start_action_waits_for_price_trigger() {
+    let (mut deps, env, executor_address, user_address) = setup();
+
+    let result = execute_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::PriceTriggerNotMet { .. })));
+    let simulation = query_simulate_action(deps.as_ref(), env.clone(), user_address.to_string(), 1, "stake_tokens".to_string(), "default".to_string(), None).unwrap();
+    assert_eq!(simulation.error.unwrap().stage, SimulationStage::Instance);
+
+    deps.querier.prices.insert("RUNE".to_string(), "3.5".to_string());
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+
+    // Every new run of a recurrent instance checks the trigger again
+    deps.querier.prices.insert("RUNE".to_string(), "4.01".to_string());
+    let result = execute_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::PriceTriggerNotMet { .. })));
+
+    // Without a price the trigger can't be checked
+    deps.querier.prices.clear();
+    let result = execute_action(&mut deps, env, &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
+
+#[test]
+fn test_pool_trigger_asset_is_validated() {
+    let (mut deps, env, _executor_address, user_address) = setup();
+
+    let instance = price_triggered_instance(PriceSource::Pool { asset: "BTC".to_string() }, PriceCondition::Above, "100000");
+    let result = execute_instance(&mut deps, env, &user_address, instance);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
@@ -17,6 +17,10 @@ fn create_oneshot_test_instance(workflow_id: String) -> NewInstanceMsg {
         execution_type: ExecutionType::OneShot,
         expiration_time: Timestamp::from_seconds(1000000000), // Far future
         cron_expression: None,
+        param_signer: None,
+        param_constraints: std::collections::HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     }
 }
 
@@ -28,6 +32,10 @@ fn create_recurrent_test_instance(workflow_id: String) -> NewInstanceMsg {
         execution_type: ExecutionType::Recurrent,
         expiration_time: Timestamp::from_seconds(1000000000), // Far future
         cron_expression: None,
+        param_signer: None,
+        param_constraints: std::collections::HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     }
 }
 
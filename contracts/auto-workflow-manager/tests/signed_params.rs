@@ -0,0 +1,284 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ActionMsg, ActionParamValue, ExecuteMsg, ExecutionType, NewInstanceMsg, NewWorkflowMsg, ParamSigner, ParamsSignature, SignatureAlgorithm, SignedParamsPayload, Template, WorkflowVisibility},
+};
+use cosmwasm_std::{to_json_vec, Addr, Binary, Env, Timestamp};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+mod utils;
+use utils::{create_test_environment, execute_instance, publish_workflow};
+
+fn create_signed_swap_workflow(contract: &Addr) -> NewWorkflowMsg {
+    NewWorkflowMsg {
+        id: "signed-swap".to_string(),
+        start_actions: HashSet::from(["swap".to_string()]),
+        end_actions: HashSet::from(["swap".to_string()]),
+        visibility: WorkflowVisibility::Public,
+        actions: HashMap::from([(
+            "swap".to_string(),
+            ActionMsg {
+                params: HashMap::from([(
+                    "amount".to_string(),
+                    ActionParamValue::String("#cp.amount".to_string()),
+                )]),
+                next_actions: HashSet::new(),
+                templates: HashMap::from([
+                    (
+                        "default".to_string(),
+                        Template {
+                            contract: contract.to_string(),
+                            message: "{\"swap\":{\"amount\":\"{{amount}}\"}}".to_string(),
+                            funds: vec![],
+                        },
+                    ),
+                    (
+                        "limit".to_string(),
+                        Template {
+                            contract: contract.to_string(),
+                            message: "{\"limit\":{\"price\":\"{{amount}}\"}}".to_string(),
+                            funds: vec![],
+                        },
+                    ),
+                ]),
+                whitelisted_contracts: HashSet::from([contract.to_string()]),
+                allowed_messages: HashMap::new(),
+                outputs: HashMap::new(),
+                signed_params: HashSet::from(["amount".to_string()]),
+                param_constraints: HashMap::new(),
+                sub_workflow: None,
+                min_return_protection: None,
+                timing: None,
+            },
+        )]),
+        creator_fee_schedule: None,
+    }
+}
+
+fn create_instance(param_signer: Option<ParamSigner>) -> NewInstanceMsg {
+    NewInstanceMsg {
+        workflow_id: "signed-swap".to_string(),
+        onchain_parameters: HashMap::new(),
+        offchain_parameters: HashMap::new(),
+        execution_type: ExecutionType::Recurrent,
+        expiration_time: Timestamp::from_seconds(1000000000),
+        cron_expression: None,
+        param_signer,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
+    }
+}
+
+fn secp256k1_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32].into()).unwrap()
+}
+
+fn secp256k1_signer(key: &SigningKey) -> ParamSigner {
+    ParamSigner {
+        public_key: Binary::from(key.verifying_key().to_encoded_point(true).as_bytes()),
+        algorithm: SignatureAlgorithm::Secp256k1,
+        required_params: HashSet::new(),
+    }
+}
+
+fn amount_params(amount: &str) -> HashMap<String, ActionParamValue> {
+    HashMap::from([("amount".to_string(), ActionParamValue::String(amount.to_string()))])
+}
+
+fn payload_digest(env: &Env, user_address: &Addr, params: &HashMap<String, ActionParamValue>, nonce: u64, expires_at: Timestamp) -> Vec<u8> {
+    let payload = SignedParamsPayload {
+        contract_address: env.contract.address.to_string(),
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "swap".to_string(),
+        template_id: "default".to_string(),
+        params: params.clone().into_iter().collect(),
+        nonce,
+        expires_at,
+    };
+    Sha256::digest(to_json_vec(&payload).unwrap()).to_vec()
+}
+
+fn secp256k1_sign(key: &SigningKey, env: &Env, user_address: &Addr, params: &HashMap<String, ActionParamValue>, nonce: u64, expires_at: Timestamp) -> ParamsSignature {
+    let signature: Signature = key.sign_prehash(&payload_digest(env, user_address, params, nonce, expires_at)).unwrap();
+    ParamsSignature {
+        signature: Binary::from(signature.to_bytes().as_slice()),
+        nonce,
+        expires_at,
+    }
+}
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn execute_swap(
+    deps: &mut TestDeps,
+    env: Env,
+    executor: &Addr,
+    user_address: &Addr,
+    params: HashMap<String, ActionParamValue>,
+    params_signature: Option<ParamsSignature>,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    execute_swap_template(deps, env, executor, user_address, "default", params, params_signature)
+}
+
+fn execute_swap_template(
+    deps: &mut TestDeps,
+    env: Env,
+    executor: &Addr,
+    user_address: &Addr,
+    template_id: &str,
+    params: HashMap<String, ActionParamValue>,
+    params_signature: Option<ParamsSignature>,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "swap".to_string(),
+        template_id: template_id.to_string(),
+        params: Some(params),
+        params_signature,
+        execution_id: None,
+    };
+    let execute_info = cosmwasm_std::testing::message_info(executor, &[]);
+    execute(deps.as_mut(), env, execute_info, execute_msg)
+}
+
+fn assert_invalid_signature(result: Result<cosmwasm_std::Response, ContractError>, expected_reason: &str) {
+    match result {
+        Err(ContractError::InvalidParamsSignature { reason }) => assert_eq!(reason, expected_reason),
+        _ => panic!("Expected InvalidParamsSignature error"),
+    }
+}
+
+#[test]
+fn test_instance_without_signer_rejected_for_signed_workflow() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    let result = execute_instance(&mut deps, env, user_address, create_instance(None));
+    match result {
+        Err(ContractError::GenericError(message)) => {
+            assert_eq!(message, "Workflow requires signed parameters but no param signer was provided");
+        }
+        _ => panic!("Expected GenericError for missing param signer"),
+    }
+}
+
+#[test]
+fn test_secp256k1_signed_params_are_accepted_once() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    let key = secp256k1_key();
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(Some(secp256k1_signer(&key)))).unwrap();
+
+    let params = amount_params("1000");
+    let signature = secp256k1_sign(&key, &env, &user_address, &params, 1, Timestamp::from_seconds(200));
+    execute_swap(&mut deps, env.clone(), &executor_address, &user_address, params.clone(), Some(signature.clone())).unwrap();
+
+    // Replaying the same signature is rejected
+    let result = execute_swap(&mut deps, env, &executor_address, &user_address, params, Some(signature));
+    assert_invalid_signature(result, "Nonce 1 already used");
+}
+
+#[test]
+fn test_signed_params_require_signature() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    let key = secp256k1_key();
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(Some(secp256k1_signer(&key)))).unwrap();
+
+    let result = execute_swap(&mut deps, env, &executor_address, &user_address, amount_params("1000"), None);
+    assert_invalid_signature(result, "Signature required for execute action parameters");
+}
+
+#[test]
+fn test_signed_params_reject_tampered_params() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    let key = secp256k1_key();
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(Some(secp256k1_signer(&key)))).unwrap();
+
+    let signature = secp256k1_sign(&key, &env, &user_address, &amount_params("1000"), 1, Timestamp::from_seconds(200));
+    let result = execute_swap(&mut deps, env, &executor_address, &user_address, amount_params("999999"), Some(signature));
+    assert_invalid_signature(result, "Signature does not match");
+}
+
+#[test]
+fn test_signed_params_are_bound_to_their_template() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    let key = secp256k1_key();
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(Some(secp256k1_signer(&key)))).unwrap();
+
+    // Signed for the default template
+    let params = amount_params("1000");
+    let signature = secp256k1_sign(&key, &env, &user_address, &params, 1, Timestamp::from_seconds(200));
+    let result = execute_swap_template(&mut deps, env.clone(), &executor_address, &user_address, "limit", params.clone(), Some(signature.clone()));
+    assert_invalid_signature(result, "Signature does not match");
+
+    execute_swap(&mut deps, env, &executor_address, &user_address, params, Some(signature)).unwrap();
+}
+
+#[test]
+fn test_signed_params_reject_expired_signature() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    let key = secp256k1_key();
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(Some(secp256k1_signer(&key)))).unwrap();
+
+    let params = amount_params("1000");
+    let signature = secp256k1_sign(&key, &env, &user_address, &params, 1, Timestamp::from_seconds(100));
+    let result = execute_swap(&mut deps, env, &executor_address, &user_address, params, Some(signature));
+    assert_invalid_signature(result, "Signature has expired");
+}
+
+#[test]
+fn test_ed25519_signed_params_are_accepted() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract = api.addr_make("swap_contract");
+    let key = ed25519_zebra::SigningKey::from([9u8; 32]);
+    env.block.time = Timestamp::from_seconds(100);
+
+    let signer = ParamSigner {
+        public_key: Binary::from(ed25519_zebra::VerificationKeyBytes::from(&key).as_ref()),
+        algorithm: SignatureAlgorithm::Ed25519,
+        required_params: HashSet::new(),
+    };
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_signed_swap_workflow(&contract)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_instance(Some(signer))).unwrap();
+
+    let params = amount_params("1000");
+    let signature = key.sign(&payload_digest(&env, &user_address, &params, 5, Timestamp::from_seconds(200)));
+    let params_signature = ParamsSignature {
+        signature: Binary::from(<[u8; 64]>::from(signature).as_slice()),
+        nonce: 5,
+        expires_at: Timestamp::from_seconds(200),
+    };
+    execute_swap(&mut deps, env, &executor_address, &user_address, params, Some(params_signature)).unwrap();
+}
@@ -0,0 +1,72 @@
+use auto_workflow_manager::{
+    contract::execute,
+    msg::{ExecuteMsg, SimulateActionResponse, SimulationStage},
+    query::{query_simulate_action, query_workflow_instance},
+};
+use cosmwasm_std::{testing::message_info, Addr, CosmosMsg, Env, Timestamp, WasmMsg};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup() -> (TestDeps, Env, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    (deps, env, executor_address, user_address, api.addr_make("contract_to_call"))
+}
+
+fn simulate(deps: &TestDeps, env: Env, user_address: &Addr, action_id: &str, template_id: &str) -> SimulateActionResponse {
+    query_simulate_action(deps.as_ref(), env, user_address.to_string(), 1, action_id.to_string(), template_id.to_string(), None).unwrap()
+}
+
+#[test]
+fn test_simulate_action_renders_messages_without_changing_state() {
+    let (deps, env, _executor_address, user_address, contract_address) = setup();
+
+    let response = simulate(&deps, env, &user_address, "stake_tokens", "default");
+    assert_eq!(response.error, None);
+    assert_eq!(response.messages.len(), 1);
+    match &response.messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+            assert_eq!(contract_addr, &contract_address.to_string());
+            assert_eq!(msg.as_slice(), b"{\"stake\":{ \"amount\": 1000000 }}");
+            assert!(funds.is_empty());
+        }
+        _ => panic!("Expected WasmMsg::Execute"),
+    }
+
+    let instance = query_workflow_instance(deps.as_ref(), user_address.to_string(), 1).unwrap().instance;
+    assert_eq!(instance.last_executed_action, None);
+}
+
+#[test]
+fn test_simulate_action_reports_failing_stage() {
+    let (mut deps, env, executor_address, user_address, _contract_address) = setup();
+
+    let response = simulate(&deps, env.clone(), &user_address, "unknown_action", "default");
+    assert!(response.messages.is_empty());
+    assert_eq!(response.error.unwrap().stage, SimulationStage::Instance);
+
+    let response = simulate(&deps, env.clone(), &user_address, "stake_tokens", "unknown_template");
+    assert!(response.messages.is_empty());
+    assert_eq!(response.error.unwrap().stage, SimulationStage::Template);
+
+    // A one-shot instance can't run its end action twice
+    execute(deps.as_mut(), env.clone(), message_info(&executor_address, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    }).unwrap();
+    let response = simulate(&deps, env, &user_address, "stake_tokens", "default");
+    assert_eq!(response.error.unwrap().stage, SimulationStage::Instance);
+}
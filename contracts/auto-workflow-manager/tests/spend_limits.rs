@@ -0,0 +1,147 @@
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    error::ContractError,
+    execute::ACTION_REPLY_ID_START,
+    msg::{ExecuteMsg, ExecutionType, SpendLimit},
+    query::query_instance_spend_limits,
+};
+use cosmwasm_std::{testing::message_info, Addr, Binary, Env, Reply, SubMsgResult, Timestamp, Uint128};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+// A recurrent instance whose action sends 400rune every time it runs
+fn setup() -> (TestDeps, Env, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    let mut workflow = create_simple_test_workflow(api);
+    let action = workflow.actions.get_mut("stake_tokens").unwrap();
+    action.templates.get_mut("default").unwrap().funds = vec![("400".to_string(), "rune".to_string())];
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, workflow).unwrap();
+
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    (deps, env, executor_address, user_address)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<cosmwasm_std::Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(executor, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    })
+}
+
+fn set_spend_limits(deps: &mut TestDeps, env: Env, user_address: &Addr, limits: Vec<SpendLimit>) -> Result<cosmwasm_std::Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(user_address, &[]), ExecuteMsg::SetInstanceSpendLimits { instance_id: 1, limits })
+}
+
+#[test]
+fn test_rolling_spend_limit() {
+    let (mut deps, mut env, executor_address, user_address) = setup();
+
+    set_spend_limits(&mut deps, env.clone(), &user_address, vec![SpendLimit {
+        denom: "rune".to_string(),
+        amount: Uint128::from(1000u128),
+        period: Some(60),
+    }]).unwrap();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+    env.block.time = Timestamp::from_seconds(130);
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+
+    let limits = query_instance_spend_limits(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap().limits;
+    assert_eq!(limits[0].spent, Uint128::from(800u128));
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::SpendLimitExceeded { .. })));
+
+    // The first spend leaves the window
+    env.block.time = Timestamp::from_seconds(160);
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+
+    let limits = query_instance_spend_limits(deps.as_ref(), env, user_address.to_string(), 1).unwrap().limits;
+    assert_eq!(limits[0].spent, Uint128::from(800u128));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_failed_action_gives_back_its_spends() {
+    let (mut deps, env, executor_address, user_address) = setup();
+
+    set_spend_limits(&mut deps, env.clone(), &user_address, vec![SpendLimit {
+        denom: "rune".to_string(),
+        amount: Uint128::from(500u128),
+        period: None,
+    }]).unwrap();
+
+    // The target contract rejects the message, no funds moved
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+    reply(deps.as_mut(), env.clone(), Reply {
+        id: ACTION_REPLY_ID_START,
+        result: SubMsgResult::Err("contract error".to_string()),
+        gas_used: 0,
+        payload: Binary::default(),
+    }).unwrap();
+
+    let limits = query_instance_spend_limits(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap().limits;
+    assert_eq!(limits[0].spent, Uint128::zero());
+    execute_stake_action(&mut deps, env, &executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_instance_spend_limit_without_period() {
+    let (mut deps, mut env, executor_address, user_address) = setup();
+
+    set_spend_limits(&mut deps, env.clone(), &user_address, vec![
+        SpendLimit {
+            denom: "rune".to_string(),
+            amount: Uint128::from(800u128),
+            period: None,
+        },
+        // Other denoms don't count against the rune limit
+        SpendLimit {
+            denom: "uusdc".to_string(),
+            amount: Uint128::zero(),
+            period: Some(10),
+        },
+    ]).unwrap();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+    env.block.time = Timestamp::from_seconds(100_000);
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address).unwrap();
+
+    env.block.time = Timestamp::from_seconds(1_000_000);
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::SpendLimitExceeded { .. })));
+
+    // Removing the limits lets the instance spend again
+    set_spend_limits(&mut deps, env.clone(), &user_address, vec![]).unwrap();
+    execute_stake_action(&mut deps, env, &executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_only_requester_sets_spend_limits() {
+    let (mut deps, env, executor_address, user_address) = setup();
+    let limit = SpendLimit {
+        denom: "rune".to_string(),
+        amount: Uint128::from(1000u128),
+        period: Some(60),
+    };
+
+    let result = set_spend_limits(&mut deps, env.clone(), &executor_address, vec![limit.clone()]);
+    assert!(matches!(result, Err(ContractError::InstanceNotFound { .. })));
+
+    let result = set_spend_limits(&mut deps, env, &user_address, vec![SpendLimit { period: Some(0), ..limit }]);
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
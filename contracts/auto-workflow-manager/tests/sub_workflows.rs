@@ -0,0 +1,177 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::{ActionMsg, ActionParamValue, ExecuteMsg, ExecutionType, NewWorkflowMsg, SubWorkflow, Template, WorkflowVisibility},
+    query::{query_executable_actions, query_simulate_action},
+};
+use cosmwasm_std::{testing::message_info, Addr, CosmosMsg, Env, Timestamp, WasmMsg};
+use std::collections::{HashMap, HashSet};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn action(contract: &Addr, message: &str, params: &[(&str, &str)], next_actions: &[&str]) -> ActionMsg {
+    ActionMsg {
+        params: params.iter().map(|(key, value)| (key.to_string(), ActionParamValue::String(value.to_string()))).collect(),
+        next_actions: next_actions.iter().map(|action_id| action_id.to_string()).collect(),
+        templates: HashMap::from([(
+            "default".to_string(),
+            Template {
+                contract: contract.to_string(),
+                message: message.to_string(),
+                funds: vec![],
+            },
+        )]),
+        whitelisted_contracts: HashSet::from([contract.to_string()]),
+        allowed_messages: HashMap::new(),
+        outputs: HashMap::new(),
+        signed_params: HashSet::new(),
+        param_constraints: HashMap::new(),
+        sub_workflow: None,
+        min_return_protection: None,
+        timing: None,
+    }
+}
+
+fn sub_workflow_action(workflow_id: &str, params: &[(&str, &str)], next_actions: &[&str]) -> ActionMsg {
+    ActionMsg {
+        params: HashMap::new(),
+        next_actions: next_actions.iter().map(|action_id| action_id.to_string()).collect(),
+        templates: HashMap::new(),
+        whitelisted_contracts: HashSet::new(),
+        allowed_messages: HashMap::new(),
+        outputs: HashMap::new(),
+        signed_params: HashSet::new(),
+        param_constraints: HashMap::new(),
+        sub_workflow: Some(SubWorkflow {
+            workflow_id: workflow_id.to_string(),
+            params: params.iter().map(|(key, value)| (key.to_string(), ActionParamValue::String(value.to_string()))).collect(),
+        }),
+        min_return_protection: None,
+        timing: None,
+    }
+}
+
+fn workflow(id: &str, start_action: &str, end_action: &str, actions: Vec<(&str, ActionMsg)>) -> NewWorkflowMsg {
+    NewWorkflowMsg {
+        id: id.to_string(),
+        start_actions: HashSet::from([start_action.to_string()]),
+        end_actions: HashSet::from([end_action.to_string()]),
+        visibility: WorkflowVisibility::Public,
+        actions: actions.into_iter().map(|(action_id, action)| (action_id.to_string(), action)).collect(),
+        creator_fee_schedule: None,
+    }
+}
+
+// "compound" runs the claim -> restake workflow after "prepare" and ends the parent workflow
+fn setup(execution_type: ExecutionType) -> (TestDeps, Env, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let contract_address = api.addr_make("contract_to_call");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), workflow("claim-restake", "claim", "restake", vec![
+        ("claim", action(&contract_address, "{\"claim\":{}}", &[], &["restake"])),
+        ("restake", action(&contract_address, "{\"stake\":{\"amount\":\"{{amount}}\"}}", &[("amount", "#ip.restake_amount")], &[])),
+    ])).unwrap();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, workflow("parent", "prepare", "compound", vec![
+        ("prepare", action(&contract_address, "{\"prepare\":{}}", &[], &["compound"])),
+        ("compound", sub_workflow_action("claim-restake", &[("restake_amount", "#ip.amount")], &[])),
+    ])).unwrap();
+
+    let mut instance = create_oneshot_test_instance("parent".to_string());
+    instance.execution_type = execution_type;
+    instance.onchain_parameters = HashMap::from([("amount".to_string(), ActionParamValue::String("500".to_string()))]);
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    (deps, env, executor_address, user_address, contract_address)
+}
+
+fn execute_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr, action_id: &str) -> Result<cosmwasm_std::Response, ContractError> {
+    execute(deps.as_mut(), env, message_info(executor, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: action_id.to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    })
+}
+
+fn executable_action_ids(deps: &TestDeps, env: Env, user_address: &Addr) -> Vec<String> {
+    query_executable_actions(deps.as_ref(), env, user_address.to_string(), 1).unwrap()
+        .actions
+        .into_iter()
+        .map(|action| action.action_id)
+        .collect()
+}
+
+#[test]
+fn test_sub_workflow_runs_inside_parent() {
+    let (mut deps, env, executor_address, user_address, contract_address) = setup(ExecutionType::OneShot);
+
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "prepare").unwrap();
+    assert_eq!(executable_action_ids(&deps, env.clone(), &user_address), vec!["compound.claim"]);
+
+    // The sub-workflow action itself isn't executable, only its steps
+    let result = execute_action(&mut deps, env.clone(), &executor_address, &user_address, "compound");
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "compound.claim").unwrap();
+    let executable_actions = query_executable_actions(deps.as_ref(), env.clone(), user_address.to_string(), 1).unwrap().actions;
+    assert_eq!(executable_actions[0].action_id, "compound.restake");
+    assert!(executable_actions[0].templates.contains_key("default"));
+
+    // Params are mapped from the parent instance into the sub-workflow
+    let simulation = query_simulate_action(deps.as_ref(), env.clone(), user_address.to_string(), 1, "compound.restake".to_string(), "default".to_string(), None).unwrap();
+    assert_eq!(simulation.error, None);
+    match &simulation.messages[0] {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+            assert_eq!(contract_addr, &contract_address.to_string());
+            assert_eq!(msg.as_slice(), b"{\"stake\":{\"amount\":\"500\"}}");
+        }
+        _ => panic!("Expected WasmMsg::Execute"),
+    }
+
+    // Reaching the end of the sub-workflow completes the parent's end action
+    execute_action(&mut deps, env.clone(), &executor_address, &user_address, "compound.restake").unwrap();
+    assert!(executable_action_ids(&deps, env, &user_address).is_empty());
+}
+
+#[test]
+fn test_recurrent_instance_restarts_after_sub_workflow() {
+    let (mut deps, env, executor_address, user_address, _contract_address) = setup(ExecutionType::Recurrent);
+
+    for action_id in ["prepare", "compound.claim", "compound.restake"] {
+        execute_action(&mut deps, env.clone(), &executor_address, &user_address, action_id).unwrap();
+    }
+    assert_eq!(executable_action_ids(&deps, env.clone(), &user_address), vec!["prepare"]);
+    execute_action(&mut deps, env, &executor_address, &user_address, "prepare").unwrap();
+}
+
+#[test]
+fn test_publish_validates_sub_workflows() {
+    let (mut deps, env, _executor_address, _user_address, contract_address) = setup(ExecutionType::OneShot);
+    let publisher_address = deps.api.addr_make("publisher");
+
+    let result = publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), workflow("missing", "run", "run", vec![
+        ("run", sub_workflow_action("unknown-workflow", &[], &[])),
+    ]));
+    assert!(matches!(result, Err(ContractError::WorkflowNotFound { .. })));
+
+    let mut with_templates = sub_workflow_action("claim-restake", &[], &[]);
+    with_templates.templates = action(&contract_address, "{}", &[], &[]).templates;
+    let result = publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), workflow("templates", "run", "run", vec![
+        ("run", with_templates),
+    ]));
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+
+    // Sub-workflows are one level deep
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, workflow("nested", "run", "run", vec![
+        ("run", sub_workflow_action("parent", &[], &[])),
+    ]));
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
@@ -1,7 +1,8 @@
+use anybuf::{Anybuf, Bufany};
 use cosmwasm_std::{
-    testing::{message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage}, Addr, DepsMut, Empty, Env, OwnedDeps, Response, Timestamp
+    from_json, testing::{message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage}, Addr, ContractResult, DepsMut, Empty, Env, GrpcQuery, OwnedDeps, Querier, QuerierResult, QueryRequest, Response, SystemResult, Timestamp
 };
-use std::collections::{HashMap, HashSet};
+use std::{collections::{HashMap, HashSet}, marker::PhantomData};
 
 use auto_workflow_manager::{
     contract::{execute, instantiate},
@@ -22,6 +23,8 @@ pub fn instantiate_contract(
         allowed_action_executors,
         referral_memo: "test-referral-memo".to_string(),
         fee_manager_address: fee_manager_address,
+        max_consecutive_failures: None,
+        executor_bonding: None,
     };
     
     let instantiate_info = message_info(&admin, &[]);
@@ -72,6 +75,13 @@ pub fn create_test_workflow(api: MockApi) -> NewWorkflowMsg {
                     whitelisted_contracts: HashSet::from([
                         token_address.to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
             (
@@ -97,9 +107,17 @@ pub fn create_test_workflow(api: MockApi) -> NewWorkflowMsg {
                     whitelisted_contracts: HashSet::from([
                         staking_address.to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
         ]),
+        creator_fee_schedule: None,
     }
 }
 
@@ -136,8 +154,16 @@ pub fn create_simple_test_workflow(api: MockApi) -> NewWorkflowMsg {
                 whitelisted_contracts: HashSet::from([
                     contract_address.to_string(),
                 ]),
+                allowed_messages: HashMap::new(),
+                outputs: HashMap::new(),
+                signed_params: HashSet::new(),
+                param_constraints: HashMap::new(),
+                sub_workflow: None,
+                min_return_protection: None,
+                timing: None,
             },
         )]),
+        creator_fee_schedule: None,
     }
 }
 
@@ -188,9 +214,17 @@ pub fn create_template_test_workflow() -> NewWorkflowMsg {
                     whitelisted_contracts: HashSet::from([
                         "osmo1contract123456789abcdefghijklmnopqrstuvwxyz".to_string(),
                     ]),
+                    allowed_messages: HashMap::new(),
+                    outputs: HashMap::new(),
+                    signed_params: HashSet::new(),
+                    param_constraints: HashMap::new(),
+                    sub_workflow: None,
+                    min_return_protection: None,
+                    timing: None,
                 },
             ),
         ]),
+        creator_fee_schedule: None,
     }
 }
 
@@ -218,6 +252,10 @@ pub fn create_oneshot_test_instance(workflow_id: String) -> NewInstanceMsg {
         execution_type: ExecutionType::OneShot,
         expiration_time: Timestamp::from_seconds(1000000000), // Far future
         cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     }
 }
 
@@ -264,3 +302,40 @@ pub fn create_test_environment() -> (OwnedDeps<MockStorage, MockApi, MockQuerier
 
     (deps, env, api, admin_address.clone(), publisher_address.clone(), executor_address.clone())
 }
+
+/// Mock querier answering THORChain oracle price queries
+#[allow(dead_code)]
+pub struct OracleQuerier {
+    pub base: MockQuerier,
+    pub prices: HashMap<String, String>,
+}
+
+impl Querier for OracleQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        if let Ok(QueryRequest::Grpc(GrpcQuery { path, data })) = from_json::<QueryRequest<Empty>>(bin_request) {
+            if path == "/types.Query/OraclePrice" {
+                let symbol = Bufany::deserialize(&data).unwrap().string(2).unwrap();
+                let response = match self.prices.get(&symbol) {
+                    Some(price) => Anybuf::new().append_message(1, &Anybuf::new().append_string(1, &symbol).append_string(2, price)),
+                    None => Anybuf::new(),
+                };
+                return SystemResult::Ok(ContractResult::Ok(response.into_vec().into()));
+            }
+        }
+        self.base.raw_query(bin_request)
+    }
+}
+
+/// Answers the oracle price queries of the given deps with the given (symbol, price) pairs
+#[allow(dead_code)]
+pub fn with_oracle_prices(deps: OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, prices: &[(&str, &str)]) -> OwnedDeps<MockStorage, MockApi, OracleQuerier, Empty> {
+    OwnedDeps {
+        storage: deps.storage,
+        api: deps.api,
+        querier: OracleQuerier {
+            base: deps.querier,
+            prices: prices.iter().map(|(symbol, price)| (symbol.to_string(), price.to_string())).collect(),
+        },
+        custom_query_type: PhantomData,
+    }
+}
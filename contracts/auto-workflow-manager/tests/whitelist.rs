@@ -47,6 +47,13 @@ fn test_publish_workflow_with_whitelist() {
             next_actions: HashSet::new(),
             templates,
             whitelisted_contracts,
+            allowed_messages: HashMap::new(),
+            outputs: HashMap::new(),
+            signed_params: HashSet::new(),
+            param_constraints: HashMap::new(),
+            sub_workflow: None,
+            min_return_protection: None,
+            timing: None,
         },
     );
 
@@ -60,6 +67,7 @@ fn test_publish_workflow_with_whitelist() {
         ]),
         visibility: WorkflowVisibility::Public,
         actions,
+        creator_fee_schedule: None,
     };
 
     let response = publish_workflow(deps.as_mut(), env, publisher_address.clone(), workflow_msg).unwrap();
@@ -115,6 +123,13 @@ fn test_execute_action_with_whitelisted_contract() {
             next_actions: HashSet::new(),
             templates,
             whitelisted_contracts,
+            allowed_messages: HashMap::new(),
+            outputs: HashMap::new(),
+            signed_params: HashSet::new(),
+            param_constraints: HashMap::new(),
+            sub_workflow: None,
+            min_return_protection: None,
+            timing: None,
         },
     );
 
@@ -128,6 +143,7 @@ fn test_execute_action_with_whitelisted_contract() {
         ]),
         visibility: WorkflowVisibility::Public,
         actions,
+        creator_fee_schedule: None,
     };
 
     // Publish workflow
@@ -142,6 +158,10 @@ fn test_execute_action_with_whitelisted_contract() {
         execution_type: auto_workflow_manager::msg::ExecutionType::OneShot,
         expiration_time: Timestamp::from_seconds(env.block.time.seconds() + 3600),
         cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     };
 
     let response = auto_workflow_manager::execute::execute_instance(
@@ -175,6 +195,8 @@ fn test_execute_action_with_whitelisted_contract() {
         "stake_tokens".to_string(),
         "stake_template".to_string(),
         Some(action_params),
+        None,
+        None,
     );
 
     // Should succeed because the resolved contract is in the whitelist
@@ -218,6 +240,13 @@ fn test_execute_action_with_non_whitelisted_contract() {
             next_actions: HashSet::new(),
             templates,
             whitelisted_contracts,
+            allowed_messages: HashMap::new(),
+            outputs: HashMap::new(),
+            signed_params: HashSet::new(),
+            param_constraints: HashMap::new(),
+            sub_workflow: None,
+            min_return_protection: None,
+            timing: None,
         },
     );
 
@@ -231,6 +260,7 @@ fn test_execute_action_with_non_whitelisted_contract() {
         ]),
         visibility: WorkflowVisibility::Public,
         actions,
+        creator_fee_schedule: None,
     };
 
     // Publish workflow
@@ -245,6 +275,10 @@ fn test_execute_action_with_non_whitelisted_contract() {
         execution_type: auto_workflow_manager::msg::ExecutionType::OneShot,
         expiration_time: Timestamp::from_seconds(env.block.time.seconds() + 3600),
         cron_expression: None,
+        param_signer: None,
+        param_constraints: HashMap::new(),
+        price_trigger: None,
+        allow_bonded_executors: false,
     };
 
     let response = auto_workflow_manager::execute::execute_instance(
@@ -278,6 +312,8 @@ fn test_execute_action_with_non_whitelisted_contract() {
         "stake_tokens".to_string(),
         "stake_template".to_string(),
         Some(action_params),
+        None,
+        None,
     );
 
     // Should fail because the resolved contract is not in the whitelist
@@ -290,4 +326,84 @@ fn test_execute_action_with_non_whitelisted_contract() {
         }
         _ => panic!("Expected ContractNotWhitelisted error, got different error"),
     }
-} 
\ No newline at end of file
+} 
+fn publish_simple_workflow_with_allowed_messages(
+    deps: cosmwasm_std::DepsMut,
+    env: cosmwasm_std::Env,
+    api: cosmwasm_std::testing::MockApi,
+    publisher_address: Addr,
+    allowed_messages: HashSet<String>,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let mut workflow_msg = utils::create_simple_test_workflow(api);
+    let contract_address = api.addr_make("contract_to_call");
+    workflow_msg.actions.get_mut("stake_tokens").unwrap().allowed_messages =
+        HashMap::from([(contract_address.to_string(), allowed_messages)]);
+    publish_workflow(deps, env, publisher_address, workflow_msg)
+}
+
+fn execute_simple_stake(
+    deps: cosmwasm_std::DepsMut,
+    env: cosmwasm_std::Env,
+    executor_address: &Addr,
+    user_address: &Addr,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    auto_workflow_manager::execute::execute_action(
+        deps,
+        env,
+        MessageInfo {
+            sender: executor_address.clone(),
+            funds: [].to_vec(),
+        },
+        user_address.to_string(),
+        1,
+        "stake_tokens".to_string(),
+        "default".to_string(),
+        None,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn test_execute_action_with_allowed_message() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_simple_workflow_with_allowed_messages(deps.as_mut(), env.clone(), api, publisher_address, HashSet::from(["stake".to_string()])).unwrap();
+    utils::execute_instance(&mut deps, env.clone(), user_address.clone(), utils::create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    let result = execute_simple_stake(deps.as_mut(), env, &executor_address, &user_address);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_execute_action_with_not_allowed_message() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_simple_workflow_with_allowed_messages(deps.as_mut(), env.clone(), api, publisher_address, HashSet::from(["swap".to_string()])).unwrap();
+    utils::execute_instance(&mut deps, env.clone(), user_address.clone(), utils::create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    let result = execute_simple_stake(deps.as_mut(), env, &executor_address, &user_address);
+    match result {
+        Err(ContractError::MessageNotAllowed { message, contract }) => {
+            assert_eq!(message, "stake");
+            assert_eq!(contract, api.addr_make("contract_to_call").to_string());
+        }
+        _ => panic!("Expected MessageNotAllowed error"),
+    }
+}
+
+#[test]
+fn test_publish_workflow_with_allowed_messages_for_non_whitelisted_contract() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+
+    let mut workflow_msg = utils::create_simple_test_workflow(api);
+    workflow_msg.actions.get_mut("stake_tokens").unwrap().allowed_messages =
+        HashMap::from([(api.addr_make("other_contract").to_string(), HashSet::from(["stake".to_string()]))]);
+
+    let result = publish_workflow(deps.as_mut(), env, publisher_address, workflow_msg);
+    assert!(matches!(result, Err(ContractError::ContractNotWhitelisted { .. })));
+}
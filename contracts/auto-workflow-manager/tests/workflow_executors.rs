@@ -0,0 +1,86 @@
+use auto_workflow_manager::{
+    contract::execute,
+    error::ContractError,
+    msg::ExecuteMsg,
+    query::query_workflow_by_id,
+};
+use cosmwasm_std::{Addr, Env, Timestamp};
+use std::collections::HashSet;
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn set_workflow_executors(deps: &mut TestDeps, env: Env, sender: &Addr, executors: Option<HashSet<Addr>>) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::SetWorkflowExecutors {
+        workflow_id: "simple-test-workflow".to_string(),
+        executors,
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(sender, &[]), execute_msg)
+}
+
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr) -> Result<cosmwasm_std::Response, ContractError> {
+    let execute_msg = ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id: 1,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    };
+    execute(deps.as_mut(), env, cosmwasm_std::testing::message_info(executor, &[]), execute_msg)
+}
+
+#[test]
+fn test_dedicated_executors_replace_global_executors() {
+    let (mut deps, mut env, api, _admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    let dedicated_executor_address = api.addr_make("dedicated_executor");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+    set_workflow_executors(&mut deps, env.clone(), &publisher_address, Some(HashSet::from([dedicated_executor_address.clone()]))).unwrap();
+
+    let workflow = query_workflow_by_id(deps.as_ref(), "simple-test-workflow".to_string()).unwrap().workflow;
+    assert_eq!(workflow.executors, Some(HashSet::from([dedicated_executor_address.clone()])));
+
+    let result = execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address);
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    execute_stake_action(&mut deps, env, &dedicated_executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_removing_dedicated_executors_falls_back_to_global() {
+    let (mut deps, mut env, api, admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("simple-test-workflow".to_string())).unwrap();
+
+    // The owner can also assign executors
+    set_workflow_executors(&mut deps, env.clone(), &admin_address, Some(HashSet::from([api.addr_make("dedicated_executor")]))).unwrap();
+    set_workflow_executors(&mut deps, env.clone(), &admin_address, None).unwrap();
+
+    let workflow = query_workflow_by_id(deps.as_ref(), "simple-test-workflow".to_string()).unwrap().workflow;
+    assert_eq!(workflow.executors, None);
+
+    execute_stake_action(&mut deps, env, &executor_address, &user_address).unwrap();
+}
+
+#[test]
+fn test_set_workflow_executors_unauthorized() {
+    let (mut deps, env, api, _admin_address, publisher_address, _executor_address) = create_test_environment();
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, create_simple_test_workflow(api)).unwrap();
+
+    let result = set_workflow_executors(&mut deps, env.clone(), &api.addr_make("user"), Some(HashSet::from([api.addr_make("user")])));
+    assert!(matches!(result, Err(ContractError::Unauthorized {})));
+
+    let result = set_workflow_executors(&mut deps, env, &api.addr_make("publisher"), Some(HashSet::new()));
+    assert!(matches!(result, Err(ContractError::GenericError(_))));
+}
@@ -0,0 +1,120 @@
+use auto_workflow_manager::{
+    contract::{execute, reply},
+    execute::ACTION_REPLY_ID_START,
+    msg::{ExecuteMsg, ExecutionType, FinishInstanceRequest},
+    query::{query_workflow_leaderboard, query_workflow_stats},
+};
+use cosmwasm_std::{testing::message_info, Addr, Binary, Env, Reply, SubMsgResponse, SubMsgResult, Timestamp};
+
+mod utils;
+use utils::{create_oneshot_test_instance, create_simple_test_workflow, create_test_environment, execute_instance, publish_workflow};
+
+type TestDeps = cosmwasm_std::OwnedDeps<cosmwasm_std::testing::MockStorage, cosmwasm_std::testing::MockApi, cosmwasm_std::testing::MockQuerier, cosmwasm_std::Empty>;
+
+fn setup() -> (TestDeps, Env, Addr, Addr, Addr, Addr) {
+    let (mut deps, mut env, api, admin_address, publisher_address, executor_address) = create_test_environment();
+    let user_address = api.addr_make("user");
+    env.block.time = Timestamp::from_seconds(100);
+
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address.clone(), create_simple_test_workflow(api)).unwrap();
+
+    (deps, env, admin_address, publisher_address, executor_address, user_address)
+}
+
+#[allow(deprecated)]
+fn ok_result() -> SubMsgResult {
+    SubMsgResult::Ok(SubMsgResponse { events: vec![], msg_responses: vec![], data: None })
+}
+
+#[allow(deprecated)]
+fn execute_stake_action(deps: &mut TestDeps, env: Env, executor: &Addr, user_address: &Addr, instance_id: u64, result: SubMsgResult) {
+    execute(deps.as_mut(), env.clone(), message_info(executor, &[]), ExecuteMsg::ExecuteAction {
+        user_address: user_address.to_string(),
+        instance_id,
+        action_id: "stake_tokens".to_string(),
+        template_id: "default".to_string(),
+        params: None,
+        params_signature: None,
+        execution_id: None,
+    }).unwrap();
+    reply(deps.as_mut(), env, Reply { id: ACTION_REPLY_ID_START, result, gas_used: 0, payload: Binary::default() }).unwrap();
+}
+
+#[test]
+fn test_stats_follow_instance_states() {
+    let (mut deps, env, admin_address, _publisher_address, executor_address, user_address) = setup();
+
+    for execution_type in [ExecutionType::OneShot, ExecutionType::OneShot, ExecutionType::Recurrent] {
+        let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+        instance.execution_type = execution_type;
+        execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+    }
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, 1, ok_result());
+    execute(deps.as_mut(), env.clone(), message_info(&user_address, &[]), ExecuteMsg::PauseSchedule { instance_id: 3 }).unwrap();
+    execute(deps.as_mut(), env.clone(), message_info(&user_address, &[]), ExecuteMsg::CancelInstance { instance_id: 2 }).unwrap();
+    execute(deps.as_mut(), env.clone(), message_info(&admin_address, &[]), ExecuteMsg::FinishInstances {
+        instances: vec![FinishInstanceRequest { requester: user_address.to_string(), instance_ids: vec![1] }],
+    }).unwrap();
+
+    let stats = query_workflow_stats(deps.as_ref(), "simple-test-workflow".to_string()).unwrap().stats;
+    assert_eq!(stats.instances_created, 3);
+    assert_eq!(stats.instances_active, 1);
+    assert_eq!(stats.instances_finished, 1);
+    assert_eq!(stats.instances_cancelled, 1);
+    assert_eq!(stats.actions_executed, 1);
+    assert!(stats.creator_fees.is_empty());
+
+    // Finishing a cancelled instance moves it between buckets
+    execute(deps.as_mut(), env, message_info(&admin_address, &[]), ExecuteMsg::FinishInstances {
+        instances: vec![FinishInstanceRequest { requester: user_address.to_string(), instance_ids: vec![2] }],
+    }).unwrap();
+
+    let stats = query_workflow_stats(deps.as_ref(), "simple-test-workflow".to_string()).unwrap().stats;
+    assert_eq!(stats.instances_created, 3);
+    assert_eq!(stats.instances_finished, 2);
+    assert_eq!(stats.instances_cancelled, 0);
+}
+
+#[test]
+fn test_failed_actions_are_not_counted() {
+    let (mut deps, env, _admin_address, _publisher_address, executor_address, user_address) = setup();
+
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, 1, SubMsgResult::Err("out of funds".to_string()));
+    let stats = query_workflow_stats(deps.as_ref(), "simple-test-workflow".to_string()).unwrap().stats;
+    assert_eq!(stats.actions_executed, 0);
+
+    execute_stake_action(&mut deps, env, &executor_address, &user_address, 1, ok_result());
+    let stats = query_workflow_stats(deps.as_ref(), "simple-test-workflow".to_string()).unwrap().stats;
+    assert_eq!(stats.actions_executed, 1);
+}
+
+#[test]
+fn test_leaderboard_orders_by_actions_executed() {
+    let (mut deps, env, _admin_address, publisher_address, executor_address, user_address) = setup();
+    let api = deps.api;
+
+    let mut workflow = create_simple_test_workflow(api);
+    workflow.id = "other-workflow".to_string();
+    publish_workflow(deps.as_mut(), env.clone(), publisher_address, workflow).unwrap();
+
+    let mut instance = create_oneshot_test_instance("simple-test-workflow".to_string());
+    instance.execution_type = ExecutionType::Recurrent;
+    execute_instance(&mut deps, env.clone(), user_address.clone(), instance).unwrap();
+    execute_instance(&mut deps, env.clone(), user_address.clone(), create_oneshot_test_instance("other-workflow".to_string())).unwrap();
+
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, 1, ok_result());
+    execute_stake_action(&mut deps, env.clone(), &executor_address, &user_address, 1, ok_result());
+    execute_stake_action(&mut deps, env, &executor_address, &user_address, 2, ok_result());
+
+    let leaderboard = query_workflow_leaderboard(deps.as_ref(), None, None).unwrap().workflows;
+    assert_eq!(leaderboard.iter().map(|entry| entry.workflow_id.as_str()).collect::<Vec<_>>(), vec!["simple-test-workflow", "other-workflow"]);
+    assert_eq!(leaderboard[0].stats.actions_executed, 2);
+
+    let page = query_workflow_leaderboard(deps.as_ref(), Some("simple-test-workflow".to_string()), Some(1)).unwrap().workflows;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].workflow_id, "other-workflow");
+}
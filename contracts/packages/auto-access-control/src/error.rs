@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AccessControlError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Unknown role {role}")]
+    UnknownRole { role: String },
+
+    #[error("{address} already has role {role}")]
+    RoleAlreadyGranted { role: String, address: String },
+
+    #[error("{address} doesn't have role {role}")]
+    RoleNotGranted { role: String, address: String },
+
+    #[error("No ownership transfer is pending")]
+    NoPendingOwner {},
+}
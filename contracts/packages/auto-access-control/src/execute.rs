@@ -0,0 +1,82 @@
+use cosmwasm_std::{DepsMut, Event, MessageInfo, Response};
+
+use crate::{
+    msg::AccessControlMsg,
+    state::{assert_owner, grant_role, has_role, load_ownership, revoke_role, save_ownership, Ownership},
+    AccessControlError,
+};
+
+/// Handles an access control message for a contract that supports `roles`.
+/// Events are named `<event_prefix>/<action>`, e.g. `autorujira-fee-manager/grant_role`.
+pub fn execute_access_control(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: AccessControlMsg,
+    roles: &[&str],
+    event_prefix: &str,
+) -> Result<Response, AccessControlError> {
+    let event = match msg {
+        AccessControlMsg::TransferOwnership { new_owner } => {
+            assert_owner(deps.storage, &info.sender)?;
+            let new_owner = deps.api.addr_validate(&new_owner)?;
+            let ownership = load_ownership(deps.storage)?;
+            save_ownership(deps.storage, &Ownership { pending_owner: Some(new_owner.clone()), ..ownership })?;
+            Event::new(format!("{}/transfer_ownership", event_prefix))
+                .add_attribute("owner", info.sender.to_string())
+                .add_attribute("pending_owner", new_owner.to_string())
+        }
+        AccessControlMsg::AcceptOwnership {} => {
+            let ownership = load_ownership(deps.storage)?;
+            match &ownership.pending_owner {
+                Some(pending_owner) if *pending_owner == info.sender => {}
+                Some(_) => return Err(AccessControlError::Unauthorized {}),
+                None => return Err(AccessControlError::NoPendingOwner {}),
+            }
+            save_ownership(deps.storage, &Ownership { owner: info.sender.clone(), pending_owner: None })?;
+            Event::new(format!("{}/accept_ownership", event_prefix))
+                .add_attribute("previous_owner", ownership.owner.to_string())
+                .add_attribute("owner", info.sender.to_string())
+        }
+        AccessControlMsg::CancelOwnershipTransfer {} => {
+            assert_owner(deps.storage, &info.sender)?;
+            let ownership = load_ownership(deps.storage)?;
+            let pending_owner = ownership.pending_owner.ok_or(AccessControlError::NoPendingOwner {})?;
+            save_ownership(deps.storage, &Ownership { owner: ownership.owner, pending_owner: None })?;
+            Event::new(format!("{}/cancel_ownership_transfer", event_prefix))
+                .add_attribute("owner", info.sender.to_string())
+                .add_attribute("pending_owner", pending_owner.to_string())
+        }
+        AccessControlMsg::GrantRole { role, address } => {
+            assert_owner(deps.storage, &info.sender)?;
+            validate_role(roles, &role)?;
+            let address = deps.api.addr_validate(&address)?;
+            if has_role(deps.storage, &role, &address) {
+                return Err(AccessControlError::RoleAlreadyGranted { role, address: address.to_string() });
+            }
+            grant_role(deps.storage, &role, &address)?;
+            Event::new(format!("{}/grant_role", event_prefix))
+                .add_attribute("role", role)
+                .add_attribute("address", address.to_string())
+        }
+        AccessControlMsg::RevokeRole { role, address } => {
+            assert_owner(deps.storage, &info.sender)?;
+            validate_role(roles, &role)?;
+            let address = deps.api.addr_validate(&address)?;
+            if !has_role(deps.storage, &role, &address) {
+                return Err(AccessControlError::RoleNotGranted { role, address: address.to_string() });
+            }
+            revoke_role(deps.storage, &role, &address);
+            Event::new(format!("{}/revoke_role", event_prefix))
+                .add_attribute("role", role)
+                .add_attribute("address", address.to_string())
+        }
+    };
+    Ok(Response::new().add_event(event))
+}
+
+fn validate_role(roles: &[&str], role: &str) -> Result<(), AccessControlError> {
+    if !roles.contains(&role) {
+        return Err(AccessControlError::UnknownRole { role: role.to_string() });
+    }
+    Ok(())
+}
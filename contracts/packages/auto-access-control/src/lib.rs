@@ -0,0 +1,7 @@
+pub mod error;
+pub mod execute;
+pub mod msg;
+pub mod query;
+pub mod state;
+
+pub use crate::error::AccessControlError;
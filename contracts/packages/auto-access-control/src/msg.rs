@@ -0,0 +1,39 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+#[cw_serde]
+pub enum AccessControlMsg {
+    // Proposes a new owner, the transfer completes when it accepts
+    TransferOwnership { new_owner: String },
+    AcceptOwnership {},
+    CancelOwnershipTransfer {},
+    GrantRole { role: String, address: String },
+    RevokeRole { role: String, address: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum AccessControlQuery {
+    #[returns(OwnershipResponse)]
+    Ownership {},
+    #[returns(bool)]
+    HasRole { role: String, address: String },
+    #[returns(RoleMembersResponse)]
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct RoleMembersResponse {
+    pub role: String,
+    pub members: Vec<Addr>,
+}
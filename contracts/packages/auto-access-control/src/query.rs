@@ -0,0 +1,31 @@
+use cosmwasm_std::{to_json_binary, Binary, Deps, StdResult};
+
+use crate::{
+    msg::{AccessControlQuery, OwnershipResponse, RoleMembersResponse},
+    state::{has_role, load_ownership, load_role_members},
+};
+
+const DEFAULT_LIMIT: u32 = 30;
+const MAX_LIMIT: u32 = 100;
+
+pub fn query_access_control(deps: Deps, msg: AccessControlQuery) -> StdResult<Binary> {
+    match msg {
+        AccessControlQuery::Ownership {} => {
+            let ownership = load_ownership(deps.storage)?;
+            to_json_binary(&OwnershipResponse {
+                owner: ownership.owner,
+                pending_owner: ownership.pending_owner,
+            })
+        }
+        AccessControlQuery::HasRole { role, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&has_role(deps.storage, &role, &address))
+        }
+        AccessControlQuery::RoleMembers { role, start_after, limit } => {
+            let start_after = start_after.map(|address| deps.api.addr_validate(&address)).transpose()?;
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let members = load_role_members(deps.storage, &role, start_after.as_ref(), Some(limit))?;
+            to_json_binary(&RoleMembersResponse { role, members })
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::AccessControlError;
+
+#[cw_serde]
+pub struct Ownership {
+    pub owner: Addr,
+    pub pending_owner: Option<Addr>,
+}
+
+pub const OWNERSHIP: Item<Ownership> = Item::new("acl_ownership");
+// (role, address) -> ()
+pub const ROLE_MEMBERS: Map<(&str, &Addr), ()> = Map::new("acl_roles");
+
+pub fn save_owner(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
+    // Setting the owner directly drops any pending transfer
+    OWNERSHIP.save(storage, &Ownership { owner: owner.clone(), pending_owner: None })
+}
+
+pub fn load_ownership(storage: &dyn Storage) -> StdResult<Ownership> {
+    OWNERSHIP.load(storage)
+}
+
+pub fn may_load_ownership(storage: &dyn Storage) -> StdResult<Option<Ownership>> {
+    OWNERSHIP.may_load(storage)
+}
+
+pub fn save_ownership(storage: &mut dyn Storage, ownership: &Ownership) -> StdResult<()> {
+    OWNERSHIP.save(storage, ownership)
+}
+
+pub fn is_owner(storage: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    Ok(may_load_ownership(storage)?.is_some_and(|ownership| ownership.owner == *address))
+}
+
+pub fn assert_owner(storage: &dyn Storage, address: &Addr) -> Result<(), AccessControlError> {
+    if !is_owner(storage, address)? {
+        return Err(AccessControlError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn has_role(storage: &dyn Storage, role: &str, address: &Addr) -> bool {
+    ROLE_MEMBERS.has(storage, (role, address))
+}
+
+pub fn grant_role(storage: &mut dyn Storage, role: &str, address: &Addr) -> StdResult<()> {
+    ROLE_MEMBERS.save(storage, (role, address), &())
+}
+
+pub fn revoke_role(storage: &mut dyn Storage, role: &str, address: &Addr) {
+    ROLE_MEMBERS.remove(storage, (role, address));
+}
+
+pub fn load_role_members(storage: &dyn Storage, role: &str, start_after: Option<&Addr>, limit: Option<usize>) -> StdResult<Vec<Addr>> {
+    let members = ROLE_MEMBERS
+        .prefix(role)
+        .keys(storage, start_after.map(Bound::exclusive), None, Order::Ascending);
+    match limit {
+        Some(limit) => members.take(limit).collect(),
+        None => members.collect(),
+    }
+}
+
+// Replaces every member of the role, used by the sudo messages that set a whole list
+pub fn save_role_members(storage: &mut dyn Storage, role: &str, members: &HashSet<Addr>) -> StdResult<()> {
+    for member in load_role_members(storage, role, None, None)? {
+        revoke_role(storage, role, &member);
+    }
+    for member in members {
+        grant_role(storage, role, member)?;
+    }
+    Ok(())
+}